@@ -1,3 +1,6 @@
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
 use core::arch::x86_64::*;
 use std::convert::TryFrom;
 
@@ -68,12 +71,7 @@ impl Domain for DomainGF2 {
         from: &[[Self::Batch; PLAYERS]; PACKED],
     ) {
         debug_assert_eq!(batch::BYTES * 8, BATCH_SIZE);
-
-        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-        return unsafe { batches_to_shares_x86(to, from) };
-
-        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
-        unimplemented!()
+        batches_to_shares_bytes(to, from)
     }
 
     const ONE: Self::ConstType = true;
@@ -81,8 +79,7 @@ impl Domain for DomainGF2 {
 }
 
 #[inline(always)]
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-unsafe fn batches_to_shares_x86(
+fn batches_to_shares_bytes(
     to: &mut [share::ShareGF2; BATCH_SIZE],
     from: &[[batch::BatchGF2; PLAYERS]; PACKED],
 ) {
@@ -92,7 +89,7 @@ unsafe fn batches_to_shares_x86(
             <&mut [share::ShareGF2; 8]>::try_from(&mut to[i * 8..(i + 1) * 8]).unwrap();
 
         // transpose the next byte from each batch
-        byte_to_shares_x86(
+        byte_to_shares(
             arr,
             [
                 // 1st instance
@@ -172,17 +169,28 @@ unsafe fn batches_to_shares_x86(
     }
 }
 
+// transposes one byte of every batch: bit 7 - j of src[63 - p] is bit p of share j
 #[inline(always)]
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-pub(crate) fn byte_to_shares_x86(dst: &mut [share::ShareGF2; 8], src: [u8; PACKED * PLAYERS]) {
+pub(crate) fn byte_to_shares(dst: &mut [share::ShareGF2; 8], src: [u8; PACKED * PLAYERS]) {
     #[cfg(target_feature = "avx2")]
     return unsafe { byte_to_shares_avx2(dst, src) };
 
     #[cfg(all(target_feature = "sse2", not(target_feature = "avx2")))]
     return unsafe { byte_to_shares_sse2(dst, src) };
 
+    // other targets (e.g. ARM, wasm32)
     #[cfg(not(any(target_feature = "sse2", target_feature = "avx2")))]
-    unimplemented!()
+    byte_to_shares_portable(dst, src)
+}
+
+#[inline(always)]
+#[cfg(any(test, not(any(target_feature = "sse2", target_feature = "avx2"))))]
+fn byte_to_shares_portable(dst: &mut [share::ShareGF2; 8], src: [u8; PACKED * PLAYERS]) {
+    for (j, share) in dst.iter_mut().enumerate() {
+        share.pack = src.iter().rev().enumerate().fold(0, |pack, (p, byte)| {
+            pack | ((((byte >> (7 - j)) & 1) as u64) << p)
+        });
+    }
 }
 
 #[inline(always)]
@@ -376,3 +384,26 @@ unsafe fn byte_to_shares_avx2(dst: &mut [share::ShareGF2; 8], src: [u8; PACKED *
         share.pack = (top << 32) | bot;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use rand::RngCore;
+
+    use super::*;
+
+    #[test]
+    fn test_byte_to_shares_portable() {
+        for _ in 0..100 {
+            let mut src = [0u8; PACKED * PLAYERS];
+            OsRng.fill_bytes(&mut src);
+            let mut expected: [share::ShareGF2; 8] = [Default::default(); 8];
+            let mut actual: [share::ShareGF2; 8] = [Default::default(); 8];
+            byte_to_shares(&mut expected, src);
+            byte_to_shares_portable(&mut actual, src);
+            for (e, a) in expected.iter().zip(actual.iter()) {
+                assert_eq!(e.pack, a.pack, "src = {:?}", src);
+            }
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use std::convert::{From, TryFrom};
 use std::fmt;
+use std::io;
 use std::ops::{Add, Mul, Sub};
 
 use num_traits::Zero;
 use serde;
 
-use crate::algebra::{EqIndex, Hashable, Pack, Recon};
+use crate::algebra::{Deserialize, EqIndex, Hashable, Pack, Recon, Serialize};
 use crate::crypto::hash::PackedHasher;
 use crate::PACKED;
 
@@ -320,6 +321,23 @@ impl Hashable for ReconGF2 {
     }
 }
 
+impl Serialize for ReconGF2 {
+    #[inline(always)]
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&self.pack.to_le_bytes())
+    }
+}
+
+impl Deserialize for ReconGF2 {
+    #[inline(always)]
+    fn deserialize<R: io::Read>(&mut self, reader: &mut R) -> io::Result<()> {
+        let mut buf = [0u8; 8];
+        reader.read_exact(&mut buf)?;
+        self.pack = u64::from_le_bytes(buf);
+        Ok(())
+    }
+}
+
 impl Zero for ReconGF2 {
     fn zero() -> Self {
         ReconGF2 { pack: 0 }
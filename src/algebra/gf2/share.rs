@@ -4,7 +4,7 @@ use std::ops::{Add, Sub};
 
 use num_traits::Zero;
 
-use super::domain::byte_to_shares_x86;
+use super::domain::byte_to_shares;
 use crate::algebra::{EqIndex, Hashable, PackSelected, Share};
 use crate::crypto::hash::PackedHasher;
 use crate::{PACKED, PLAYERS};
@@ -200,7 +200,7 @@ impl PackSelected for ShareGF2 {
             }
 
             // transpose
-            byte_to_shares_x86(&mut tmp_dst, tmp_src);
+            byte_to_shares(&mut tmp_dst, tmp_src);
 
             // copy partial shares to destination
             dst.extend_from_slice(&tmp_dst);
@@ -55,6 +55,8 @@ pub trait Recon:
     + PartialEq
     + Eq
     + EqIndex
+    + Serialize
+    + Deserialize
 {
 }
 
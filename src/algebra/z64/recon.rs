@@ -192,6 +192,18 @@ impl Serialize for ReconZ64 {
     }
 }
 
+impl Deserialize for ReconZ64 {
+    #[inline(always)]
+    fn deserialize<R: io::Read>(&mut self, reader: &mut R) -> io::Result<()> {
+        let mut buf = [0u8; 8];
+        for i in 0..PACKED {
+            reader.read_exact(&mut buf)?;
+            self.pack[i] = u64::from_le_bytes(buf);
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
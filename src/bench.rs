@@ -0,0 +1,127 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use reverie::proof::Proof;
+use reverie::{capabilities, largest_wires, CombineOperation, Operation};
+
+/// Shape of a synthetic program: `inputs` witness values followed by `gates` random
+/// additions/multiplications, each reading two of the previous `window` wires
+/// (a small window gives deep programs, a large window wide ones).
+#[derive(Debug, Clone, Copy)]
+pub struct Shape {
+    pub inputs: usize,
+    pub gates: usize,
+    pub mul_ratio: f64,
+    pub window: usize,
+}
+
+/// A random program of the given shape (in the gf2 or z64 domain) and a witness for it.
+pub fn synthetic(
+    rng: &mut impl Rng,
+    shape: &Shape,
+    z64: bool,
+) -> (Vec<CombineOperation>, Vec<bool>, Vec<u64>) {
+    fn op(z64: bool, op: Operation<u64>) -> CombineOperation {
+        if z64 {
+            return CombineOperation::Z64(op);
+        }
+        CombineOperation::GF2(match op {
+            Operation::Input(dst) => Operation::Input(dst),
+            Operation::Add(dst, a, b) => Operation::Add(dst, a, b),
+            Operation::Mul(dst, a, b) => Operation::Mul(dst, a, b),
+            _ => unreachable!(),
+        })
+    }
+
+    let inputs = shape.inputs.max(1);
+    let mut program = Vec::with_capacity(inputs + shape.gates);
+    let (mut wit_gf2, mut wit_z64) = (vec![], vec![]);
+    for dst in 0..inputs {
+        program.push(op(z64, Operation::Input(dst)));
+        if z64 {
+            wit_z64.push(rng.gen());
+        } else {
+            wit_gf2.push(rng.gen());
+        }
+    }
+    for dst in inputs..inputs + shape.gates {
+        let lo = dst.saturating_sub(shape.window.max(1));
+        let (a, b) = (rng.gen_range(lo..dst), rng.gen_range(lo..dst));
+        program.push(op(
+            z64,
+            if rng.gen_bool(shape.mul_ratio) {
+                Operation::Mul(dst, a, b)
+            } else {
+                Operation::Add(dst, a, b)
+            },
+        ));
+    }
+    (program, wit_gf2, wit_z64)
+}
+
+/// Proves and verifies a synthetic program, reporting the times and proof size.
+pub fn bench(shape: &Shape, z64: bool, seed: u64) -> Result<(), String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (program, wit_gf2, wit_z64) = synthetic(&mut rng, shape, z64);
+    let wire_counts = largest_wires(&program);
+    let program = Arc::new(program);
+
+    println!(
+        "{:<12} {:>12} {:>8} {:>14} {:>14} {:>14}",
+        "preset", "gates", "domain", "prove (ms)", "verify (ms)", "proof bytes"
+    );
+    // the parameters are compiled in: only the compiled presets can prove
+    for preset in capabilities().compiled_presets() {
+        let start = Instant::now();
+        let proof = Proof::new(
+            program.clone(),
+            Arc::new(wit_gf2.clone()),
+            Arc::new(wit_z64.clone()),
+            wire_counts,
+        );
+        let prove_time = start.elapsed();
+
+        let start = Instant::now();
+        if let Err(e) = proof.verify(program.clone(), wire_counts) {
+            return Err(format!("Unverifiable Proof: {}", e));
+        }
+        let verify_time = start.elapsed();
+
+        println!(
+            "{:<12} {:>12} {:>8} {:>14.3} {:>14.3} {:>14}",
+            preset.name,
+            program.len(),
+            if z64 { "z64" } else { "gf2" },
+            prove_time.as_secs_f64() * 1000.0,
+            verify_time.as_secs_f64() * 1000.0,
+            proof.serialized_size()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synthetic() {
+        let shape = Shape {
+            inputs: 4,
+            gates: 100,
+            mul_ratio: 0.5,
+            window: 8,
+        };
+        let (program, wit_gf2, wit_z64) = synthetic(&mut StdRng::seed_from_u64(0), &shape, false);
+        assert_eq!(program.len(), 104);
+        assert_eq!((wit_gf2.len(), wit_z64.len()), (4, 0));
+        // one gf2 wire per instruction (mcircuit counts a z64 wire even without z64 gates)
+        assert_eq!(largest_wires(&program).1, 104);
+
+        let (program, _, wit_z64) = synthetic(&mut StdRng::seed_from_u64(0), &shape, true);
+        assert!(matches!(program[50], CombineOperation::Z64(_)));
+        assert_eq!(wit_z64.len(), 4);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::proof::Parameters;
+use crate::PACKED;
+
+/// Witness formats of the command line (`--witness-format`).
+pub const WITNESS_FORMATS: [&str; 3] = ["bits", "hex", "packed"];
+
+/// A named parameter set, see `Parameters::PRESETS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresetInfo {
+    pub name: &'static str,
+    pub players: usize,
+    pub total_reps: usize,
+    pub online_reps: usize,
+    pub packed: usize,
+    /// The prover/verifier core is compiled for this set: only such presets can be used
+    /// for proving/verifying, the others can be estimated (see `passes::estimate`).
+    pub compiled: bool,
+}
+
+/// Describes what the linked crate supports (see [`capabilities`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Crate version.
+    pub version: &'static str,
+
+    /// Domains supported by the instruction set.
+    pub domains: Vec<&'static str>,
+
+    /// SIMD extensions detected on the host which the implementation can make use of.
+    pub simd: Vec<&'static str>,
+
+    /// The named parameter sets (`Parameters::PRESETS`).
+    pub presets: Vec<PresetInfo>,
+
+    /// Program (circuit) formats understood by the library.
+    pub program_formats: Vec<&'static str>,
+
+    /// Witness formats understood by the command line.
+    pub witness_formats: Vec<&'static str>,
+}
+
+impl Capabilities {
+    pub fn supports_domain(&self, domain: &str) -> bool {
+        self.domains.contains(&domain)
+    }
+
+    pub fn preset(&self, name: &str) -> Option<&PresetInfo> {
+        self.presets.iter().find(|p| p.name == name)
+    }
+
+    /// The presets which can be used for proving/verifying.
+    pub fn compiled_presets(&self) -> impl Iterator<Item = &PresetInfo> {
+        self.presets.iter().filter(|p| p.compiled)
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn detect_simd() -> Vec<&'static str> {
+    let mut simd = vec![];
+    if is_x86_feature_detected!("sse2") {
+        simd.push("sse2");
+    }
+    if is_x86_feature_detected!("ssse3") {
+        simd.push("ssse3");
+    }
+    if is_x86_feature_detected!("avx2") {
+        simd.push("avx2");
+    }
+    if is_x86_feature_detected!("aes") {
+        simd.push("aes");
+    }
+    simd
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn detect_simd() -> Vec<&'static str> {
+    vec![]
+}
+
+/// Returns the domains, host SIMD support, parameter presets and formats
+/// supported by this build of the crate.
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        version: env!("CARGO_PKG_VERSION"),
+        domains: vec!["gf2", "z64"],
+        simd: detect_simd(),
+        presets: Parameters::PRESETS
+            .iter()
+            .map(|preset| PresetInfo {
+                name: preset.name,
+                players: preset.params.players,
+                total_reps: preset.params.total_reps,
+                online_reps: preset.params.online_reps,
+                packed: PACKED,
+                compiled: !preset.estimate_only,
+            })
+            .collect(),
+        program_formats: vec![
+            "bincode",
+            "bristol",
+            "sieve-ir-text",
+            "blif",
+            "aiger",
+            "json",
+            "compact",
+        ],
+        witness_formats: WITNESS_FORMATS.to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ONLINE_REPS, TOTAL_REPS};
+
+    #[test]
+    fn test_capabilities() {
+        let caps = capabilities();
+        assert!(caps.supports_domain("gf2"));
+        assert!(caps.supports_domain("z64"));
+        assert!(!caps.supports_domain("gf2p8"));
+        let preset = caps.preset("default").unwrap();
+        assert_eq!(preset.total_reps, TOTAL_REPS);
+        assert_eq!(preset.online_reps, ONLINE_REPS);
+        assert!(preset.compiled);
+
+        // every named preset is described, only the compiled one can prove
+        assert_eq!(caps.presets.len(), Parameters::PRESETS.len());
+        assert!(!caps.preset("128-bit").unwrap().compiled);
+        let compiled: Vec<_> = caps.compiled_presets().map(|p| p.name).collect();
+        assert_eq!(compiled, vec!["default"]);
+
+        assert_eq!(caps.witness_formats, vec!["bits", "hex", "packed"]);
+    }
+}
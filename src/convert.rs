@@ -0,0 +1,194 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use reverie::parsers::{
+    parse_aiger, parse_blif, parse_bristol, parse_json, parse_sieve_text, write_json,
+    write_program, JsonCircuit, PROGRAM_CHUNK_SIZE,
+};
+use reverie::passes::validate;
+use reverie::proof::{Output, OutputWire};
+use reverie::{largest_wires, CombineOperation};
+
+use super::load_program;
+
+/// Formats which can be read by `convert`.
+pub const INPUT_FORMATS: [&str; 7] = [
+    "bincode", "compact", "bristol", "sieve", "blif", "aiger", "json",
+];
+
+/// Formats which can be written by `convert`.
+pub const OUTPUT_FORMATS: [&str; 3] = ["bincode", "compact", "json"];
+
+/// Guesses the format of a circuit file from its extension.
+pub fn format_of(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()? {
+        "bin" | "bincode" => Some("bincode"),
+        "rvr" | "compact" => Some("compact"),
+        "bristol" => Some("bristol"),
+        "rel" | "sieve" => Some("sieve"),
+        "blif" => Some("blif"),
+        "aag" | "aig" => Some("aiger"),
+        "json" => Some("json"),
+        _ => None,
+    }
+}
+
+fn invalid<S: Into<String>>(msg: S) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+/// Reads a circuit: the program and the output wires declared by the format (if any).
+///
+/// SIEVE IR relations are read along with their instance.
+pub fn read_circuit(
+    path: &Path,
+    format: &str,
+    instance: Option<&Path>,
+) -> io::Result<(Vec<CombineOperation>, Vec<Output>)> {
+    let reader = || File::open(path).map(BufReader::new);
+    match format {
+        "bincode" | "compact" => Ok((load_program(path)?, vec![])),
+        "bristol" => {
+            let circuit = parse_bristol(reader()?)?;
+            let outputs = circuit.output_wires();
+            Ok((circuit.program, outputs))
+        }
+        "sieve" => {
+            let instance =
+                instance.ok_or_else(|| invalid("SIEVE IR relations require an instance"))?;
+            let circuit = parse_sieve_text(
+                &fs::read_to_string(path)?,
+                &fs::read_to_string(instance)?,
+                None,
+            )?;
+            Ok((circuit.program, vec![]))
+        }
+        "blif" => {
+            let circuit = parse_blif(reader()?)?;
+            let outputs = circuit.output_wires();
+            Ok((circuit.program, outputs))
+        }
+        "aiger" => {
+            let circuit = parse_aiger(reader()?)?;
+            let outputs = circuit.output_wires();
+            Ok((circuit.program, outputs))
+        }
+        "json" => {
+            let circuit = parse_json(reader()?)?;
+            Ok((circuit.program()?, circuit.output_wires()))
+        }
+        _ => Err(invalid(format!("cannot read the {} format", format))),
+    }
+}
+
+/// Writes a program (and, in the JSON format, its output wires).
+pub fn write_circuit(
+    path: &Path,
+    format: &str,
+    program: &[CombineOperation],
+    outputs: &[Output],
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    match format {
+        "bincode" => bincode::serialize_into(&mut writer, program).map_err(io::Error::other)?,
+        "compact" => write_program(&mut writer, program, PROGRAM_CHUNK_SIZE)?,
+        "json" => write_json(&mut writer, &JsonCircuit::from_program(program, outputs))?,
+        _ => return Err(invalid(format!("cannot write the {} format", format))),
+    }
+    writer.flush()
+}
+
+/// Writes output wires in the format read by `--outputs`.
+pub fn write_outputs(path: &Path, outputs: &[Output]) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    for output in outputs {
+        match output.wire {
+            OutputWire::GF2(wire) => writeln!(writer, "{} gf2 {}", output.label, wire)?,
+            OutputWire::Z64(wire) => writeln!(writer, "{} z64 {}", output.label, wire)?,
+        }
+    }
+    writer.flush()
+}
+
+/// Translates a circuit between formats, optionally validating it first.
+pub fn convert(
+    input: (&Path, &str),
+    output: (&Path, &str),
+    instance: Option<&Path>,
+    check: bool,
+    outputs_path: Option<&Path>,
+) -> io::Result<Result<(), String>> {
+    let (program, outputs) = read_circuit(input.0, input.1, instance)?;
+    if check {
+        if let Err(e) = validate(&program, &outputs, largest_wires(&program)) {
+            return Ok(Err(e.to_string()));
+        }
+    }
+    write_circuit(output.0, output.1, &program, &outputs)?;
+    if let Some(path) = outputs_path {
+        write_outputs(path, &outputs)?;
+    }
+    println!(
+        "Converted {} operations and {} outputs ({} to {})",
+        program.len(),
+        outputs.len(),
+        input.1,
+        output.1
+    );
+    Ok(Ok(()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reverie::Operation;
+
+    #[test]
+    fn test_convert() {
+        let dir = tempfile::tempdir().unwrap();
+        let bristol = dir.path().join("and.bristol");
+        fs::write(&bristol, "1 3\n2 1 1\n1 1\n\n2 1 0 1 2 AND\n").unwrap();
+        assert_eq!(format_of(&bristol), Some("bristol"));
+
+        // bristol -> json -> compact
+        let json = dir.path().join("and.json");
+        let compact = dir.path().join("and.rvr");
+        let outputs = dir.path().join("and.outputs");
+        convert((&bristol, "bristol"), (&json, "json"), None, true, None)
+            .unwrap()
+            .unwrap();
+        convert(
+            (&json, "json"),
+            (&compact, "compact"),
+            None,
+            true,
+            Some(&outputs),
+        )
+        .unwrap()
+        .unwrap();
+
+        let (program, declared) = read_circuit(&compact, "compact", None).unwrap();
+        assert_eq!(program.len(), 3);
+        assert!(matches!(
+            program[2],
+            CombineOperation::GF2(Operation::Mul(2, 0, 1))
+        ));
+        assert!(declared.is_empty());
+        assert_eq!(fs::read_to_string(&outputs).unwrap(), "out0[0] gf2 2\n");
+
+        // validation failures are reported, nothing is written
+        fs::write(&bristol, "1 3\n2 1 1\n1 1\n\n2 1 0 2 2 AND\n").unwrap();
+        let bincode = dir.path().join("bad.bin");
+        assert!(convert(
+            (&bristol, "bristol"),
+            (&bincode, "bincode"),
+            None,
+            true,
+            None
+        )
+        .unwrap()
+        .is_err());
+        assert!(!bincode.exists());
+    }
+}
@@ -8,14 +8,67 @@ const BUFFER_SLACK: usize = 128;
 pub const HASH_SIZE: usize = 32;
 
 use std::convert::AsMut;
+use std::io;
 use std::ops::{Index, IndexMut};
 
 pub type Hasher = BufferedHasher;
 pub type Hash = blake3::Hash;
 
+/// A hash function the commitments, transcripts and random oracle can be built on.
+pub trait HashBackend: Clone + io::Write {
+    /// Identifies the hash function in the header of proofs.
+    const ID: u8;
+
+    fn init() -> Self;
+    fn absorb(&mut self, buf: &[u8]);
+    fn digest(&self) -> Hash;
+}
+
+impl HashBackend for blake3::Hasher {
+    const ID: u8 = 0;
+
+    fn init() -> Self {
+        blake3::Hasher::new()
+    }
+
+    fn absorb(&mut self, buf: &[u8]) {
+        self.update(buf);
+    }
+
+    fn digest(&self) -> Hash {
+        self.finalize()
+    }
+}
+
+#[cfg(feature = "sha256")]
+impl HashBackend for sha2::Sha256 {
+    const ID: u8 = 1;
+
+    fn init() -> Self {
+        sha2::Digest::new()
+    }
+
+    fn absorb(&mut self, buf: &[u8]) {
+        sha2::Digest::update(self, buf);
+    }
+
+    fn digest(&self) -> Hash {
+        let digest: [u8; HASH_SIZE] = sha2::Digest::finalize(self.clone()).into();
+        Hash::from(digest)
+    }
+}
+
+/// The hash function of this build: BLAKE3, or SHA-256 with the `sha256` feature
+/// (for environments which only allow SHA-2). Proofs record it in their header.
+#[cfg(not(feature = "sha256"))]
+pub type Backend = blake3::Hasher;
+
+#[cfg(feature = "sha256")]
+pub type Backend = sha2::Sha256;
+
 #[derive(Debug)]
 pub struct BufferedHasher {
-    hasher: blake3::Hasher,
+    hasher: Backend,
     buffer: Vec<u8>,
 }
 
@@ -28,7 +81,7 @@ impl Default for BufferedHasher {
 impl BufferedHasher {
     pub fn new() -> Self {
         BufferedHasher {
-            hasher: blake3::Hasher::new(),
+            hasher: Backend::init(),
             buffer: Vec::with_capacity(BUFFER_SIZE + BUFFER_SLACK),
         }
     }
@@ -36,7 +89,7 @@ impl BufferedHasher {
     pub fn push(&mut self, val: u8) {
         self.buffer.push(val);
         if self.buffer.len() >= BUFFER_SIZE {
-            self.hasher.update(&self.buffer);
+            self.hasher.absorb(&self.buffer);
             self.buffer.clear();
         }
     }
@@ -45,15 +98,15 @@ impl BufferedHasher {
         debug_assert!(buf.len() < BUFFER_SLACK);
         self.buffer.extend_from_slice(buf);
         if self.buffer.len() >= BUFFER_SIZE {
-            self.hasher.update(&self.buffer);
+            self.hasher.absorb(&self.buffer);
             self.buffer.clear();
         }
     }
 
     pub fn finalize(&self) -> Hash {
         let mut hasher = self.hasher.clone();
-        hasher.update(&self.buffer);
-        hasher.finalize()
+        hasher.absorb(&self.buffer);
+        hasher.digest()
     }
 }
 
@@ -118,10 +171,11 @@ impl AsRef<[Hasher; PACKED]> for PackedHasher {
 #[macro_export]
 macro_rules! HASH {
     ( $($input:expr),* ) => {{
-        let mut h = blake3::Hasher::new();
+        use $crate::crypto::hash::HashBackend;
+        let mut h = $crate::crypto::hash::Backend::init();
         $(
-            h.update($input);
+            h.absorb($input);
         )*
-        h.finalize()
+        h.digest()
     }};
 }
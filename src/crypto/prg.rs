@@ -1,22 +1,66 @@
 use aes::cipher::generic_array::GenericArray;
 use aes::cipher::{KeyIvInit, StreamCipher};
-use aes::Aes128;
 
 const BLOCK_SIZE: usize = 16;
 
-type Aes128Ctr = ctr::Ctr128BE<Aes128>;
+#[cfg(not(feature = "key256"))]
+type AesCtr = ctr::Ctr128BE<aes::Aes128>;
 
+#[cfg(feature = "key256")]
+type AesCtr = ctr::Ctr128BE<aes::Aes256>;
+
+/// Length of the seeds (the PRG keys): 16 bytes, or 32 bytes with the `key256` feature
+/// (AES-256 instead of AES-128, implied by `chacha20`). Proofs record it in their header.
+#[cfg(not(feature = "key256"))]
 pub const KEY_SIZE: usize = 16;
 
+#[cfg(feature = "key256")]
+pub const KEY_SIZE: usize = 32;
+
 pub type Key = [u8; KEY_SIZE];
 
-pub struct PRG(Aes128Ctr);
+/// A stream cipher the seeds are expanded with (into the random tapes of the players).
+pub trait PrgBackend: StreamCipher {
+    /// Identifies the PRG in the header of proofs.
+    const ID: u8;
 
-impl PRG {
-    pub fn new(key: &[u8; KEY_SIZE]) -> Self {
+    fn init(key: &Key) -> Self;
+}
+
+impl PrgBackend for AesCtr {
+    const ID: u8 = 0;
+
+    fn init(key: &Key) -> Self {
         let key = GenericArray::from_slice(key);
         let nonce = GenericArray::from_slice(&[0u8; 16]);
-        PRG(Aes128Ctr::new(key, nonce))
+        AesCtr::new(key, nonce)
+    }
+}
+
+#[cfg(feature = "chacha20")]
+impl PrgBackend for chacha20::ChaCha20 {
+    const ID: u8 = 1;
+
+    fn init(key: &Key) -> Self {
+        let key = GenericArray::from_slice(key);
+        let nonce = GenericArray::from_slice(&[0u8; 12]);
+        chacha20::ChaCha20::new(key, nonce)
+    }
+}
+
+/// The PRG of this build: AES-CTR, or ChaCha20 with the `chacha20` feature
+/// (for hosts without AES instructions). Proofs record it in their header.
+#[cfg(not(feature = "chacha20"))]
+pub type Backend = AesCtr;
+
+#[cfg(feature = "chacha20")]
+pub type Backend = chacha20::ChaCha20;
+
+pub struct PRG(Backend);
+
+impl PRG {
+    pub fn new(key: &[u8; KEY_SIZE]) -> Self {
+        PRG(Backend::init(key))
     }
 
     pub fn xor_bytes(&mut self, dst: &mut [u8]) {
@@ -36,3 +80,18 @@ impl PRG {
         self.xor_bytes(dst);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test::Bencher;
+
+    use super::*;
+
+    #[bench]
+    fn bench_prg(b: &mut Bencher) {
+        let mut prg = PRG::new(&[1u8; KEY_SIZE]);
+        let mut tape = vec![0u8; 1 << 16];
+        b.bytes = tape.len() as u64;
+        b.iter(|| prg.gen(&mut tape));
+    }
+}
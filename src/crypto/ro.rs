@@ -1,9 +1,12 @@
-use blake3;
+#[cfg(feature = "sha256")]
+use super::hash::{HashBackend, HASH_SIZE};
 
+#[cfg(not(feature = "sha256"))]
 pub struct RandomOracle {
     reader: blake3::OutputReader,
 }
 
+#[cfg(not(feature = "sha256"))]
 impl RandomOracle {
     pub fn new(ctx: &'static str, input: &[u8]) -> RandomOracle {
         let mut hasher = blake3::Hasher::new();
@@ -19,3 +22,44 @@ impl RandomOracle {
         self.reader.fill(buf)
     }
 }
+
+/// SHA-256 has no extendable output: the output is SHA-256(seed || counter) for
+/// counter = 0, 1, ..., with seed = SHA-256(ctx || 0 || input).
+#[cfg(feature = "sha256")]
+pub struct RandomOracle {
+    seed: [u8; HASH_SIZE],
+    counter: u64,
+    block: [u8; HASH_SIZE],
+    used: usize,
+}
+
+#[cfg(feature = "sha256")]
+impl RandomOracle {
+    pub fn new(ctx: &'static str, input: &[u8]) -> RandomOracle {
+        let mut hasher = sha2::Sha256::init();
+        hasher.absorb(ctx.as_bytes());
+        hasher.absorb(&[0u8]);
+        hasher.absorb(input);
+        RandomOracle {
+            seed: *hasher.digest().as_bytes(),
+            counter: 0,
+            block: [0u8; HASH_SIZE],
+            used: HASH_SIZE,
+        }
+    }
+
+    pub fn fill(&mut self, buf: &mut [u8]) {
+        for byte in buf.iter_mut() {
+            if self.used == HASH_SIZE {
+                let mut hasher = sha2::Sha256::init();
+                hasher.absorb(&self.seed);
+                hasher.absorb(&self.counter.to_le_bytes());
+                self.block = *hasher.digest().as_bytes();
+                self.counter += 1;
+                self.used = 0;
+            }
+            *byte = self.block[self.used];
+            self.used += 1;
+        }
+    }
+}
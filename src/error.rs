@@ -0,0 +1,169 @@
+use std::io;
+use std::ops::Range;
+
+use thiserror::Error;
+
+use crate::proof::{OutputValue, Parameters};
+
+/// Why proving or verifying failed.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The program (or proof) could not be read.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// The proof is not a valid encoding.
+    #[error("corrupt proof encoding: {0}")]
+    Encoding(#[from] bincode::Error),
+
+    /// The proof is not a valid encoding in another format (see `Proof::serialize_json`).
+    #[error("corrupt {format} proof encoding: {reason}")]
+    Codec {
+        format: &'static str,
+        reason: String,
+    },
+
+    /// The encoded proof is larger than the limit given to the reader
+    /// (see `Proof::deserialize_with_limit`).
+    #[error("the proof is larger than the limit of {limit} bytes")]
+    TooLarge { limit: u64 },
+
+    /// The data does not start with the magic number of a proof.
+    #[error("not a proof (bad magic number), or a proof from an older version")]
+    Magic,
+
+    /// The proof was serialized with an incompatible version of the format.
+    #[error("unsupported proof format version {found} (supported: {supported})")]
+    Version { found: u16, supported: u16 },
+
+    /// The proof is for other domains than those supported by this build.
+    #[error("the proof is for domains {found}, expected domains {expected} (gf2 and z64)")]
+    Domains { found: u8, expected: u8 },
+
+    /// The proof uses seeds of another length than this build (see the `key256` feature).
+    #[error("the proof uses {found}-byte seeds, this build {expected}-byte seeds")]
+    KeySize { found: u8, expected: u8 },
+
+    /// The proof uses another hash function than this build (0: BLAKE3, 1: SHA-256,
+    /// see the `sha256` feature).
+    #[error("the proof uses hash function {found}, this build hash function {expected}")]
+    HashFunction { found: u8, expected: u8 },
+
+    /// The proof uses another PRG than this build (0: AES-CTR, 1: ChaCha20,
+    /// see the `chacha20` feature).
+    #[error("the proof uses PRG {found}, this build PRG {expected}")]
+    Prg { found: u8, expected: u8 },
+
+    /// The parameter set is invalid or not supported by this build.
+    #[error("invalid parameters {params:?}: {reason}")]
+    Parameters {
+        params: Parameters,
+        reason: &'static str,
+    },
+
+    /// The witness does not hold one value per `Input` operation of the domain.
+    #[error(
+        "the {domain} witness has {got} values, but the program has {expected} {domain} inputs"
+    )]
+    WitnessLength {
+        domain: &'static str,
+        expected: usize,
+        got: usize,
+    },
+
+    /// A wire of the program (or an output) is beyond the wire counts given to the prover.
+    #[error("{at}: {domain} wire {wire} out of range ({wires} wires)")]
+    WireRange {
+        at: String,
+        domain: &'static str,
+        wire: usize,
+        wires: usize,
+    },
+
+    /// The witness does not satisfy the program: an assertion fails when the program is
+    /// evaluated in the clear (see `proof::evaluate`).
+    #[error("{at}: {domain} assertion fails (wire {wire} is not zero)")]
+    Unsatisfied {
+        at: String,
+        domain: &'static str,
+        wire: usize,
+    },
+
+    /// A wire (or output) is read before the program assigns it (see `passes::validate`).
+    #[error("{at}: {domain} wire {wire} is read before it is assigned")]
+    Unassigned {
+        at: String,
+        domain: &'static str,
+        wire: usize,
+    },
+
+    /// A hint does not fit the program, or returns the wrong number of values
+    /// (see `hints::expand_witness`).
+    #[error("hint for instructions {range:?}: {reason}")]
+    Hint { range: Range<usize>, reason: String },
+
+    /// The region markers of a structured program are not properly nested
+    /// (see `regions::strip`).
+    #[error("invalid region markers: {reason}")]
+    Regions { reason: String },
+
+    /// The proof decodes, but does not have the shape required by the parameters.
+    #[error("malformed proof: expected {expected} {domain} {what}, found {found}")]
+    Format {
+        domain: &'static str,
+        what: &'static str,
+        expected: usize,
+        found: usize,
+    },
+
+    /// An online repetition opens a different player than the challenge derived from
+    /// the commitment: the proof was created for another commitment, or corrupted in transit.
+    #[error("{domain} repetition {repetition}: the opened players do not match the challenge")]
+    Challenge {
+        domain: &'static str,
+        repetition: usize,
+    },
+
+    /// An assertion (`AssertZero`) fails in a batch of online repetitions,
+    /// i.e. the prover does not know a witness satisfying the program.
+    #[error("invalid MPC transcript: {domain} assertion fails in repetitions {repetitions:?}")]
+    AssertZero {
+        domain: &'static str,
+        repetitions: Vec<usize>,
+    },
+
+    /// An opened online repetition reconstructs a different value for an output than the others.
+    #[error("invalid MPC transcript: repetition {repetition} disagrees on output '{label}'")]
+    Outputs { label: String, repetition: usize },
+
+    /// An expected output is not among the outputs revealed by the proof.
+    #[error("output '{label}' is not revealed by the proof")]
+    OutputMissing { label: String },
+
+    /// The proof is valid, but reveals another value for an output than expected:
+    /// it proves a different statement.
+    #[error("output '{label}' is {found}, expected {expected}")]
+    OutputMismatch {
+        label: String,
+        expected: OutputValue,
+        found: OutputValue,
+    },
+
+    /// The proof was created for another program (e.g. a different version of the circuit file).
+    #[error("the proof is for another program (hash {proof}), not this one (hash {program})")]
+    Program { proof: String, program: String },
+
+    /// Re-executing the opened repetitions does not reproduce the commitment of the prover:
+    /// the proof does not prove this statement, or a preprocessing/online opening was tampered with.
+    ///
+    /// The proof commits to all repetitions with a single hash,
+    /// hence the mismatching repetition cannot be identified.
+    #[error("the repetitions do not match the commitment of the proof")]
+    Commitment,
+
+    /// Proving or verification was stopped by a `CancelToken` (cancelled or timed out).
+    #[error("cancelled")]
+    Cancelled,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
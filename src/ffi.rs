@@ -0,0 +1,191 @@
+//! C interface to the prover and verifier (feature `ffi`), built into a shared library with
+//! `cargo rustc --lib --release --crate-type cdylib --features ffi`.
+//!
+//! Programs are passed in the compact binary format (see `parsers::write_program`),
+//! proofs as written by `Proof::serialize`. Every function returns a `REVERIE_*` status code.
+//!
+//! ```c
+//! int reverie_prove(const uint8_t *program, size_t program_len,
+//!                   const uint8_t *wit_gf2, size_t wit_gf2_len,
+//!                   const uint64_t *wit_z64, size_t wit_z64_len,
+//!                   uint8_t **proof, size_t *proof_len);
+//! int reverie_verify(const uint8_t *program, size_t program_len,
+//!                    const uint8_t *proof, size_t proof_len);
+//! void reverie_free_proof(uint8_t *proof, size_t proof_len);
+//! ```
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+use crate::proof::bytes;
+use crate::CombineOperation;
+
+/// Success: the proof was created, or verifies.
+pub const REVERIE_OK: i32 = 0;
+/// A null pointer, or a program which cannot be decoded.
+pub const REVERIE_INVALID: i32 = 1;
+/// The witness does not fit (or satisfy) the program, or the proof does not verify.
+pub const REVERIE_REJECTED: i32 = 2;
+/// Internal error (a panic, caught at the boundary).
+pub const REVERIE_PANIC: i32 = 3;
+
+// the bytes of a (pointer, length) pair, empty for a null pointer with length 0
+unsafe fn buffer<'a, T>(ptr: *const T, len: usize) -> Option<&'a [T]> {
+    match (ptr.is_null(), len) {
+        (true, 0) => Some(&[]),
+        (true, _) => None,
+        (false, _) => Some(slice::from_raw_parts(ptr, len)),
+    }
+}
+
+fn decode_program(bytes: &[u8]) -> Option<Vec<CombineOperation>> {
+    bytes::decode_program(bytes).ok()
+}
+
+// runs `f` without letting a panic unwind into the caller
+fn guard(f: impl FnOnce() -> i32) -> i32 {
+    panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(REVERIE_PANIC)
+}
+
+/// Proves a program for the witness (one byte, 0 or 1, per gf2 input), and stores the
+/// serialized proof in `*proof`/`*proof_len`, to be released with `reverie_free_proof`.
+///
+/// # Safety
+///
+/// Every (pointer, length) pair must describe a readable buffer (or be null with length 0),
+/// `proof` and `proof_len` must be writable.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn reverie_prove(
+    program: *const u8,
+    program_len: usize,
+    wit_gf2: *const u8,
+    wit_gf2_len: usize,
+    wit_z64: *const u64,
+    wit_z64_len: usize,
+    proof: *mut *mut u8,
+    proof_len: *mut usize,
+) -> i32 {
+    guard(|| {
+        let inputs = (
+            buffer(program, program_len).and_then(decode_program),
+            buffer(wit_gf2, wit_gf2_len),
+            buffer(wit_z64, wit_z64_len),
+        );
+        let (program, wit_gf2, wit_z64) = match inputs {
+            (Some(program), Some(wit_gf2), Some(wit_z64)) => (program, wit_gf2, wit_z64),
+            _ => return REVERIE_INVALID,
+        };
+        if proof.is_null() || proof_len.is_null() {
+            return REVERIE_INVALID;
+        }
+        let wit_gf2 = wit_gf2.iter().map(|bit| *bit != 0).collect();
+        match bytes::prove(&program, wit_gf2, wit_z64.to_vec()) {
+            Ok(bytes) => {
+                *proof_len = bytes.len();
+                *proof = Box::into_raw(bytes.into_boxed_slice()) as *mut u8;
+                REVERIE_OK
+            }
+            Err(_) => REVERIE_REJECTED,
+        }
+    })
+}
+
+/// Verifies a serialized proof of a program.
+///
+/// # Safety
+///
+/// Every (pointer, length) pair must describe a readable buffer (or be null with length 0).
+#[no_mangle]
+pub unsafe extern "C" fn reverie_verify(
+    program: *const u8,
+    program_len: usize,
+    proof: *const u8,
+    proof_len: usize,
+) -> i32 {
+    guard(|| {
+        let program = match buffer(program, program_len).and_then(decode_program) {
+            Some(program) => program,
+            None => return REVERIE_INVALID,
+        };
+        let proof = match buffer(proof, proof_len) {
+            Some(proof) => proof,
+            None => return REVERIE_INVALID,
+        };
+        match bytes::verify(&program, proof) {
+            Ok(()) => REVERIE_OK,
+            Err(_) => REVERIE_REJECTED,
+        }
+    })
+}
+
+/// Releases a proof returned by `reverie_prove` (null is ignored).
+///
+/// # Safety
+///
+/// `proof` and `proof_len` must be those returned by `reverie_prove`, released only once.
+#[no_mangle]
+pub unsafe extern "C" fn reverie_free_proof(proof: *mut u8, proof_len: usize) {
+    if !proof.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(proof, proof_len)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use super::*;
+    use crate::parsers::write_program;
+    use crate::Operation;
+
+    #[test]
+    fn test_ffi() {
+        let program = vec![
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::SubConst(1, 0, 7)),
+            CombineOperation::Z64(Operation::AssertZero(1)),
+        ];
+        let encode = |program: &[CombineOperation]| {
+            let mut encoded = vec![];
+            write_program(&mut encoded, program, 16).unwrap();
+            encoded
+        };
+        let encoded = encode(&program);
+
+        let prove = |wit: u64| unsafe {
+            let (mut proof, mut proof_len) = (ptr::null_mut(), 0);
+            let status = reverie_prove(
+                encoded.as_ptr(),
+                encoded.len(),
+                ptr::null(),
+                0,
+                &wit,
+                1,
+                &mut proof,
+                &mut proof_len,
+            );
+            (status, proof, proof_len)
+        };
+
+        let (status, proof, proof_len) = prove(7);
+        assert_eq!(status, REVERIE_OK);
+        unsafe {
+            let verify =
+                |program: &[u8]| reverie_verify(program.as_ptr(), program.len(), proof, proof_len);
+            assert_eq!(verify(&encoded), REVERIE_OK);
+            assert_eq!(verify(&encoded[..4]), REVERIE_INVALID);
+
+            // the verifier rejects the proof for another program
+            let mut other = program.clone();
+            other[1] = CombineOperation::Z64(Operation::SubConst(1, 0, 8));
+            assert_eq!(verify(&encode(&other)), REVERIE_REJECTED);
+            reverie_free_proof(proof, proof_len);
+        }
+
+        // the prover rejects an unsatisfied program
+        let (status, proof, _) = prove(8);
+        assert_eq!(status, REVERIE_REJECTED);
+        assert!(proof.is_null());
+    }
+}
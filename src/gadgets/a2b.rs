@@ -0,0 +1,88 @@
+use super::ProgramBuilder;
+use crate::algebra::z64::BIT_SIZE;
+use crate::hints::{Hint, HintValues, HintWires};
+use crate::{CombineOperation, Operation};
+
+impl ProgramBuilder {
+    /// Checked arithmetic to boolean conversion:
+    /// decomposes the z64 wire `src` into 64 gf2 wires (least significant bit first)
+    /// and returns the first of them.
+    ///
+    /// The bits are provided by a hint and recomposed with `B2A`,
+    /// the recomposition is asserted to equal `src`, which makes the decomposition unique.
+    pub fn a2b(&mut self, src: usize) -> usize {
+        let bits = self.fresh_gf2(BIT_SIZE);
+
+        // bits are advice
+        let start = self.len();
+        for i in 0..BIT_SIZE {
+            self.push(CombineOperation::GF2(Operation::Input(bits + i)));
+        }
+        self.hint(Hint::new(start..self.len(), move |wires: &HintWires| {
+            let value = wires.z64(src);
+            HintValues {
+                gf2: (0..BIT_SIZE).map(|i| (value >> i) & 1 == 1).collect(),
+                z64: vec![],
+            }
+        }));
+
+        // check: B2A(bits) - src = 0
+        let recomposed = self.fresh_z64(2);
+        self.push(CombineOperation::B2A(recomposed, bits));
+        self.push(CombineOperation::Z64(Operation::Sub(
+            recomposed + 1,
+            recomposed,
+            src,
+        )));
+        self.push(CombineOperation::Z64(Operation::AssertZero(recomposed + 1)));
+        bits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::hints::expand_witness;
+    use crate::largest_wires;
+    use crate::proof::{Output, OutputValue, Proof, ProverConfig};
+
+    #[test]
+    fn test_a2b() {
+        let value: u64 = 0xf00d_0000_1234_abcd;
+
+        // x = a + b, decomposed into bits
+        let mut builder = ProgramBuilder::default();
+        builder.push(CombineOperation::Z64(Operation::Input(0)));
+        builder.push(CombineOperation::Z64(Operation::Input(1)));
+        builder.push(CombineOperation::Z64(Operation::Add(2, 0, 1)));
+        let bits = builder.a2b(2);
+        let (program, hints) = builder.finish();
+
+        let (wit_gf2, wit_z64) = expand_witness(&program, &hints, &[], &[value - 5, 5]).unwrap();
+
+        let outputs: Vec<Output> = (0..BIT_SIZE)
+            .map(|i| Output::gf2(format!("bit{}", i), bits + i))
+            .collect();
+        let wire_counts = largest_wires(&program);
+        let program = Arc::new(program);
+        let proof = Proof::new_with_config(
+            program.clone(),
+            &outputs,
+            Arc::new(wit_gf2),
+            Arc::new(wit_z64),
+            wire_counts,
+            &ProverConfig::default(),
+        );
+        let revealed = proof
+            .verify_with_outputs(program, &outputs, wire_counts)
+            .unwrap();
+        for i in 0..BIT_SIZE {
+            assert_eq!(
+                revealed[&format!("bit{}", i)],
+                OutputValue::GF2((value >> i) & 1 == 1)
+            );
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use super::ProgramBuilder;
+use crate::algebra::z64::BIT_SIZE;
+use crate::{CombineOperation, Operation};
+
+impl ProgramBuilder {
+    fn gf2(&mut self, op: Operation<bool>) {
+        self.push(CombineOperation::GF2(op));
+    }
+
+    // borrow out of a - b for the bit-decompositions starting at a and b (64 AND gates)
+    fn borrow_bits(&mut self, a: usize, b: usize) -> usize {
+        // borrow_{i+1} = maj(!a_i, b_i, borrow_i) = b_i + (b_i + a_i + 1) * (b_i + borrow_i)
+        let mut borrow: Option<usize> = None;
+        for i in 0..BIT_SIZE {
+            let t = self.fresh_gf2(4);
+            self.gf2(Operation::Add(t, b + i, a + i));
+            self.gf2(Operation::AddConst(t + 1, t, true));
+            let rhs = match borrow {
+                None => b + i, // borrow_0 = 0
+                Some(borrow) => {
+                    self.gf2(Operation::Add(t + 2, b + i, borrow));
+                    t + 2
+                }
+            };
+            self.gf2(Operation::Mul(t + 3, t + 1, rhs));
+            let next = self.fresh_gf2(1);
+            self.gf2(Operation::Add(next, b + i, t + 3));
+            borrow = Some(next);
+        }
+        borrow.unwrap()
+    }
+
+    /// Returns a gf2 wire which is one iff `a < b` for the z64 wires a and b (as unsigned integers).
+    ///
+    /// Both wires are bit-decomposed (see `a2b`) and compared with a borrow chain.
+    pub fn less_than(&mut self, a: usize, b: usize) -> usize {
+        let a = self.a2b(a);
+        let b = self.a2b(b);
+        self.borrow_bits(a, b)
+    }
+
+    /// Returns a gf2 wire which is one iff `a > b` for the z64 wires a and b (as unsigned integers).
+    pub fn greater_than(&mut self, a: usize, b: usize) -> usize {
+        self.less_than(b, a)
+    }
+
+    /// Constrains `a < b` for the z64 wires a and b (as unsigned integers).
+    pub fn assert_less_than(&mut self, a: usize, b: usize) {
+        let lt = self.less_than(a, b);
+        let not_lt = self.fresh_gf2(1);
+        self.gf2(Operation::AddConst(not_lt, lt, true));
+        self.gf2(Operation::AssertZero(not_lt));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::hints::expand_witness;
+    use crate::largest_wires;
+    use crate::proof::{Output, OutputValue, Proof, ProverConfig};
+
+    #[test]
+    fn test_compare() {
+        let pairs = [
+            (3, 5),
+            (5, 3),
+            (7, 7),
+            (0, u64::MAX),
+            (u64::MAX, 0),
+            (1 << 63, (1 << 63) - 1),
+        ];
+
+        let mut builder = ProgramBuilder::default();
+        let mut outputs = vec![];
+        for (i, _) in pairs.iter().enumerate() {
+            builder.push(CombineOperation::Z64(Operation::Input(2 * i)));
+            builder.push(CombineOperation::Z64(Operation::Input(2 * i + 1)));
+            let lt = builder.less_than(2 * i, 2 * i + 1);
+            let gt = builder.greater_than(2 * i, 2 * i + 1);
+            outputs.push(Output::gf2(format!("lt{}", i), lt));
+            outputs.push(Output::gf2(format!("gt{}", i), gt));
+        }
+        let (program, hints) = builder.finish();
+
+        let wit_z64: Vec<u64> = pairs.iter().flat_map(|(a, b)| [*a, *b]).collect();
+        let (wit_gf2, wit_z64) = expand_witness(&program, &hints, &[], &wit_z64).unwrap();
+
+        let wire_counts = largest_wires(&program);
+        let program = Arc::new(program);
+        let proof = Proof::new_with_config(
+            program.clone(),
+            &outputs,
+            Arc::new(wit_gf2),
+            Arc::new(wit_z64),
+            wire_counts,
+            &ProverConfig::default(),
+        );
+        let values = proof
+            .verify_with_outputs(program, &outputs, wire_counts)
+            .unwrap();
+        for (i, (a, b)) in pairs.iter().enumerate() {
+            assert_eq!(values[&format!("lt{}", i)], OutputValue::GF2(a < b));
+            assert_eq!(values[&format!("gt{}", i)], OutputValue::GF2(a > b));
+        }
+    }
+
+    #[test]
+    fn test_assert_less_than() {
+        let mut builder = ProgramBuilder::default();
+        builder.push(CombineOperation::Z64(Operation::Input(0)));
+        builder.push(CombineOperation::Z64(Operation::Const(1, 1000)));
+        builder.assert_less_than(0, 1);
+        let (program, hints) = builder.finish();
+
+        assert!(expand_witness(&program, &hints, &[], &[999]).is_ok());
+        assert!(expand_witness(&program, &hints, &[], &[1000]).is_err());
+    }
+}
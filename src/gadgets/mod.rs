@@ -0,0 +1,94 @@
+//! Gadgets lowered to the `CombineOperation` instruction set.
+//!
+//! Gadgets which need nondeterministic advice (e.g. a bit-decomposition)
+//! register a [`Hint`] computing it, see [`crate::hints::expand_witness`].
+
+mod a2b;
+mod compare;
+
+use crate::hints::Hint;
+use crate::passes::op_wires;
+use crate::{largest_wires, CombineOperation};
+
+/// Appends instructions (and hints) to a program, allocating fresh wires for gadgets.
+pub struct ProgramBuilder {
+    program: Vec<CombineOperation>,
+    hints: Vec<Hint>,
+    next_gf2: usize,
+    next_z64: usize,
+}
+
+impl Default for ProgramBuilder {
+    fn default() -> Self {
+        Self::new(vec![])
+    }
+}
+
+impl ProgramBuilder {
+    /// Continues an existing program: fresh wires are allocated above its largest wires.
+    pub fn new(program: Vec<CombineOperation>) -> Self {
+        // largest_wires panics on an empty program
+        let (next_z64, next_gf2) = if program.is_empty() {
+            (0, 0)
+        } else {
+            largest_wires(&program)
+        };
+        ProgramBuilder {
+            program,
+            hints: vec![],
+            next_gf2,
+            next_z64,
+        }
+    }
+
+    /// Returns the first of `n` fresh (consecutive) gf2 wires.
+    pub fn fresh_gf2(&mut self, n: usize) -> usize {
+        let wire = self.next_gf2;
+        self.next_gf2 += n;
+        wire
+    }
+
+    /// Returns the first of `n` fresh (consecutive) z64 wires.
+    pub fn fresh_z64(&mut self, n: usize) -> usize {
+        let wire = self.next_z64;
+        self.next_z64 += n;
+        wire
+    }
+
+    /// Appends an instruction, any wires it writes should be allocated with `fresh_*`.
+    pub fn push(&mut self, op: CombineOperation) {
+        match op {
+            CombineOperation::GF2(ref op) => {
+                if let Some(dst) = op_wires(op).0 {
+                    self.next_gf2 = self.next_gf2.max(dst + 1);
+                }
+            }
+            CombineOperation::Z64(ref op) => {
+                if let Some(dst) = op_wires(op).0 {
+                    self.next_z64 = self.next_z64.max(dst + 1);
+                }
+            }
+            CombineOperation::B2A(dst, _) => self.next_z64 = self.next_z64.max(dst + 1),
+            CombineOperation::SizeHint(_, _) => (),
+        }
+        self.program.push(op);
+    }
+
+    /// Index of the next instruction.
+    pub fn len(&self) -> usize {
+        self.program.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.program.is_empty()
+    }
+
+    fn hint(&mut self, hint: Hint) {
+        self.hints.push(hint);
+    }
+
+    /// Returns the program and the hints computing the advice of its gadgets.
+    pub fn finish(self) -> (Vec<CombineOperation>, Vec<Hint>) {
+        (self.program, self.hints)
+    }
+}
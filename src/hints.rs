@@ -0,0 +1,444 @@
+//! Witness values computed by the prover during (plain) evaluation of the program.
+//!
+//! A [`Hint`] covers a range of instructions: when evaluation reaches the start of the range,
+//! the hint is called with the current wire values and returns the witness consumed by the
+//! `Input` operations within the range (e.g. the bit-decomposition of an intermediate value).
+//! The hint values are not trusted: they must be constrained by subsequent gates.
+
+use std::collections::VecDeque;
+use std::iter::Peekable;
+use std::ops::Range;
+use std::slice;
+
+use mcircuit::WireValue;
+
+use crate::algebra::z64::BIT_SIZE;
+use crate::error::{Error, Result};
+use crate::{CombineOperation, Operation};
+
+/// Plain wire values visible to a hint.
+///
+/// Wires which have not been assigned (and `Random` wires) read as zero.
+pub struct HintWires<'a> {
+    gf2: &'a [bool],
+    z64: &'a [u64],
+}
+
+impl<'a> HintWires<'a> {
+    pub fn gf2(&self, wire: usize) -> bool {
+        self.gf2.get(wire).copied().unwrap_or_default()
+    }
+
+    pub fn z64(&self, wire: usize) -> u64 {
+        self.z64.get(wire).copied().unwrap_or_default()
+    }
+}
+
+/// Witness values returned by a hint, consumed in order by the `Input` operations of its range.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HintValues {
+    pub gf2: Vec<bool>,
+    pub z64: Vec<u64>,
+}
+
+type HintFn = dyn Fn(&HintWires) -> HintValues + Send + Sync;
+
+/// Computes the witness of the `Input` operations in a range of instructions.
+pub struct Hint {
+    range: Range<usize>,
+    compute: Box<HintFn>,
+}
+
+impl Hint {
+    pub fn new<F>(range: Range<usize>, compute: F) -> Self
+    where
+        F: Fn(&HintWires) -> HintValues + Send + Sync + 'static,
+    {
+        Hint {
+            range,
+            compute: Box::new(compute),
+        }
+    }
+}
+
+trait Plain: WireValue + Copy + Default {
+    fn add(self, other: Self) -> Self;
+    fn sub(self, other: Self) -> Self;
+    fn mul(self, other: Self) -> Self;
+    fn is_zero(self) -> bool;
+}
+
+impl Plain for bool {
+    fn add(self, other: Self) -> Self {
+        self ^ other
+    }
+
+    fn sub(self, other: Self) -> Self {
+        self ^ other
+    }
+
+    fn mul(self, other: Self) -> Self {
+        self & other
+    }
+
+    fn is_zero(self) -> bool {
+        !self
+    }
+}
+
+impl Plain for u64 {
+    fn add(self, other: Self) -> Self {
+        self.wrapping_add(other)
+    }
+
+    fn sub(self, other: Self) -> Self {
+        self.wrapping_sub(other)
+    }
+
+    fn mul(self, other: Self) -> Self {
+        self.wrapping_mul(other)
+    }
+
+    fn is_zero(self) -> bool {
+        self == 0
+    }
+}
+
+// plain evaluation of a single domain, recording the witness consumed by inputs
+struct Plaintext<'a, T: Plain> {
+    name: &'static str,
+    wires: Vec<T>,
+    witness: std::slice::Iter<'a, T>,
+    hinted: VecDeque<T>,
+    expanded: Vec<T>,
+}
+
+impl<'a, T: Plain> Plaintext<'a, T> {
+    fn new(name: &'static str, witness: &'a [T]) -> Self {
+        Plaintext {
+            name,
+            wires: vec![],
+            witness: witness.iter(),
+            hinted: VecDeque::new(),
+            expanded: Vec::with_capacity(witness.len()),
+        }
+    }
+
+    fn get(&self, wire: usize) -> T {
+        self.wires.get(wire).copied().unwrap_or_default()
+    }
+
+    fn set(&mut self, wire: usize, value: T) {
+        if wire >= self.wires.len() {
+            self.wires.resize(wire + 1, T::default());
+        }
+        self.wires[wire] = value;
+    }
+
+    // the witness holds a value for every input outside the hints (checked before evaluation)
+    fn input(&mut self, hint: Option<&Hint>) -> Result<T> {
+        let value = match hint {
+            Some(hint) => self.hinted.pop_front().ok_or_else(|| Error::Hint {
+                range: hint.range.clone(),
+                reason: format!("returned too few {} values", self.name),
+            })?,
+            None => *self
+                .witness
+                .next()
+                .expect("the witness length is checked before evaluation"),
+        };
+        self.expanded.push(value);
+        Ok(value)
+    }
+
+    fn step(&mut self, idx: usize, hint: Option<&Hint>, op: &Operation<T>) -> Result<()> {
+        match *op {
+            Operation::Input(dst) => {
+                let value = self.input(hint)?;
+                self.set(dst, value);
+            }
+            Operation::Random(dst) => self.set(dst, T::default()),
+            Operation::Add(dst, src1, src2) => self.set(dst, self.get(src1).add(self.get(src2))),
+            Operation::Sub(dst, src1, src2) => self.set(dst, self.get(src1).sub(self.get(src2))),
+            Operation::Mul(dst, src1, src2) => self.set(dst, self.get(src1).mul(self.get(src2))),
+            Operation::AddConst(dst, src, c) => self.set(dst, self.get(src).add(c)),
+            Operation::SubConst(dst, src, c) => self.set(dst, self.get(src).sub(c)),
+            Operation::MulConst(dst, src, c) => self.set(dst, self.get(src).mul(c)),
+            Operation::AssertZero(src) => {
+                if !self.get(src).is_zero() {
+                    return Err(Error::Unsatisfied {
+                        at: format!("instruction {}", idx),
+                        domain: self.name,
+                        wire: src,
+                    });
+                }
+            }
+            Operation::Const(dst, c) => self.set(dst, c),
+        }
+        Ok(())
+    }
+}
+
+/// Evaluates a program in the clear, operation by operation, checking its assertions:
+/// the evaluator of `expand_witness` (with hints) and `proof::evaluate` (without).
+///
+/// The witness must hold one value for every `Input` outside the hint ranges.
+/// Unassigned (and `Random`) wires read as zero.
+pub(crate) struct Evaluator<'a> {
+    gf2: Plaintext<'a, bool>,
+    z64: Plaintext<'a, u64>,
+    hints: Peekable<slice::Iter<'a, Hint>>,
+    active: Option<&'a Hint>,
+}
+
+impl<'a> Evaluator<'a> {
+    /// Hint ranges must be sorted and disjoint.
+    pub(crate) fn new(wit_gf2: &'a [bool], wit_z64: &'a [u64], hints: &'a [Hint]) -> Self {
+        Evaluator {
+            gf2: Plaintext::new("gf2", wit_gf2),
+            z64: Plaintext::new("z64", wit_z64),
+            hints: hints.iter().peekable(),
+            active: None,
+        }
+    }
+
+    fn unused(&self, hint: &Hint) -> Result<()> {
+        if self.gf2.hinted.is_empty() && self.z64.hinted.is_empty() {
+            return Ok(());
+        }
+        Err(Error::Hint {
+            range: hint.range.clone(),
+            reason: "returned unused values".to_string(),
+        })
+    }
+
+    /// Evaluates the operation at index `idx` of the program.
+    pub(crate) fn step(&mut self, idx: usize, op: &CombineOperation) -> Result<()> {
+        // leave the current hint range
+        if let Some(hint) = self.active {
+            if idx >= hint.range.end {
+                self.unused(hint)?;
+                self.active = None;
+            }
+        }
+
+        // enter the next hint range (a hint for an empty range covers no input: it is skipped)
+        while let Some(hint) = self.hints.next_if(|hint| hint.range.start == idx) {
+            if hint.range.is_empty() {
+                continue;
+            }
+            let values = (hint.compute)(&HintWires {
+                gf2: &self.gf2.wires,
+                z64: &self.z64.wires,
+            });
+            self.gf2.hinted = values.gf2.into();
+            self.z64.hinted = values.z64.into();
+            self.active = Some(hint);
+            break;
+        }
+
+        match op {
+            CombineOperation::GF2(op) => self.gf2.step(idx, self.active, op),
+            CombineOperation::Z64(op) => self.z64.step(idx, self.active, op),
+            CombineOperation::B2A(dst, src) => {
+                let mut value = 0u64;
+                for i in 0..BIT_SIZE {
+                    value |= (self.gf2.get(src + i) as u64) << i;
+                }
+                self.z64.set(*dst, value);
+                Ok(())
+            }
+            CombineOperation::SizeHint(_, _) => Ok(()),
+        }
+    }
+
+    pub(crate) fn gf2(&self, wire: usize) -> bool {
+        self.gf2.get(wire)
+    }
+
+    pub(crate) fn z64(&self, wire: usize) -> u64 {
+        self.z64.get(wire)
+    }
+
+    pub(crate) fn set_gf2(&mut self, wire: usize, value: bool) {
+        self.gf2.set(wire, value)
+    }
+
+    pub(crate) fn set_z64(&mut self, wire: usize, value: u64) {
+        self.z64.set(wire, value)
+    }
+
+    /// Ends the evaluation, returns the witness consumed by the inputs (see `expand_witness`).
+    pub(crate) fn finish(self) -> Result<(Vec<bool>, Vec<u64>)> {
+        // a hint at the end of the program
+        if let Some(hint) = self.active {
+            self.unused(hint)?;
+        }
+        Ok((self.gf2.expanded, self.z64.expanded))
+    }
+}
+
+/// Evaluates the program, calling the hints to compute the witness of their ranges.
+///
+/// Returns the complete (gf2, z64) witness in the order consumed by the prover:
+/// the values from `wit_gf2`/`wit_z64` (one per `Input` outside the hint ranges) interleaved
+/// with the values computed by the hints. Hint ranges must be sorted and disjoint.
+pub fn expand_witness(
+    program: &[CombineOperation],
+    hints: &[Hint],
+    wit_gf2: &[bool],
+    wit_z64: &[u64],
+) -> Result<(Vec<bool>, Vec<u64>)> {
+    for pair in hints.windows(2) {
+        if pair[1].range.start < pair[0].range.end {
+            return Err(Error::Hint {
+                range: pair[1].range.clone(),
+                reason: format!("not sorted after, or overlaps, {:?}", pair[0].range),
+            });
+        }
+    }
+    if let Some(hint) = hints.iter().find(|hint| hint.range.start >= program.len()) {
+        return Err(Error::Hint {
+            range: hint.range.clone(),
+            reason: format!("outside the program ({} instructions)", program.len()),
+        });
+    }
+
+    // the witness holds one value for every input outside the hint ranges
+    let (mut gf2_inputs, mut z64_inputs) = (0, 0);
+    let mut ranges = hints.iter().map(|hint| &hint.range).peekable();
+    for (idx, op) in program.iter().enumerate() {
+        while ranges.peek().is_some_and(|range| range.end <= idx) {
+            ranges.next();
+        }
+        if ranges.peek().is_some_and(|range| range.contains(&idx)) {
+            continue;
+        }
+        match op {
+            CombineOperation::GF2(Operation::Input(_)) => gf2_inputs += 1,
+            CombineOperation::Z64(Operation::Input(_)) => z64_inputs += 1,
+            _ => (),
+        }
+    }
+    for (domain, expected, got) in [
+        ("gf2", gf2_inputs, wit_gf2.len()),
+        ("z64", z64_inputs, wit_z64.len()),
+    ] {
+        if expected != got {
+            return Err(Error::WitnessLength {
+                domain,
+                expected,
+                got,
+            });
+        }
+    }
+
+    let mut evaluator = Evaluator::new(wit_gf2, wit_z64, hints);
+    for (idx, op) in program.iter().enumerate() {
+        evaluator.step(idx, op)?;
+    }
+    evaluator.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::largest_wires;
+    use crate::proof::Proof;
+
+    #[test]
+    fn test_hint_bit_decomposition() {
+        // x = a * b, the bits of x are computed by a hint and recomposed with B2A
+        let mut program = vec![
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Input(1)),
+            CombineOperation::Z64(Operation::Mul(2, 0, 1)),
+        ];
+        let start = program.len();
+        for i in 0..64 {
+            program.push(CombineOperation::GF2(Operation::Input(i)));
+        }
+        let end = program.len();
+        program.push(CombineOperation::B2A(3, 0));
+        program.push(CombineOperation::Z64(Operation::Sub(4, 3, 2)));
+        program.push(CombineOperation::Z64(Operation::AssertZero(4)));
+
+        let hints = vec![Hint::new(start..end, |wires: &HintWires| {
+            let x = wires.z64(2);
+            HintValues {
+                gf2: (0..64).map(|i| (x >> i) & 1 == 1).collect(),
+                z64: vec![],
+            }
+        })];
+
+        let (wit_gf2, wit_z64) = expand_witness(&program, &hints, &[], &[1234, 5678]).unwrap();
+        assert_eq!(wit_gf2.len(), 64);
+        assert_eq!(wit_z64, vec![1234, 5678]);
+
+        let wire_counts = largest_wires(&program);
+        let program = Arc::new(program);
+        let proof = Proof::new(
+            program.clone(),
+            Arc::new(wit_gf2),
+            Arc::new(wit_z64),
+            wire_counts,
+        );
+        proof.verify(program, wire_counts).unwrap();
+    }
+
+    #[test]
+    fn test_hint_errors() {
+        let program = vec![
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::GF2(Operation::Input(1)),
+            CombineOperation::GF2(Operation::AssertZero(1)),
+        ];
+
+        // too few / too many hinted values
+        let hint = |n: usize| {
+            vec![Hint::new(1..2, move |_: &HintWires| HintValues {
+                gf2: vec![false; n],
+                z64: vec![],
+            })]
+        };
+        assert_eq!(
+            expand_witness(&program, &hint(0), &[true], &[])
+                .unwrap_err()
+                .to_string(),
+            "hint for instructions 1..2: returned too few gf2 values"
+        );
+        assert!(matches!(
+            expand_witness(&program, &hint(2), &[true], &[]),
+            Err(Error::Hint { .. })
+        ));
+        assert_eq!(
+            expand_witness(&program, &hint(1), &[true], &[]).unwrap(),
+            (vec![true, false], vec![])
+        );
+
+        // an empty range covers no input, the next input is read from the witness
+        let empty = vec![Hint::new(1..1, |_: &HintWires| -> HintValues {
+            panic!("hint for an empty range called")
+        })];
+        assert_eq!(
+            expand_witness(&program, &empty, &[true, false], &[]).unwrap(),
+            (vec![true, false], vec![])
+        );
+
+        // the evaluation checks the assertions, and the witness outside the hints
+        assert!(matches!(
+            expand_witness(&program, &[], &[true, true], &[]),
+            Err(Error::Unsatisfied { wire: 1, .. })
+        ));
+        assert!(matches!(
+            expand_witness(&program, &hint(1), &[true, false], &[]),
+            Err(Error::WitnessLength {
+                domain: "gf2",
+                expected: 1,
+                got: 2
+            })
+        ));
+    }
+}
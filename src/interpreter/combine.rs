@@ -7,6 +7,7 @@ use super::single::Instance;
 use crate::algebra::{gf2, z64, Domain};
 use crate::crypto::hash::Hash;
 use crate::interpreter::Wire;
+use crate::proof::{OutputValue, OutputWire};
 use crate::transcript::Transcript;
 use crate::HASH;
 use crate::{CombineOperation, PACKED};
@@ -101,6 +102,10 @@ impl<T1: Transcript<gf2::Domain>, T2: Transcript<z64::Domain>> CombineInstance<T
         (self.gf2, self.z64)
     }
 
+    pub(crate) fn transcripts(&self) -> (&T1, &T2) {
+        (&self.gf2.transcript, &self.z64.transcript)
+    }
+
     pub fn hash(&self) -> [Hash; PACKED] {
         let gf2_hash = self.gf2.transcript.hash();
         let z64_hash = self.z64.transcript.hash();
@@ -117,6 +122,24 @@ impl<T1: Transcript<gf2::Domain>, T2: Transcript<z64::Domain>> CombineInstance<T
         ]
     }
 
+    /// Reveals an output wire: the mask is reconstructed (recording the broadcast).
+    /// Returns the value of the wire in every repetition.
+    pub fn output(&mut self, wire: OutputWire) -> [OutputValue; PACKED] {
+        match wire {
+            OutputWire::GF2(src) => {
+                let wire = &self.gf2.wires[src];
+                let value = self.gf2.transcript.reconstruct(wire.mask) + wire.corr;
+                let bits: [u8; PACKED] = value.into();
+                bits.map(|b| OutputValue::GF2(b != 0))
+            }
+            OutputWire::Z64(src) => {
+                let wire = &self.z64.wires[src];
+                let value = self.z64.transcript.reconstruct(wire.mask) + wire.corr;
+                value.pack.map(OutputValue::Z64)
+            }
+        }
+    }
+
     pub fn step(&mut self, operation: &CombineOperation) {
         match operation {
             CombineOperation::SizeHint(z64, gf2) => {
@@ -129,6 +152,9 @@ impl<T1: Transcript<gf2::Domain>, T2: Transcript<z64::Domain>> CombineInstance<T
             }
             CombineOperation::GF2(op) => self.gf2.step(op),
             CombineOperation::Z64(op) => self.z64.step(op),
+            // B2A(dst, src) converts the gf2 wires src..src+64 (least significant bit first)
+            // into the z64 wire dst: a random r is shared in both domains (an edaBit),
+            // x + r is computed with a gf2 adder and opened, then dst = (x + r) - r in z64.
             CombineOperation::B2A(dst, src) => {
                 let dst = *dst;
                 let src = *src;
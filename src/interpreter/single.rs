@@ -233,19 +233,19 @@ mod test {
         let gate = Operation::Mul(2, 1, 0);
 
         // 1 * 1
-        let actual = test_gate_gf2(&[true, true], gate.clone());
+        let actual = test_gate_gf2(&[true, true], gate);
         let expected: gf2::Recon = gf2::Domain::ONE.into();
 
         assert_eq!(expected, actual);
 
         // 1 * 0
-        let actual = test_gate_gf2(&[true, false], gate.clone());
+        let actual = test_gate_gf2(&[true, false], gate);
         let expected: gf2::Recon = gf2::Domain::ZERO.into();
 
         assert_eq!(expected, actual);
 
         // 0 * 0
-        let actual = test_gate_gf2(&[false, false], gate.clone());
+        let actual = test_gate_gf2(&[false, false], gate);
         let expected: gf2::Recon = gf2::Domain::ZERO.into();
 
         assert_eq!(expected, actual);
@@ -256,13 +256,13 @@ mod test {
         let gate = Operation::MulConst(1, 0, gf2::Domain::ONE.into());
 
         // 1 * 1
-        let actual = test_gate_gf2(&[true], gate.clone());
+        let actual = test_gate_gf2(&[true], gate);
         let expected: gf2::Recon = gf2::Domain::ONE.into();
 
         assert_eq!(expected, actual);
 
         // 0 * 1
-        let actual = test_gate_gf2(&[false], gate.clone());
+        let actual = test_gate_gf2(&[false], gate);
         let expected: gf2::Recon = gf2::Domain::ZERO.into();
 
         assert_eq!(expected, actual);
@@ -270,13 +270,13 @@ mod test {
         let gate = Operation::MulConst(1, 0, gf2::Domain::ZERO.into());
 
         // 1 * 0
-        let actual = test_gate_gf2(&[true], gate.clone());
+        let actual = test_gate_gf2(&[true], gate);
         let expected: gf2::Recon = gf2::Domain::ZERO.into();
 
         assert_eq!(expected, actual);
 
         // 0 * 0
-        let actual = test_gate_gf2(&[false], gate.clone());
+        let actual = test_gate_gf2(&[false], gate);
         let expected: gf2::Recon = gf2::Domain::ZERO.into();
 
         assert_eq!(expected, actual);
@@ -287,19 +287,19 @@ mod test {
         let gate = Operation::Add(2, 1, 0);
 
         // 1 + 1
-        let actual = test_gate_gf2(&[true, true], gate.clone());
+        let actual = test_gate_gf2(&[true, true], gate);
         let expected: gf2::Recon = gf2::Domain::ZERO.into();
 
         assert_eq!(expected, actual);
 
         // 1 + 0
-        let actual = test_gate_gf2(&[true, false], gate.clone());
+        let actual = test_gate_gf2(&[true, false], gate);
         let expected: gf2::Recon = gf2::Domain::ONE.into();
 
         assert_eq!(expected, actual);
 
         // 0 + 0
-        let actual = test_gate_gf2(&[false, false], gate.clone());
+        let actual = test_gate_gf2(&[false, false], gate);
         let expected: gf2::Recon = gf2::Domain::ZERO.into();
 
         assert_eq!(expected, actual);
@@ -310,13 +310,13 @@ mod test {
         let gate = Operation::AddConst(1, 0, gf2::Domain::ONE.into());
 
         // 1 + 1
-        let actual = test_gate_gf2(&[true], gate.clone());
+        let actual = test_gate_gf2(&[true], gate);
         let expected: gf2::Recon = gf2::Domain::ZERO.into();
 
         assert_eq!(expected, actual);
 
         // 0 + 1
-        let actual = test_gate_gf2(&[false], gate.clone());
+        let actual = test_gate_gf2(&[false], gate);
         let expected: gf2::Recon = gf2::Domain::ONE.into();
 
         assert_eq!(expected, actual);
@@ -324,13 +324,13 @@ mod test {
         let gate = Operation::AddConst(1, 0, gf2::Domain::ZERO.into());
 
         // 1 + 0
-        let actual = test_gate_gf2(&[true], gate.clone());
+        let actual = test_gate_gf2(&[true], gate);
         let expected: gf2::Recon = gf2::Domain::ONE.into();
 
         assert_eq!(expected, actual);
 
         // 0 + 0
-        let actual = test_gate_gf2(&[false], gate.clone());
+        let actual = test_gate_gf2(&[false], gate);
         let expected: gf2::Recon = gf2::Domain::ZERO.into();
 
         assert_eq!(expected, actual);
@@ -341,19 +341,19 @@ mod test {
         let gate = Operation::Sub(2, 1, 0);
 
         // 1 - 1
-        let actual = test_gate_gf2(&[true, true], gate.clone());
+        let actual = test_gate_gf2(&[true, true], gate);
         let expected: gf2::Recon = gf2::Domain::ZERO.into();
 
         assert_eq!(expected, actual);
 
         // 1 - 0
-        let actual = test_gate_gf2(&[true, false], gate.clone());
+        let actual = test_gate_gf2(&[true, false], gate);
         let expected: gf2::Recon = gf2::Domain::ONE.into();
 
         assert_eq!(expected, actual);
 
         // 0 - 0
-        let actual = test_gate_gf2(&[false, false], gate.clone());
+        let actual = test_gate_gf2(&[false, false], gate);
         let expected: gf2::Recon = gf2::Domain::ZERO.into();
 
         assert_eq!(expected, actual);
@@ -365,31 +365,31 @@ mod test {
 
         // 0 + 0
         let inputs = [0, 0];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0] + inputs[1]).into();
         assert_eq!(expected, actual);
 
         // 0 + 1
         let inputs = [0, 1];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0] + inputs[1]).into();
         assert_eq!(expected, actual);
 
         // 400 + 20
         let inputs = [400, 20];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0] + inputs[1]).into();
         assert_eq!(expected, actual);
 
         // almost wrapping
         let inputs = [u64::MAX - 20, 20];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0].wrapping_add(inputs[1])).into();
         assert_eq!(expected, actual);
 
         // wrapping
         let inputs = [u64::MAX - 1, 20];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0].wrapping_add(inputs[1])).into();
         assert_eq!(expected, actual);
     }
@@ -411,54 +411,78 @@ mod test {
         let gate = Operation::AddConst(1, 0, 20);
         // 400 + 20
         let inputs = [400];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = 420.into();
         assert_eq!(expected, actual);
 
         // almost wrapping
         let inputs = [u64::MAX - 20];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0].wrapping_add(20)).into();
         assert_eq!(expected, actual);
 
         // wrapping
         let inputs = [u64::MAX - 1];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0].wrapping_add(20)).into();
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn test_subc_z64() {
+        let gate = Operation::SubConst(1, 0, 20);
+
+        // 400 - 20
+        let inputs = [400];
+        let actual = test_gate_z64(&inputs, gate);
+        let expected: z64::Recon = 380.into();
+        assert_eq!(expected, actual);
+
+        // wrapping
+        let inputs = [10];
+        let actual = test_gate_z64(&inputs, gate);
+        let expected: z64::Recon = (inputs[0].wrapping_sub(20)).into();
+        assert_eq!(expected, actual);
+    }
+
+    #[test]
+    fn test_const_z64() {
+        let actual = test_gate_z64(&[], Operation::Const(0, u64::MAX));
+        let expected: z64::Recon = u64::MAX.into();
+        assert_eq!(expected, actual);
+    }
+
     #[test]
     fn test_mul_z64() {
         let gate = Operation::Mul(2, 1, 0);
 
         // 0 * 0
         let inputs = [0, 0];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0] * inputs[1]).into();
         assert_eq!(expected, actual);
 
         // 0 * 1
         let inputs = [0, 1];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0] * inputs[1]).into();
         assert_eq!(expected, actual);
 
         // 400 * 20
         let inputs = [400, 20];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0] * inputs[1]).into();
         assert_eq!(expected, actual);
 
         // almost wrapping
         let inputs = [u64::MAX - 20, 20];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0].wrapping_mul(inputs[1])).into();
         assert_eq!(expected, actual);
 
         // wrapping
         let inputs = [u64::MAX - 1, 20];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0].wrapping_mul(inputs[1])).into();
         assert_eq!(expected, actual);
     }
@@ -480,19 +504,19 @@ mod test {
         let gate = Operation::MulConst(1, 0, 20);
         // 400 * 20
         let inputs = [400];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = 8000.into();
         assert_eq!(expected, actual);
 
         // almost wrapping
         let inputs = [u64::MAX - 20];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0].wrapping_mul(20)).into();
         assert_eq!(expected, actual);
 
         // wrapping
         let inputs = [u64::MAX - 1];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0].wrapping_mul(20)).into();
         assert_eq!(expected, actual);
     }
@@ -503,31 +527,31 @@ mod test {
 
         // 0 - 0
         let inputs = [0, 0];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0] - inputs[1]).into();
         assert_eq!(expected, actual);
 
         // 1 - 0
         let inputs = [1, 0];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0] - inputs[1]).into();
         assert_eq!(expected, actual);
 
         // 400 - 20
         let inputs = [400, 20];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0] - inputs[1]).into();
         assert_eq!(expected, actual);
 
         // almost wrapping
         let inputs = [20, 20];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0].wrapping_sub(inputs[1])).into();
         assert_eq!(expected, actual);
 
         // wrapping
         let inputs = [10, 20];
-        let actual = test_gate_z64(&inputs, gate.clone());
+        let actual = test_gate_z64(&inputs, gate);
         let expected: z64::Recon = (inputs[0].wrapping_sub(inputs[1])).into();
         assert_eq!(expected, actual);
     }
@@ -3,15 +3,33 @@
 extern crate test;
 
 pub use algebra::*;
+pub use capabilities::capabilities;
+pub use error::Error;
 pub use mcircuit::{evaluate_composite_program, largest_wires};
 pub use mcircuit::{CombineOperation, Operation};
 
 pub mod algebra;
+pub mod capabilities;
 pub mod crypto;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod gadgets;
 pub mod generator;
+pub mod hints;
 pub mod interpreter;
+pub mod parsers;
+pub mod passes;
+pub mod profile;
 pub mod proof;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod regions;
+#[cfg(feature = "service")]
+pub mod service;
 pub mod transcript;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 // players in MPC protocol
 pub const PLAYERS: usize = 8;
@@ -0,0 +1,111 @@
+use std::fmt;
+use std::io;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use indicatif::{ProgressBar, ProgressStyle};
+use reverie::proof::PROGRESS_TARGET;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::filter::{filter_fn, LevelFilter};
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Renders the progress events of the prover/verifier (see `PROGRESS_TARGET`) as a
+/// progress bar on stderr: passes over the program completed, gates and multiplications
+/// processed, and the current phase.
+struct ProgressLayer {
+    bar: ProgressBar,
+    gates: AtomicU64,
+    multiplications: AtomicU64,
+}
+
+#[derive(Default)]
+struct ProgressFields {
+    total_passes: Option<u64>,
+    passes: u64,
+    gates: u64,
+    multiplications: u64,
+    phase: Option<String>,
+}
+
+impl Visit for ProgressFields {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        match field.name() {
+            "total_passes" => self.total_passes = Some(value),
+            "passes" => self.passes = value,
+            "gates" => self.gates = value,
+            "multiplications" => self.multiplications = value,
+            _ => (),
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "phase" {
+            self.phase = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+impl<S: Subscriber> Layer<S> for ProgressLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        if event.metadata().target() != PROGRESS_TARGET {
+            return;
+        }
+        let mut fields = ProgressFields::default();
+        event.record(&mut fields);
+        if let Some(total) = fields.total_passes {
+            // a new proof/verification starts
+            self.gates.store(0, Ordering::Relaxed);
+            self.multiplications.store(0, Ordering::Relaxed);
+            self.bar.reset();
+            self.bar.set_length(total);
+            self.bar.set_message("0 gates");
+            return;
+        }
+        let gates = self.gates.fetch_add(fields.gates, Ordering::Relaxed) + fields.gates;
+        let multiplications = self
+            .multiplications
+            .fetch_add(fields.multiplications, Ordering::Relaxed)
+            + fields.multiplications;
+        self.bar.set_message(format!(
+            "{} gates, {} multiplications ({})",
+            gates,
+            multiplications,
+            fields.phase.as_deref().unwrap_or("?")
+        ));
+        self.bar.inc(fields.passes);
+        if self.bar.position() == self.bar.length().unwrap_or(0) {
+            self.bar.finish_and_clear();
+        }
+    }
+}
+
+/// Installs the global `tracing` subscriber: log lines at or above `level` on stderr,
+/// and (unless disabled, or stderr is not a terminal) a progress bar.
+pub fn init(level: LevelFilter, progress: bool) {
+    let bar = if !progress {
+        ProgressBar::hidden()
+    } else {
+        ProgressBar::new(0)
+    };
+    bar.set_style(
+        ProgressStyle::with_template("[{elapsed_precise}] {bar:40} {pos}/{len} passes, {msg}")
+            .unwrap(),
+    );
+    tracing_subscriber::registry()
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(io::stderr)
+                .with_filter(filter_fn(move |meta| {
+                    meta.target() != PROGRESS_TARGET && *meta.level() <= level
+                })),
+        )
+        .with(ProgressLayer {
+            bar,
+            gates: AtomicU64::new(0),
+            multiplications: AtomicU64::new(0),
+        })
+        .init();
+}
@@ -1,49 +1,78 @@
 #![allow(clippy::explicit_auto_deref)]
 
-use std::fs::File;
+use std::fs::{self, File};
 use std::io;
-use std::io::{BufReader, BufWriter};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::marker::PhantomData;
 use std::mem;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::exit;
 use std::sync::Arc;
 
 use async_std::task;
-use clap::{value_parser, Arg, Command};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use memmap2::Mmap;
 use num_traits::Zero;
+use rayon::prelude::*;
 use reverie::algebra::*;
-use reverie::proof::Proof;
+use reverie::crypto::ro::RandomOracle;
+use reverie::parsers::{read_program, PROGRAM_MAGIC};
+use reverie::passes::{estimate, stats, validate};
+use reverie::profile;
+use reverie::proof::{
+    evaluate, Output, OutputValue, OutputWire, Outputs, ProgramMap, ProgramSource, Proof,
+    ProverConfig, Statement,
+};
 use reverie::CombineOperation;
-use reverie::{evaluate_composite_program, largest_wires};
+use reverie::{capabilities, largest_wires, Error};
+use tracing_subscriber::filter::LevelFilter;
 
+use report::Report;
+
+mod bench;
+mod convert;
+mod logging;
+mod report;
 mod witness;
 
 mod built_info {
     include!(concat!(env!("OUT_DIR"), "/built.rs"));
 }
 
+/// A source of witness data: a file, stdin or an in-memory buffer.
+pub type WitnessReader = Box<dyn BufRead + Send>;
+
 pub trait Parser<E>: Sized {
-    fn new(reader: BufReader<File>) -> io::Result<Self>;
+    fn new(reader: WitnessReader) -> io::Result<Self>;
 
     fn next(&mut self) -> io::Result<Option<E>>;
 }
 
+// The witness, parsed into memory: the prover takes it as a vector, hence (unlike the program,
+// see `ProgramMap`) it is not paged in lazily. Mapping the files only saves the copies
+// through a read buffer.
 enum FileStreamer<E, P: Parser<E>> {
     Memory(Arc<Vec<E>>, PhantomData<P>),
 }
 
 impl<E, P: Parser<E>> FileStreamer<E, P> {
-    fn new(path: &str) -> io::Result<Self> {
-        let file = File::open(path)?;
-        let meta = file.metadata()?;
-
-        // parse once and load into memory
-        let reader = BufReader::new(file);
-        let mut contents: Vec<E> = Vec::with_capacity(meta.len() as usize / mem::size_of::<E>());
-        let mut parser = P::new(reader)?;
-        while let Some(elem) = parser.next()? {
-            contents.push(elem)
+    /// Parse the files in order, logically concatenating their contents ("-" reads stdin)
+    fn new<T: AsRef<Path>>(paths: &[T]) -> io::Result<Self> {
+        let mut contents: Vec<E> = vec![];
+        for path in paths {
+            // parse once (from the mapped file) and load into memory, see above
+            let reader: WitnessReader = if path.as_ref() == Path::new("-") {
+                Box::new(BufReader::new(io::stdin()))
+            } else {
+                let file = File::open(path)?;
+                let map = unsafe { Mmap::map(&file)? };
+                contents.reserve(map.len() / mem::size_of::<E>());
+                Box::new(io::Cursor::new(map))
+            };
+            let mut parser = P::new(reader)?;
+            while let Some(elem) = parser.next()? {
+                contents.push(elem)
+            }
         }
         Ok(FileStreamer::Memory(Arc::new(contents), PhantomData))
     }
@@ -55,137 +84,678 @@ impl<E, P: Parser<E>> FileStreamer<E, P> {
     }
 }
 
+/// Loads a program in the compact binary format or (otherwise) as a bincode encoded vector.
+fn load_program(path: &Path) -> io::Result<Vec<CombineOperation>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    if reader.fill_buf()?.starts_with(&PROGRAM_MAGIC) {
+        return read_program(reader);
+    }
+    bincode::deserialize_from(reader).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+// parses the non-empty lines of a file, skipping comments (from '#' to the end of the line)
+fn load_records<T>(
+    path: &Path,
+    parse: impl Fn(&[&str]) -> Result<T, &'static str>,
+) -> io::Result<Vec<T>> {
+    let mut records = vec![];
+    for (idx, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+        let line = line?;
+        let fields: Vec<&str> = line.split('#').next().unwrap().split_whitespace().collect();
+        if fields.is_empty() {
+            continue;
+        }
+        records.push(parse(&fields).map_err(|msg| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("line {}: {}", idx + 1, msg),
+            )
+        })?);
+    }
+    Ok(records)
+}
+
+/// Loads the output wires revealed by the proof, one per line: "<label> <gf2|z64> <wire>".
+fn load_outputs(path: Option<&PathBuf>) -> io::Result<Vec<Output>> {
+    let path = match path {
+        Some(path) => path,
+        None => return Ok(vec![]),
+    };
+    load_records(path, |fields| match *fields {
+        [label, domain, wire] => {
+            let wire: usize = wire.parse().map_err(|_| "invalid wire index")?;
+            match domain {
+                "gf2" => Ok(Output::gf2(label, wire)),
+                "z64" => Ok(Output::z64(label, wire)),
+                _ => Err("the domain must be \"gf2\" or \"z64\""),
+            }
+        }
+        _ => Err("expected \"<label> <gf2|z64> <wire>\""),
+    })
+}
+
+/// Loads the expected values of the outputs, one per line: "<label> <value>".
+fn load_expected(path: Option<&PathBuf>) -> io::Result<Vec<(String, u64)>> {
+    let path = match path {
+        Some(path) => path,
+        None => return Ok(vec![]),
+    };
+    load_records(path, |fields| match *fields {
+        [label, value] => {
+            let value = value.parse().map_err(|_| "invalid output value")?;
+            Ok((label.to_string(), value))
+        }
+        _ => Err("expected \"<label> <value>\""),
+    })
+}
+
 async fn prove<WP: Parser<bool> + Send + 'static>(
-    program_path: &str,
-    witness_path: &str,
-    proof_path: &str,
+    program_path: &Path,
+    witness_paths: &[&PathBuf],
+    proof_path: &Path,
+    outputs: &[Output],
+    config: &ProverConfig,
+    report: &mut Report,
 ) -> io::Result<Result<(), String>> {
-    // open and parse program
-    let program_file = File::open(program_path)?;
-    let program_reader = BufReader::new(program_file);
-    let program: Vec<CombineOperation> = bincode::deserialize_from(program_reader).unwrap();
-
     // open and parse witness
-    let witness: FileStreamer<_, WP> = FileStreamer::new(witness_path)?;
+    let witness: FileStreamer<_, WP> = FileStreamer::new(witness_paths)?;
 
-    // Create Proof
-    println!("Evaluating program in ~zero knowledge~");
-    let wire_counts = largest_wires(program.as_slice());
-    let proof = Proof::new(
-        Arc::new(program),
-        witness.rewind(),
-        Arc::new(vec![]),
-        wire_counts,
-    );
-
-    // Write proof to file
-    let proof_file = File::create(proof_path)?;
-    let proof_writer = BufWriter::new(proof_file);
-    if bincode::serialize_into(proof_writer, &proof).is_ok() {
-        Ok(Ok(()))
-    } else {
-        Ok(Err("Could not serialize Proof".to_string()))
+    // programs in the compact binary format are mapped and streamed on every pass
+    let proof = match ProgramMap::open(program_path) {
+        Ok(source) => {
+            report.status("Evaluating program in ~zero knowledge~ (streaming)");
+            let wire_counts = source.wire_counts()?;
+            report.time("prove", || {
+                Proof::from_source(
+                    &source,
+                    outputs,
+                    witness.rewind(),
+                    Arc::new(vec![]),
+                    wire_counts,
+                    config,
+                )
+            })
+        }
+        Err(_) => {
+            let program = load_program(program_path)?;
+            report.status("Evaluating program in ~zero knowledge~");
+            let wire_counts = largest_wires(program.as_slice());
+            if let Err(e) = validate(&program, outputs, wire_counts) {
+                return Ok(Err(e.to_string()));
+            }
+            report.time("prove", || {
+                Proof::from_source(
+                    program.as_slice(),
+                    outputs,
+                    witness.rewind(),
+                    Arc::new(vec![]),
+                    wire_counts,
+                    config,
+                )
+            })
+        }
+    };
+    let proof = match proof {
+        Ok(proof) => proof,
+        Err(Error::Io(e)) => return Err(e),
+        Err(e) => return Ok(Err(format!("Cannot prove: {}", e))),
+    };
+
+    match write_proof(proof_path, &proof) {
+        Ok(bytes) => {
+            report.proof_bytes = Some(bytes);
+            Ok(Ok(()))
+        }
+        Err(e) => Ok(Err(format!(
+            "Could not write proof to {}: {}",
+            proof_path.display(),
+            e
+        ))),
     }
 }
 
-async fn verify<WP: Parser<bool> + Send + 'static>(
-    program_path: &str,
-    proof_path: &str,
-) -> io::Result<Result<(), String>> {
-    // open and parse program
-    let program_file = File::open(program_path)?;
-    let program_reader = BufReader::new(program_file);
-    let program: Vec<CombineOperation> = bincode::deserialize_from(program_reader).unwrap();
+// writes the proof (creating missing parent directories), returns its size
+fn write_proof(proof_path: &Path, proof: &Proof) -> io::Result<u64> {
+    if let Some(dir) = proof_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut writer = BufWriter::new(File::create(proof_path)?);
+    proof.serialize(&mut writer).map_err(io::Error::other)?;
+    writer.flush()?;
+    Ok(proof.serialized_size())
+}
 
+/// Verifies a proof (no witness required) and records the revealed outputs.
+fn verify_proof<S: ProgramSource + ?Sized>(
+    source: &S,
+    wire_counts: (usize, usize),
+    proof_path: &Path,
+    outputs: &[Output],
+    expected: &[(String, u64)],
+    report: &mut Report,
+) -> io::Result<Result<(), String>> {
     // Deserialize the proof
-    let proof_file = File::open(proof_path)?;
-    let proof_reader = BufReader::new(proof_file);
-    let proof: Proof = bincode::deserialize_from(proof_reader).unwrap();
+    let proof = load_proof(proof_path)?;
+    report.proof_bytes = Some(proof.serialized_size());
 
-    // Verify the proof
-    println!("Verifying Proof");
-    let wire_counts = largest_wires(program.as_slice());
-    if proof.verify(Arc::new(program), wire_counts) {
-        Ok(Ok(()))
-    } else {
-        Ok(Err("Unverifiable Proof".to_string()))
+    // Verify the proof: it must prove the expected statement
+    let expected = expected_values(outputs, expected);
+    let values = report.time("verify", || {
+        proof.verify_source_expected(source, outputs, &expected, wire_counts)
+    });
+    match values {
+        Ok(values) => {
+            report.outputs(&values);
+            Ok(Ok(()))
+        }
+        Err(Error::Io(e)) => Err(e),
+        Err(e @ (Error::OutputMissing { .. } | Error::OutputMismatch { .. })) => {
+            Ok(Err(format!("Valid proof of another statement: {}", e)))
+        }
+        Err(e) => Ok(Err(format!("Unverifiable Proof: {}", e))),
     }
 }
 
-async fn oneshot<WP: Parser<gf2::Recon> + Send + 'static>(
-    program_path: &str,
-    witness_path: &str,
+/// The expected values in the domains of the outputs (gf2 outputs expect 0 or 1).
+fn expected_values(outputs: &[Output], expected: &[(String, u64)]) -> Outputs {
+    expected
+        .iter()
+        .map(|(label, value)| {
+            let gf2 = outputs
+                .iter()
+                .any(|output| output.label == *label && matches!(output.wire, OutputWire::GF2(_)));
+            let value = match *value {
+                0 | 1 if gf2 => OutputValue::GF2(*value == 1),
+                value => OutputValue::Z64(value),
+            };
+            (label.clone(), value)
+        })
+        .collect()
+}
+
+// a single proof prints its outputs, several are verified in parallel (one line per proof)
+fn verify_all<S: ProgramSource + ?Sized>(
+    source: &S,
+    proof_paths: &[&PathBuf],
+    outputs: &[Output],
+    expected: &[(String, u64)],
+    json: bool,
 ) -> io::Result<()> {
+    let wire_counts = source.wire_counts()?;
+    if let [proof_path] = proof_paths {
+        let mut report = Report::new(json);
+        report.status("Verifying Proof");
+        let res = verify_proof(
+            source,
+            wire_counts,
+            proof_path,
+            outputs,
+            expected,
+            &mut report,
+        );
+        report.finish(res);
+        return Ok(());
+    }
+
+    let reports: Vec<Report> = proof_paths
+        .par_iter()
+        .map(|proof_path| {
+            let mut report = Report::new(true); // quiet
+            let res = verify_proof(
+                source,
+                wire_counts,
+                proof_path,
+                outputs,
+                expected,
+                &mut report,
+            );
+            report.record(res);
+            report
+        })
+        .collect();
+    report::finish_batch(proof_paths, reports, json);
+    Ok(())
+}
+
+/// Verifies proofs against the program, which is loaded once.
+async fn verify(
+    program_path: &Path,
+    proof_paths: &[&PathBuf],
+    outputs: &[Output],
+    expected: &[(String, u64)],
+    json: bool,
+) -> io::Result<()> {
+    match ProgramMap::open(program_path) {
+        Ok(source) => verify_all(&source, proof_paths, outputs, expected, json),
+        Err(_) => {
+            let program = load_program(program_path)?;
+            verify_all(program.as_slice(), proof_paths, outputs, expected, json)
+        }
+    }
+}
+
+fn load_proof(path: &Path) -> io::Result<Proof> {
+    let reader = BufReader::new(File::open(path)?);
+    Proof::deserialize(reader).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn inspect(proof_path: &Path) -> io::Result<()> {
+    print!("{}", load_proof(proof_path)?.info());
+    Ok(())
+}
+
+async fn oneshot<WP: Parser<gf2::Recon> + Send + 'static>(
+    program_path: &Path,
+    witness_paths: &[&PathBuf],
+    outputs: &[Output],
+    report: &mut Report,
+) -> io::Result<Result<(), String>> {
     // open and parse program
-    let file = File::open(program_path)?;
-    let reader = BufReader::new(file);
-    let program: Vec<CombineOperation> = bincode::deserialize_from(reader).unwrap();
+    let program = load_program(program_path)?;
 
     // open and parse witness
-    let witness: FileStreamer<_, WP> = FileStreamer::new(witness_path)?;
+    let witness: FileStreamer<_, WP> = FileStreamer::new(witness_paths)?;
     let witness: Vec<bool> = witness.rewind().iter().map(|r| !r.is_zero()).collect();
 
-    println!("Evaluating program in cleartext");
-    evaluate_composite_program(program.as_slice(), &witness, &[]);
-
-    Ok(())
+    // evaluate the program in the clear, checking the witness without proving
+    let wire_counts = largest_wires(program.as_slice());
+    let statement = Statement {
+        outputs,
+        ..Statement::default()
+    };
+    let values = report.time("evaluate", || {
+        evaluate(program.as_slice(), &statement, &witness, &[], wire_counts)
+    });
+    match values {
+        Ok(values) => {
+            report.outputs(&values);
+            Ok(Ok(()))
+        }
+        Err(Error::Io(e)) => Err(e),
+        Err(e) => Ok(Err(format!("Invalid witness: {}", e))),
+    }
 }
 
 async fn oneshot_zk<WP: Parser<bool> + Send + 'static>(
-    program_path: &str,
-    witness_path: &str,
+    program_path: &Path,
+    witness_paths: &[&PathBuf],
+    report: &mut Report,
 ) -> io::Result<Result<(), String>> {
     // open and parse program
-    let file = File::open(program_path)?;
-    let reader = BufReader::new(file);
-    let program: Vec<CombineOperation> = bincode::deserialize_from(reader).unwrap();
+    let program = load_program(program_path)?;
 
     // open and parse witness
-    let witness: FileStreamer<_, WP> = FileStreamer::new(witness_path)?;
+    let witness: FileStreamer<_, WP> = FileStreamer::new(witness_paths)?;
 
-    println!("Evaluating program in ~zero knowledge~");
+    report.status("Evaluating program in ~zero knowledge~");
     let wire_counts = largest_wires(program.as_slice());
+    if let Err(e) = validate(&program, &[], wire_counts) {
+        return Ok(Err(e.to_string()));
+    }
 
     let program_arc = Arc::new(program);
 
     // Create the proof
-    let proof = Proof::new(
-        program_arc.clone(),
-        witness.rewind(),
-        Arc::new(vec![]),
-        wire_counts,
-    );
+    let proof = report.time("prove", || {
+        Proof::from_source(
+            program_arc.as_slice(),
+            &[],
+            witness.rewind(),
+            Arc::new(vec![]),
+            wire_counts,
+            &ProverConfig::default(),
+        )
+    });
+    let proof = match proof {
+        Ok(proof) => proof,
+        Err(Error::Io(e)) => return Err(e),
+        Err(e) => return Ok(Err(format!("Cannot prove: {}", e))),
+    };
+    report.proof_bytes = Some(proof.serialized_size());
 
     // Verify the proof
-    if proof.verify(program_arc, wire_counts) {
-        Ok(Ok(()))
-    } else {
-        Ok(Err("Unverifiable Proof".to_string()))
+    match report.time("verify", || proof.verify(program_arc, wire_counts)) {
+        Ok(()) => Ok(Ok(())),
+        Err(e) => Ok(Err(format!("Unverifiable Proof: {}", e))),
     }
 }
 
+async fn profile<WP: Parser<bool> + Send + 'static>(
+    program_path: &Path,
+    witness_paths: &[&PathBuf],
+) -> io::Result<()> {
+    // open and parse program
+    let program = load_program(program_path)?;
+
+    // open and parse witness
+    let witness: FileStreamer<_, WP> = FileStreamer::new(witness_paths)?;
+
+    println!("Profiling program");
+    let wire_counts = largest_wires(program.as_slice());
+    let report = profile::profile(&program, &[], &[], &witness.rewind(), &[], wire_counts);
+    print!("{}", report);
+    Ok(())
+}
+
+fn witness_format_arg() -> Arg {
+    Arg::new("witness-format")
+        .long("witness-format")
+        .help(
+            "The format of the witness files: \"bits\" (one 0/1 character per bit) \
+            \"hex\" (hex strings, every digit is 4 bits, most significant first) \
+            or \"packed\" (u64 little-endian bit count, then 8 bits per byte, \
+            least significant first)",
+        )
+        .value_parser(capabilities::WITNESS_FORMATS)
+        .default_value("bits")
+}
+
+fn expected_output_arg() -> Arg {
+    Arg::new("expected-output")
+        .long("expected-output")
+        .help(
+            "A file with the expected values of the outputs, one \"<label> <value>\" per line: \
+            verification fails if a revealed value differs",
+        )
+        .requires("outputs")
+        .value_parser(value_parser!(PathBuf))
+}
+
+fn seed_arg() -> Arg {
+    Arg::new("seed").long("seed").help(
+        "Derive the prover randomness from this string (hashed) to make the proof \
+            reproducible: for debugging only, the proof is not zero-knowledge w.r.t. the seed",
+    )
+}
+
+// domain separation of the --seed hash
+const CTX_SEED: &str = "prover seed";
+
+// prover settings from the command line
+fn prover_config(matches: &ArgMatches) -> ProverConfig {
+    ProverConfig {
+        seed: matches.get_one::<String>("seed").map(|seed| {
+            // hashed with the hash function of the build (see `crypto::hash::Backend`)
+            let mut bytes = [0u8; 32];
+            RandomOracle::new(CTX_SEED, seed.as_bytes()).fill(&mut bytes);
+            bytes
+        }),
+        ..ProverConfig::default()
+    }
+}
+
+fn outputs_arg() -> Arg {
+    Arg::new("outputs")
+        .long("outputs")
+        .help(
+            "A file listing the output wires revealed by the proof, one \
+            \"<label> <gf2|z64> <wire>\" per line (the prover and verifier must use the same)",
+        )
+        .value_parser(value_parser!(PathBuf))
+}
+
 fn app() -> Command {
     Command::new("Speed Reverie")
         .about("Gotta go fast")
+        .subcommand_negates_reqs(true)
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help(
+                    "Print the result of proving/verifying as a JSON object \
+                    (success, error, timings, proof size and outputs)",
+                )
+                .global(true)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("log-level")
+                .long("log-level")
+                .help("Log messages of this level and above to stderr")
+                .global(true)
+                .value_parser(["off", "error", "warn", "info", "debug", "trace"])
+                .default_value("warn"),
+        )
+        .arg(
+            Arg::new("no-progress")
+                .long("no-progress")
+                .help(
+                    "Do not show the progress of proving/verifying \
+                    (only shown when stderr is a terminal)",
+                )
+                .global(true)
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("threads")
+                .long("threads")
+                .help(
+                    "Number of threads used by the prover/verifier and the async runtime \
+                    (defaults to the number of CPUs)",
+                )
+                .global(true)
+                .value_parser(value_parser!(u64).range(1..)),
+        )
+        .subcommand(
+            Command::new("prove")
+                .about("Prove knowledge of a witness satisfying the program")
+                .arg(
+                    Arg::new("program")
+                        .help("The program (bincode or compact binary format)")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("witness")
+                        .help(
+                            "The witness files, concatenated in order \
+                            (\"-\" reads the witness from stdin)",
+                        )
+                        .required(true)
+                        .num_args(1..)
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("output")
+                        .short('o')
+                        .long("output")
+                        .visible_alias("out")
+                        .help("Where to write the proof (missing directories are created)")
+                        .default_value("proof.bin")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(witness_format_arg())
+                .arg(outputs_arg())
+                .arg(seed_arg()),
+        )
+        .subcommand(
+            Command::new("verify")
+                .about("Verify a proof against the program")
+                .arg(
+                    Arg::new("program")
+                        .help("The program (bincode or compact binary format)")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("proof")
+                        .help(
+                            "The proofs to verify: several proofs are verified in parallel, \
+                            printing one line per proof",
+                        )
+                        .required(true)
+                        .num_args(1..)
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(outputs_arg())
+                .arg(expected_output_arg()),
+        )
+        .subcommand(
+            Command::new("convert")
+                .about("Translate a circuit between formats")
+                .arg(
+                    Arg::new("input")
+                        .help("The circuit to convert")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("output")
+                        .help("Where to write the converted circuit")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("from")
+                        .long("from")
+                        .help("The format of the input (guessed from the extension by default)")
+                        .value_parser(convert::INPUT_FORMATS),
+                )
+                .arg(
+                    Arg::new("to")
+                        .long("to")
+                        .help("The format of the output (guessed from the extension by default)")
+                        .value_parser(convert::OUTPUT_FORMATS),
+                )
+                .arg(
+                    Arg::new("instance")
+                        .long("instance")
+                        .help("The instance of a SIEVE IR relation")
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(
+                    Arg::new("validate")
+                        .long("validate")
+                        .help("Check the program (and its outputs) before writing it")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("write-outputs")
+                        .long("write-outputs")
+                        .help(
+                            "Write the output wires declared by the input, for use with --outputs",
+                        )
+                        .value_parser(value_parser!(PathBuf)),
+                ),
+        )
+        .subcommand(
+            Command::new("stats")
+                .about("Print gate counts, multiplicative depth and peak live wires of a program")
+                .arg(
+                    Arg::new("program")
+                        .help("The program (bincode or compact binary format)")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf)),
+                ),
+        )
+        .subcommand(
+            Command::new("estimate")
+                .about(
+                    "Predict the proof size, prover memory and prover time of a program \
+                    for every parameter preset (without proving)",
+                )
+                .arg(
+                    Arg::new("program")
+                        .help("The program (bincode or compact binary format)")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf)),
+                )
+                .arg(outputs_arg()),
+        )
+        .subcommand(
+            Command::new("bench")
+                .about("Prove and verify a random program of the given shape")
+                .arg(
+                    Arg::new("gates")
+                        .long("gates")
+                        .help("Number of (non-input) gates")
+                        .default_value("100000")
+                        .value_parser(value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("inputs")
+                        .long("inputs")
+                        .help("Number of witness inputs")
+                        .default_value("64")
+                        .value_parser(value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("mul-ratio")
+                        .long("mul-ratio")
+                        .help("Fraction of the gates which are multiplications (0 to 1)")
+                        .default_value("0.5")
+                        .value_parser(value_parser!(f64)),
+                )
+                .arg(
+                    Arg::new("window")
+                        .long("window")
+                        .help(
+                            "Gates read two of the previous <window> wires \
+                            (small windows give deep programs)",
+                        )
+                        .default_value("64")
+                        .value_parser(value_parser!(usize)),
+                )
+                .arg(
+                    Arg::new("domain")
+                        .long("domain")
+                        .value_parser(["gf2", "z64"])
+                        .default_value("gf2"),
+                )
+                .arg(
+                    Arg::new("seed")
+                        .long("seed")
+                        .help("Seed of the random program")
+                        .default_value("0")
+                        .value_parser(value_parser!(u64)),
+                ),
+        )
+        .subcommand(
+            Command::new("inspect")
+                .about("Print the metadata of a proof")
+                .arg(
+                    Arg::new("proof")
+                        .help("The proof to inspect")
+                        .required(true)
+                        .value_parser(value_parser!(PathBuf)),
+                ),
+        )
         .arg(
             Arg::new("operation")
                 .long("operation")
                 .help("Specify the operation: \"prove\", \"verify\"")
-                .value_parser(["prove", "verify", "oneshot", "oneshot-zk", "version_info"])
+                .value_parser([
+                    "prove",
+                    "verify",
+                    "oneshot",
+                    "oneshot-zk",
+                    "profile",
+                    "version_info",
+                ])
                 .required(true),
         )
         .arg(
             Arg::new("witness-path")
                 .long("witness-path")
-                .help("The path to the file containing the witness (for proving)")
+                .help(
+                    "The path to the file containing the witness (for proving), \
+                    may be repeated to concatenate several witness files in order; \
+                    \"-\" reads the witness from stdin",
+                )
+                .action(ArgAction::Append)
                 .required_if_eq_any([
                     ("operation", "prove"),
                     ("operation", "oneshot"),
                     ("operation", "oneshot-zk"),
+                    ("operation", "profile"),
                     ("operation", "bench"),
                 ])
                 .value_parser(value_parser!(PathBuf)),
         )
+        .arg(witness_format_arg())
+        .arg(outputs_arg())
+        .arg(expected_output_arg())
+        .arg(seed_arg())
         .arg(
             Arg::new("program-path")
                 .long("program-path")
@@ -195,6 +765,7 @@ fn app() -> Command {
                     ("operation", "verify"),
                     ("operation", "oneshot"),
                     ("operation", "oneshot-zk"),
+                    ("operation", "profile"),
                     ("operation", "bench"),
                 ])
                 .value_parser(value_parser!(PathBuf)),
@@ -208,65 +779,222 @@ fn app() -> Command {
         )
 }
 
-async fn async_main() {
-    let matches = app().get_matches();
+// calls an operation with the witness parser selected by --witness-format
+macro_rules! with_witness_parser {
+    ($format:expr, $operation:ident($($arg:expr),*)) => {
+        match $format {
+            "hex" => $operation::<witness::HexParser>($($arg),*).await,
+            "packed" => $operation::<witness::PackedParser>($($arg),*).await,
+            _ => $operation::<witness::WitParser>($($arg),*).await,
+        }
+    };
+}
 
-    match *matches.get_one("operation").unwrap() {
-        "oneshot" => {
-            let res = oneshot::<witness::WitParser>(
-                *matches.get_one("program-path").unwrap(),
-                *matches.get_one("witness-path").unwrap(),
-            )
-            .await;
-            match res {
-                Err(e) => {
-                    eprintln!("Invalid proof: {}", e);
+fn outputs_or_exit(matches: &ArgMatches) -> (Vec<Output>, Vec<(String, u64)>) {
+    let outputs = load_outputs(matches.get_one::<PathBuf>("outputs")).and_then(|outputs| {
+        Ok((
+            outputs,
+            load_expected(matches.get_one::<PathBuf>("expected-output"))?,
+        ))
+    });
+    match outputs {
+        Ok(outputs) => outputs,
+        Err(e) => {
+            eprintln!("Could not load outputs: {}", e);
+            exit(-1)
+        }
+    }
+}
+
+async fn async_main(matches: ArgMatches) {
+    if let Some(("convert", sub)) = matches.subcommand() {
+        let input = sub.get_one::<PathBuf>("input").unwrap();
+        let output = sub.get_one::<PathBuf>("output").unwrap();
+        let format = |flag: &str, path: &Path| {
+            let format = sub.get_one::<String>(flag).map(|f| f.as_str());
+            format
+                .or_else(|| convert::format_of(path))
+                .unwrap_or_else(|| {
+                    eprintln!("Unknown format of {}, use --{}", path.display(), flag);
                     exit(-1)
-                }
-                Ok(output) => println!("{:?}", output),
+                })
+        };
+        let res = convert::convert(
+            (input, format("from", input)),
+            (output, format("to", output)),
+            sub.get_one::<PathBuf>("instance").map(|p| p.as_path()),
+            sub.get_flag("validate"),
+            sub.get_one::<PathBuf>("write-outputs").map(|p| p.as_path()),
+        );
+        match res {
+            Ok(Ok(())) => (),
+            Ok(Err(e)) => {
+                eprintln!("Invalid program: {}", e);
+                exit(-1)
+            }
+            Err(e) => {
+                eprintln!("Could not convert circuit: {}", e);
+                exit(-1)
             }
         }
-        "oneshot-zk" => {
-            let res = oneshot_zk::<witness::WitParser>(
-                *matches.get_one("program-path").unwrap(),
-                *matches.get_one("witness-path").unwrap(),
-            )
-            .await;
-            match res {
-                Err(e) => {
-                    eprintln!("Invalid proof: {}", e);
-                    exit(-1)
+        return;
+    }
+
+    if let Some(("stats", sub)) = matches.subcommand() {
+        match load_program(sub.get_one::<PathBuf>("program").unwrap()) {
+            Ok(program) => print!("{}", stats(&program)),
+            Err(e) => {
+                eprintln!("Could not load program: {}", e);
+                exit(-1)
+            }
+        }
+        return;
+    }
+
+    if let Some(("estimate", sub)) = matches.subcommand() {
+        let program = load_program(sub.get_one::<PathBuf>("program").unwrap());
+        let outputs = load_outputs(sub.get_one::<PathBuf>("outputs"));
+        match program.and_then(|program| Ok((program, outputs?))) {
+            Ok((program, outputs)) => {
+                let stats = stats(&program);
+                println!(
+                    "{:<12} {:>16} {:>20} {:>16}",
+                    "preset", "proof bytes", "prover memory bytes", "prove (ms)"
+                );
+                for preset in capabilities().presets.iter() {
+                    println!(
+                        "{}",
+                        estimate(&stats, &outputs, preset, rayon::current_num_threads())
+                    );
                 }
-                Ok(output) => println!("{:?}", output),
+            }
+            Err(e) => {
+                eprintln!("Could not load program: {}", e);
+                exit(-1)
             }
         }
-        "prove" => {
-            let res = prove::<witness::WitParser>(
-                *matches.get_one("program-path").unwrap(),
-                *matches.get_one("witness-path").unwrap(),
-                *matches.get_one("proof-path").unwrap(),
-            )
-            .await;
-            match res {
-                Err(e) => {
-                    eprintln!("Invalid proof: {}", e);
-                    exit(-1)
+        return;
+    }
+
+    if let Some(("bench", sub)) = matches.subcommand() {
+        let shape = bench::Shape {
+            inputs: *sub.get_one("inputs").unwrap(),
+            gates: *sub.get_one("gates").unwrap(),
+            mul_ratio: sub.get_one::<f64>("mul-ratio").unwrap().clamp(0.0, 1.0),
+            window: *sub.get_one("window").unwrap(),
+        };
+        let z64 = sub.get_one::<String>("domain").unwrap() == "z64";
+        if let Err(e) = bench::bench(&shape, z64, *sub.get_one("seed").unwrap()) {
+            eprintln!("Invalid proof: {}", e);
+            exit(-1)
+        }
+        return;
+    }
+
+    if let Some(("inspect", sub)) = matches.subcommand() {
+        if let Err(e) = inspect(sub.get_one::<PathBuf>("proof").unwrap()) {
+            eprintln!("Could not inspect proof: {}", e);
+            exit(-1)
+        }
+        return;
+    }
+
+    if let Some((command, sub)) = matches.subcommand() {
+        let program_path = sub.get_one::<PathBuf>("program").unwrap();
+        let (outputs, expected) = outputs_or_exit(sub);
+        let mut report = Report::new(sub.get_flag("json"));
+        let res = match command {
+            "prove" => {
+                let format = sub.get_one::<String>("witness-format").unwrap().as_str();
+                let witness_paths: Vec<&PathBuf> = sub.get_many("witness").unwrap().collect();
+                let proof_path = sub.get_one::<PathBuf>("output").unwrap();
+                with_witness_parser!(
+                    format,
+                    prove(
+                        program_path,
+                        &witness_paths,
+                        proof_path,
+                        &outputs,
+                        &prover_config(sub),
+                        &mut report
+                    )
+                )
+            }
+            "verify" => {
+                let proof_paths: Vec<&PathBuf> = sub.get_many("proof").unwrap().collect();
+                let res = verify(program_path, &proof_paths, &outputs, &expected, report.json);
+                if let Err(e) = res.await {
+                    report.finish(Err(e));
                 }
-                Ok(output) => println!("{:?}", output),
+                return;
             }
+            _ => unreachable!(),
+        };
+        report.finish(res);
+        return;
+    }
+
+    let format = matches
+        .get_one::<String>("witness-format")
+        .unwrap()
+        .as_str();
+
+    let program_path = matches.get_one::<PathBuf>("program-path");
+    let proof_path = matches.get_one::<PathBuf>("proof-path");
+    let witness_paths: Vec<&PathBuf> = matches
+        .get_many::<PathBuf>("witness-path")
+        .map(|paths| paths.collect())
+        .unwrap_or_default();
+    let (outputs, expected) = outputs_or_exit(&matches);
+    let mut report = Report::new(matches.get_flag("json"));
+
+    match matches.get_one::<String>("operation").unwrap().as_str() {
+        "oneshot" => {
+            let res = with_witness_parser!(
+                format,
+                oneshot(program_path.unwrap(), &witness_paths, &outputs, &mut report)
+            );
+            report.finish(res);
+        }
+        "oneshot-zk" => {
+            let res = with_witness_parser!(
+                format,
+                oneshot_zk(program_path.unwrap(), &witness_paths, &mut report)
+            );
+            report.finish(res);
+        }
+        "prove" => {
+            let res = with_witness_parser!(
+                format,
+                prove(
+                    program_path.unwrap(),
+                    &witness_paths,
+                    proof_path.unwrap(),
+                    &outputs,
+                    &prover_config(&matches),
+                    &mut report
+                )
+            );
+            report.finish(res);
         }
         "verify" => {
-            let res = verify::<witness::WitParser>(
-                *matches.get_one("program-path").unwrap(),
-                *matches.get_one("proof-path").unwrap(),
+            let res = verify(
+                program_path.unwrap(),
+                &[proof_path.unwrap()],
+                &outputs,
+                &expected,
+                report.json,
             )
             .await;
-            match res {
-                Err(e) => {
-                    eprintln!("Invalid proof: {}", e);
-                    exit(-1)
-                }
-                Ok(output) => println!("{:?}", output),
+            if let Err(e) = res {
+                report.finish(Err(e));
+            }
+        }
+        "profile" => {
+            let res = with_witness_parser!(format, profile(program_path.unwrap(), &witness_paths));
+            if let Err(e) = res {
+                eprintln!("Could not profile program: {}", e);
+                exit(-1)
             }
         }
         "version_info" => print_version().await,
@@ -285,8 +1013,31 @@ async fn print_version() {
     }
 }
 
+// sizes the thread pools before they are first used
+fn set_threads(threads: usize) {
+    std::env::set_var("ASYNC_STD_THREAD_COUNT", threads.to_string());
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build_global()
+        .expect("the thread pool is configured once");
+}
+
 fn main() {
-    task::block_on(async_main());
+    let matches = app().get_matches();
+    let global = match matches.subcommand() {
+        Some((_, sub)) => sub,
+        None => &matches,
+    };
+    if let Some(threads) = global.get_one::<u64>("threads") {
+        set_threads(*threads as usize);
+    }
+    let level: LevelFilter = global
+        .get_one::<String>("log-level")
+        .unwrap()
+        .parse()
+        .expect("the log levels are validated by clap");
+    logging::init(level, !global.get_flag("no-progress"));
+    task::block_on(async_main(matches));
 }
 
 #[cfg(all(test, unix))]
@@ -297,4 +1048,109 @@ mod tests {
     fn test_app() {
         app().debug_assert();
     }
+
+    #[test]
+    fn test_subcommands() {
+        let matches = app()
+            .try_get_matches_from(["speed-reverie", "prove", "prog.bin", "w1", "-", "-o", "p"])
+            .unwrap();
+        let (command, sub) = matches.subcommand().unwrap();
+        assert_eq!(command, "prove");
+        let witness: Vec<&PathBuf> = sub.get_many("witness").unwrap().collect();
+        assert_eq!(witness, [&PathBuf::from("w1"), &PathBuf::from("-")]);
+        assert_eq!(
+            sub.get_one::<PathBuf>("output").unwrap(),
+            &PathBuf::from("p")
+        );
+
+        // global flags may follow the subcommand
+        let matches = app()
+            .try_get_matches_from(["speed-reverie", "stats", "prog.bin", "--threads", "2"])
+            .unwrap();
+        let (_, sub) = matches.subcommand().unwrap();
+        assert_eq!(sub.get_one::<u64>("threads"), Some(&2));
+        assert!(app()
+            .try_get_matches_from(["speed-reverie", "--threads", "0", "stats", "prog.bin"])
+            .is_err());
+        assert!(app()
+            .try_get_matches_from(["speed-reverie", "stats", "prog.bin", "--log-level", "debug"])
+            .is_ok());
+        assert!(app()
+            .try_get_matches_from(["speed-reverie", "stats", "prog.bin", "--log-level", "loud"])
+            .is_err());
+
+        // the proof path is required for verification
+        assert!(app()
+            .try_get_matches_from(["speed-reverie", "verify", "prog.bin"])
+            .is_err());
+    }
+
+    #[test]
+    fn test_prover_config_seed() {
+        let seed = |args: &[&str]| {
+            let matches = app().try_get_matches_from(args).unwrap();
+            prover_config(matches.subcommand_matches("prove").unwrap()).seed
+        };
+        let a = seed(&["speed-reverie", "prove", "p", "w", "--seed", "a"]);
+        assert_eq!(
+            a,
+            seed(&["speed-reverie", "prove", "p", "w", "--seed", "a"])
+        );
+        assert_ne!(
+            a,
+            seed(&["speed-reverie", "prove", "p", "w", "--seed", "b"])
+        );
+        assert_eq!(seed(&["speed-reverie", "prove", "p", "w"]), None);
+    }
+
+    #[test]
+    fn test_load_outputs() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "# revealed wires\nsum z64 3\n\ncarry gf2 7 # last").unwrap();
+        let path = file.path().to_path_buf();
+        assert_eq!(
+            load_outputs(Some(&path)).unwrap(),
+            vec![Output::z64("sum", 3), Output::gf2("carry", 7)]
+        );
+        assert!(load_outputs(None).unwrap().is_empty());
+
+        let mut expected = tempfile::NamedTempFile::new().unwrap();
+        writeln!(expected, "sum 12\ncarry 1").unwrap();
+        assert_eq!(
+            load_expected(Some(&expected.path().to_path_buf())).unwrap(),
+            vec![("sum".to_string(), 12), ("carry".to_string(), 1)]
+        );
+
+        writeln!(file, "x f64 1").unwrap();
+        let err = load_outputs(Some(&path)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 5: the domain must be \"gf2\" or \"z64\""
+        );
+    }
+
+    #[test]
+    fn test_write_proof() {
+        let program = vec![
+            CombineOperation::GF2(reverie::Operation::Input(0)),
+            CombineOperation::GF2(reverie::Operation::AssertZero(0)),
+        ];
+        let wire_counts = largest_wires(&program);
+        let proof = Proof::new(
+            Arc::new(program),
+            Arc::new(vec![false]),
+            Arc::new(vec![]),
+            wire_counts,
+        );
+
+        // missing directories are created
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a/b/proof.bin");
+        let bytes = write_proof(&path, &proof).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), bytes);
+        assert_eq!(load_proof(&path).unwrap(), proof);
+
+        // a file in the way is an error
+        assert!(write_proof(&path.join("proof.bin"), &proof).is_err());
+    }
 }
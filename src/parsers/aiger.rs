@@ -0,0 +1,396 @@
+use std::io::{self, BufRead};
+
+use super::{invalid, invalid_line};
+use crate::proof::Output;
+use crate::{CombineOperation, Operation};
+
+/// A combinational and-inverter graph lowered to a gf2 program.
+#[derive(Debug, Clone)]
+pub struct AigerCircuit {
+    /// Gf2 program: an `Input` for every input (in order) followed by the gates.
+    pub program: Vec<CombineOperation>,
+
+    /// Total number of wires.
+    pub wires: usize,
+
+    /// Names of the inputs ("i{index}" without a symbol table entry), input i is wire i.
+    pub inputs: Vec<String>,
+
+    /// Names ("o{index}" without a symbol table entry) and wires of the outputs.
+    pub outputs: Vec<(String, usize)>,
+}
+
+impl AigerCircuit {
+    /// Output wires labelled with the names of the outputs.
+    pub fn output_wires(&self) -> Vec<Output> {
+        self.outputs
+            .iter()
+            .map(|(name, wire)| Output::gf2(name.clone(), *wire))
+            .collect()
+    }
+}
+
+struct Cursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+    line: usize, // 0-indexed line of pos
+}
+
+impl<'a> Cursor<'a> {
+    fn try_line(&mut self) -> io::Result<Option<(usize, &'a str)>> {
+        if self.pos >= self.data.len() {
+            return Ok(None);
+        }
+        let rest = &self.data[self.pos..];
+        let len = rest.iter().position(|b| *b == b'\n').unwrap_or(rest.len());
+        let line = std::str::from_utf8(&rest[..len])
+            .map_err(|_| invalid_line(self.line, "invalid utf-8"))?;
+        self.pos += len + 1;
+        self.line += 1;
+        Ok(Some((self.line - 1, line.trim_end_matches('\r'))))
+    }
+
+    fn line(&mut self) -> io::Result<(usize, &'a str)> {
+        self.try_line()?
+            .ok_or_else(|| invalid("unexpected end of input"))
+    }
+
+    // 7-bit little-endian varint of the binary format
+    fn varint(&mut self) -> io::Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self
+                .data
+                .get(self.pos)
+                .ok_or_else(|| invalid("truncated and gates"))?;
+            self.pos += 1;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("malformed delta encoding"))
+    }
+}
+
+fn numbers(line: usize, tokens: &[&str]) -> io::Result<Vec<u64>> {
+    tokens
+        .iter()
+        .map(|t| {
+            t.parse()
+                .map_err(|_| invalid_line(line, format!("expected a number, found '{}'", t)))
+        })
+        .collect()
+}
+
+struct Lowering {
+    program: Vec<CombineOperation>,
+    next_wire: usize,
+    wires: Vec<Option<usize>>,   // wire of every variable
+    negated: Vec<Option<usize>>, // wire of the negation of every variable
+}
+
+impl Lowering {
+    fn gate(&mut self, op: impl FnOnce(usize) -> Operation<bool>) -> usize {
+        let dst = self.next_wire;
+        self.next_wire += 1;
+        self.program.push(CombineOperation::GF2(op(dst)));
+        dst
+    }
+
+    // wire of a literal whose variable is defined (negations and the constant are shared)
+    fn literal(&mut self, lit: u64) -> usize {
+        let var = (lit / 2) as usize;
+        let wire = match self.wires[var] {
+            Some(wire) => wire,
+            None => {
+                // only the constant (variable 0) is created on demand
+                let wire = self.gate(|dst| Operation::Const(dst, false));
+                self.wires[var] = Some(wire);
+                wire
+            }
+        };
+        if lit & 1 == 0 {
+            return wire;
+        }
+        match self.negated[var] {
+            Some(negated) => negated,
+            None => {
+                let negated = self.gate(|dst| Operation::AddConst(dst, wire, true));
+                self.negated[var] = Some(negated);
+                negated
+            }
+        }
+    }
+}
+
+/// Parses a combinational AIGER file, in the ASCII ("aag") or binary ("aig") format.
+///
+/// Every AND gate becomes a `Mul` and inverters become `AddConst(.., true)`. Latches are not
+/// supported; names are taken from the symbol table when present.
+pub fn parse_aiger<R: BufRead>(mut reader: R) -> io::Result<AigerCircuit> {
+    let mut data = vec![];
+    reader.read_to_end(&mut data)?;
+    let mut cursor = Cursor {
+        data: &data,
+        pos: 0,
+        line: 0,
+    };
+
+    // header: "aag M I L O A" (the optional B C J F counts must be zero)
+    let (n, header) = cursor.line()?;
+    let tokens: Vec<&str> = header.split_whitespace().collect();
+    let binary = match tokens.first() {
+        Some(&"aag") => false,
+        Some(&"aig") => true,
+        _ => return Err(invalid_line(n, "expected 'aag' or 'aig'")),
+    };
+    let counts = numbers(n, &tokens[1..])?;
+    let (max_var, n_inputs, n_outputs, n_ands) = match counts[..] {
+        [m, i, 0, o, a, ref rest @ ..] if rest.len() <= 4 && rest.iter().all(|c| *c == 0) => {
+            (m as usize, i as usize, o as usize, a as usize)
+        }
+        [_, _, l, _, _, ..] if l != 0 => {
+            return Err(invalid_line(n, "latches are not supported"));
+        }
+        [_, _, _, _, _, ..] => {
+            return Err(invalid_line(
+                n,
+                "bad state, constraint and fairness properties are not supported",
+            ))
+        }
+        _ => return Err(invalid_line(n, "expected 'M I L O A'")),
+    };
+    if n_inputs + n_ands > max_var {
+        return Err(invalid_line(n, "M is less than I + L + A"));
+    }
+    let check = |line: usize, lit: u64| -> io::Result<u64> {
+        if lit / 2 > max_var as u64 {
+            return Err(invalid_line(line, format!("literal {} out of range", lit)));
+        }
+        Ok(lit)
+    };
+    let single = |line: usize, text: &str| -> io::Result<u64> {
+        match numbers(line, &text.split_whitespace().collect::<Vec<_>>())?[..] {
+            [lit] => check(line, lit),
+            _ => Err(invalid_line(line, "expected a literal")),
+        }
+    };
+
+    // inputs (implicit in the binary format)
+    let mut lowering = Lowering {
+        program: Vec::with_capacity(n_inputs + n_ands),
+        next_wire: 0,
+        wires: vec![None; max_var + 1],
+        negated: vec![None; max_var + 1],
+    };
+    for i in 0..n_inputs {
+        let var = if binary {
+            i + 1
+        } else {
+            let (n, text) = cursor.line()?;
+            let lit = single(n, text)?;
+            if lit & 1 == 1 || lit == 0 || lowering.wires[(lit / 2) as usize].is_some() {
+                return Err(invalid_line(n, format!("invalid input literal {}", lit)));
+            }
+            (lit / 2) as usize
+        };
+        lowering.wires[var] = Some(lowering.gate(Operation::Input));
+    }
+
+    let mut outputs = vec![];
+    for _ in 0..n_outputs {
+        let (n, text) = cursor.line()?;
+        outputs.push((n, single(n, text)?));
+    }
+
+    // and gates: (line, lhs variable, rhs literals)
+    let mut ands: Vec<(usize, usize, u64, u64)> = Vec::with_capacity(n_ands);
+    let mut defined: Vec<Option<usize>> = vec![None; max_var + 1]; // index into ands
+    for i in 0..n_ands {
+        let (n, lhs, rhs0, rhs1) = if binary {
+            let lhs = 2 * (n_inputs + i + 1) as u64;
+            let rhs0 = lhs
+                .checked_sub(cursor.varint()?)
+                .ok_or_else(|| invalid("malformed delta encoding"))?;
+            let rhs1 = rhs0
+                .checked_sub(cursor.varint()?)
+                .ok_or_else(|| invalid("malformed delta encoding"))?;
+            (cursor.line, lhs, rhs0, rhs1)
+        } else {
+            let (n, text) = cursor.line()?;
+            match numbers(n, &text.split_whitespace().collect::<Vec<_>>())?[..] {
+                [lhs, rhs0, rhs1] => (n, check(n, lhs)?, check(n, rhs0)?, check(n, rhs1)?),
+                _ => return Err(invalid_line(n, "expected 'lhs rhs0 rhs1'")),
+            }
+        };
+        let var = (lhs / 2) as usize;
+        if lhs & 1 == 1 || var == 0 || lowering.wires[var].is_some() || defined[var].is_some() {
+            return Err(invalid_line(n, format!("invalid and gate output {}", lhs)));
+        }
+        defined[var] = Some(ands.len());
+        ands.push((n, var, rhs0, rhs1));
+    }
+    if binary {
+        // the symbol table starts on the line after the and gates
+        cursor.line = data[..cursor.pos.min(data.len())]
+            .iter()
+            .filter(|b| **b == b'\n')
+            .count();
+    }
+
+    // the ASCII format does not order the gates: lower them depth-first
+    let mut visiting = vec![false; max_var + 1];
+    for root in 0..ands.len() {
+        let mut stack = vec![root];
+        while let Some(&idx) = stack.last() {
+            let (n, var, rhs0, rhs1) = ands[idx];
+            if lowering.wires[var].is_some() {
+                stack.pop();
+                continue;
+            }
+            visiting[var] = true;
+            let pending = [rhs0, rhs1]
+                .iter()
+                .map(|lit| (lit / 2) as usize)
+                .find(|v| *v != 0 && lowering.wires[*v].is_none());
+            match pending {
+                Some(v) if visiting[v] => {
+                    return Err(invalid_line(n, format!("cycle through variable {}", v)))
+                }
+                Some(v) => match defined[v] {
+                    Some(dep) => stack.push(dep),
+                    None => return Err(invalid_line(n, format!("variable {} is undefined", v))),
+                },
+                None => {
+                    let a = lowering.literal(rhs0);
+                    let b = lowering.literal(rhs1);
+                    lowering.wires[var] = Some(lowering.gate(|dst| Operation::Mul(dst, a, b)));
+                    visiting[var] = false;
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    let mut output_wires = vec![];
+    for (n, lit) in outputs {
+        let var = (lit / 2) as usize;
+        if var != 0 && lowering.wires[var].is_none() {
+            return Err(invalid_line(n, format!("variable {} is undefined", var)));
+        }
+        output_wires.push(lowering.literal(lit));
+    }
+
+    // symbol table (until the comment section)
+    let mut inputs: Vec<String> = (0..n_inputs).map(|i| format!("i{}", i)).collect();
+    let mut outputs: Vec<(String, usize)> = output_wires
+        .into_iter()
+        .enumerate()
+        .map(|(i, wire)| (format!("o{}", i), wire))
+        .collect();
+    while let Some((n, text)) = cursor.try_line()? {
+        if text == "c" {
+            break;
+        }
+        let (position, name) = text
+            .split_once(' ')
+            .ok_or_else(|| invalid_line(n, "malformed symbol"))?;
+        let index = |kind: &str| -> io::Result<usize> {
+            position[kind.len()..]
+                .parse()
+                .map_err(|_| invalid_line(n, "malformed symbol"))
+        };
+        match position.get(..1) {
+            Some("i") => match inputs.get_mut(index("i")?) {
+                Some(input) => *input = name.to_string(),
+                None => return Err(invalid_line(n, "symbol of a missing input")),
+            },
+            Some("o") => match outputs.get_mut(index("o")?) {
+                Some(output) => output.0 = name.to_string(),
+                None => return Err(invalid_line(n, "symbol of a missing output")),
+            },
+            _ => return Err(invalid_line(n, "malformed symbol")),
+        }
+    }
+
+    Ok(AigerCircuit {
+        program: lowering.program,
+        wires: lowering.next_wire,
+        inputs,
+        outputs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::{OutputValue, Proof, ProverConfig};
+    use std::sync::Arc;
+
+    // xor of two inputs: !( !(a & !b) & !(!a & b) ), with the gates out of order
+    const XOR: &str = "\
+aag 5 2 0 1 3
+2
+4
+11
+10 7 9
+6 2 5
+8 3 4
+i0 a
+i1 b
+o0 x
+c
+xor
+";
+
+    fn prove(circuit: &AigerCircuit, inputs: &[bool]) -> crate::proof::Outputs {
+        let outputs = circuit.output_wires();
+        let program = Arc::new(circuit.program.clone());
+        let proof = Proof::new_with_config(
+            program.clone(),
+            &outputs,
+            Arc::new(inputs.to_vec()),
+            Arc::new(vec![]),
+            (0, circuit.wires),
+            &ProverConfig::default(),
+        );
+        proof
+            .verify_with_outputs(program, &outputs, (0, circuit.wires))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_aiger_ascii() {
+        let circuit = parse_aiger(XOR.as_bytes()).unwrap();
+        assert_eq!(circuit.inputs, vec!["a", "b"]);
+        for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+            assert_eq!(prove(&circuit, &[a, b])["x"], OutputValue::GF2(a ^ b));
+        }
+    }
+
+    #[test]
+    fn test_aiger_binary() {
+        // the same circuit and a constant output
+        let mut src = b"aig 5 2 0 2 3\n11\n1\n".to_vec();
+        src.extend_from_slice(&[6 - 5, 5 - 2, 8 - 4, 4 - 3, 10 - 9, 9 - 7]);
+        src.extend_from_slice(b"o1 one\n");
+        let circuit = parse_aiger(&src[..]).unwrap();
+        assert_eq!(circuit.outputs[1].0, "one");
+        for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+            let values = prove(&circuit, &[a, b]);
+            assert_eq!(values["o0"], OutputValue::GF2(a ^ b));
+            assert_eq!(values["one"], OutputValue::GF2(true));
+        }
+    }
+
+    #[test]
+    fn test_aiger_errors() {
+        assert!(parse_aiger("aag 1 1 0 1 0\n2\n2\n".as_bytes()).is_ok());
+        assert!(parse_aiger("aag 1 0 1 1 0\n2 3\n2\n".as_bytes()).is_err()); // latch
+        assert!(parse_aiger("aag 2 1 0 1 0\n2\n4\n".as_bytes()).is_err()); // undefined
+        assert!(parse_aiger("aag 3 1 0 1 2\n2\n4\n4 6 2\n6 4 2\n".as_bytes()).is_err()); // cycle
+        assert!(parse_aiger("aag 1 1 0 1 0\n2\n9\n".as_bytes()).is_err()); // out of range
+        assert!(parse_aiger("aig 1 1 0 1\n".as_bytes()).is_err()); // header
+    }
+}
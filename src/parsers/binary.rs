@@ -0,0 +1,174 @@
+use std::io::{self, Read, Write};
+
+use bincode::Options;
+use rayon::prelude::*;
+
+use super::invalid;
+use crate::CombineOperation;
+
+/// Leading bytes of a program in the compact binary format.
+pub const PROGRAM_MAGIC: [u8; 8] = *b"RVRPRG01";
+
+/// Default number of operations per chunk.
+pub const PROGRAM_CHUNK_SIZE: usize = 1 << 16;
+
+// variable length integers: wire indices and constants are mostly small
+fn options() -> impl Options {
+    bincode::DefaultOptions::new()
+}
+
+/// Writes a program in the compact binary format: the magic followed by chunks of
+/// at most `chunk_size` operations, each a little-endian u64 byte length and the
+/// (varint) bincode encoding of the operations.
+pub fn write_program<W: Write>(
+    mut writer: W,
+    program: &[CombineOperation],
+    chunk_size: usize,
+) -> io::Result<()> {
+    assert!(chunk_size > 0, "chunks must not be empty");
+    writer.write_all(&PROGRAM_MAGIC)?;
+    for chunk in program.chunks(chunk_size) {
+        let bytes = options().serialize(chunk).map_err(io::Error::other)?;
+        writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+        writer.write_all(&bytes)?;
+    }
+    writer.flush()
+}
+
+/// Streams the chunks of a program in the compact binary format.
+pub struct ProgramReader<R: Read> {
+    reader: R,
+}
+
+impl<R: Read> ProgramReader<R> {
+    /// Checks the magic, the chunks follow.
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        if magic != PROGRAM_MAGIC {
+            return Err(invalid("not a program in the compact binary format"));
+        }
+        Ok(ProgramReader { reader })
+    }
+
+    /// The encoding of the next chunk (None at the end of the program).
+    fn next_raw(&mut self) -> io::Result<Option<Vec<u8>>> {
+        let mut len = [0u8; 8];
+        let mut read = 0;
+        while read < len.len() {
+            match self.reader.read(&mut len[read..])? {
+                0 if read == 0 => return Ok(None),
+                0 => return Err(invalid("truncated chunk header")),
+                n => read += n,
+            }
+        }
+        let len = u64::from_le_bytes(len);
+        let mut bytes = vec![];
+        self.reader.by_ref().take(len).read_to_end(&mut bytes)?;
+        if bytes.len() as u64 != len {
+            return Err(invalid("truncated chunk"));
+        }
+        Ok(Some(bytes))
+    }
+}
+
+fn decode(bytes: &[u8]) -> io::Result<Vec<CombineOperation>> {
+    options()
+        .deserialize(bytes)
+        .map_err(|e| invalid(format!("malformed chunk: {}", e)))
+}
+
+impl<R: Read> Iterator for ProgramReader<R> {
+    type Item = io::Result<Vec<CombineOperation>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_raw() {
+            Ok(Some(bytes)) => Some(decode(&bytes)),
+            Ok(None) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// Reads a whole program in the compact binary format, decoding chunks in parallel.
+pub fn read_program<R: Read>(reader: R) -> io::Result<Vec<CombineOperation>> {
+    let mut reader = ProgramReader::new(reader)?;
+    let batch = rayon::current_num_threads();
+    let mut program = vec![];
+    loop {
+        let mut raw = Vec::with_capacity(batch);
+        while raw.len() < batch {
+            match reader.next_raw()? {
+                Some(bytes) => raw.push(bytes),
+                None => break,
+            }
+        }
+        let done = raw.len() < batch;
+        let chunks: Vec<Vec<CombineOperation>> = raw
+            .par_iter()
+            .map(|bytes| decode(bytes))
+            .collect::<io::Result<_>>()?;
+        for chunk in chunks {
+            program.extend(chunk);
+        }
+        if done {
+            return Ok(program);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Operation;
+
+    fn program() -> Vec<CombineOperation> {
+        let mut program = vec![];
+        for i in 0..1000 {
+            program.push(CombineOperation::GF2(Operation::Input(i)));
+            program.push(CombineOperation::Z64(Operation::MulConst(
+                i,
+                i,
+                u64::MAX - i as u64,
+            )));
+        }
+        program.push(CombineOperation::B2A(1, 0));
+        program
+    }
+
+    #[test]
+    fn test_binary_roundtrip() {
+        let program = program();
+        let mut bytes = vec![];
+        write_program(&mut bytes, &program, 7).unwrap();
+
+        // more compact than the plain bincode encoding
+        assert!(bytes.len() < bincode::serialize(&program).unwrap().len() / 2);
+
+        let read = read_program(&bytes[..]).unwrap();
+        assert_eq!(
+            bincode::serialize(&read).unwrap(),
+            bincode::serialize(&program).unwrap()
+        );
+
+        let chunks: Vec<_> = ProgramReader::new(&bytes[..]).unwrap().collect();
+        assert_eq!(chunks.len(), program.len().div_ceil(7));
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.as_ref().unwrap().len() <= 7));
+
+        // empty program
+        let mut bytes = vec![];
+        write_program(&mut bytes, &[], PROGRAM_CHUNK_SIZE).unwrap();
+        assert!(read_program(&bytes[..]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_binary_errors() {
+        let mut bytes = vec![];
+        write_program(&mut bytes, &program(), 100).unwrap();
+        assert!(read_program(&bytes[..bytes.len() - 1]).is_err());
+        assert!(read_program(&bytes[..PROGRAM_MAGIC.len() + 3]).is_err());
+        assert!(read_program(&bincode::serialize(&program()).unwrap()[..]).is_err());
+    }
+}
@@ -0,0 +1,364 @@
+use std::collections::HashMap;
+use std::io::{self, BufRead};
+
+use super::{invalid, invalid_line};
+use crate::proof::Output;
+use crate::{CombineOperation, Operation};
+
+/// A combinational BLIF model lowered to a gf2 program.
+#[derive(Debug, Clone)]
+pub struct BlifCircuit {
+    /// Gf2 program: an `Input` for every primary input (in declaration order) followed by the gates.
+    pub program: Vec<CombineOperation>,
+
+    /// Total number of wires.
+    pub wires: usize,
+
+    /// Names of the primary inputs, input i is wire i.
+    pub inputs: Vec<String>,
+
+    /// Names and wires of the primary outputs.
+    pub outputs: Vec<(String, usize)>,
+}
+
+impl BlifCircuit {
+    /// Output wires labelled with the names of the primary outputs.
+    pub fn output_wires(&self) -> Vec<Output> {
+        self.outputs
+            .iter()
+            .map(|(name, wire)| Output::gf2(name.clone(), *wire))
+            .collect()
+    }
+}
+
+// a single-output cover: ".names in_1 ... in_n out" followed by its cubes
+struct Names {
+    line: usize,
+    inputs: Vec<String>,
+    output: String,
+    cubes: Vec<Vec<Option<bool>>>,
+    onset: bool, // whether the cubes describe the ones (or the zeros) of the output
+}
+
+// logical lines: comments removed, continuations joined, empty lines skipped
+fn logical_lines<R: BufRead>(reader: R) -> io::Result<Vec<(usize, String)>> {
+    let mut lines = vec![];
+    let mut pending: Option<(usize, String)> = None;
+    for (n, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.split('#').next().unwrap().trim_end();
+        let (line, continued) = match line.strip_suffix('\\') {
+            Some(line) => (line, true),
+            None => (line, false),
+        };
+        let (start, mut joined) = pending.take().unwrap_or((n, String::new()));
+        joined.push(' ');
+        joined.push_str(line);
+        if continued {
+            pending = Some((start, joined));
+        } else if !joined.trim().is_empty() {
+            lines.push((start, joined));
+        }
+    }
+    if let Some((start, joined)) = pending {
+        lines.push((start, joined));
+    }
+    Ok(lines)
+}
+
+fn cube(line: usize, plane: &str, inputs: usize) -> io::Result<Vec<Option<bool>>> {
+    if plane.len() != inputs {
+        return Err(invalid_line(
+            line,
+            format!("expected {} input literals, found '{}'", inputs, plane),
+        ));
+    }
+    plane
+        .chars()
+        .map(|c| match c {
+            '0' => Ok(Some(false)),
+            '1' => Ok(Some(true)),
+            '-' => Ok(None),
+            _ => Err(invalid_line(line, format!("invalid literal '{}'", c))),
+        })
+        .collect()
+}
+
+struct Lowering {
+    program: Vec<CombineOperation>,
+    next_wire: usize,
+}
+
+impl Lowering {
+    fn gate(&mut self, op: impl FnOnce(usize) -> Operation<bool>) -> usize {
+        let dst = self.next_wire;
+        self.next_wire += 1;
+        self.program.push(CombineOperation::GF2(op(dst)));
+        dst
+    }
+
+    fn or(&mut self, a: usize, b: usize) -> usize {
+        // a | b = a + b + ab
+        let ab = self.gate(|dst| Operation::Mul(dst, a, b));
+        let sum = self.gate(|dst| Operation::Add(dst, a, b));
+        self.gate(|dst| Operation::Add(dst, sum, ab))
+    }
+
+    // lowers a cover given the wires of its inputs, returns the output wire
+    fn names(&mut self, names: &Names, inputs: &[usize]) -> usize {
+        let mut cover = None;
+        for cube in names.cubes.iter() {
+            let mut product = None;
+            for (wire, literal) in inputs.iter().zip(cube.iter()) {
+                let literal = match literal {
+                    None => continue,
+                    Some(true) => *wire,
+                    Some(false) => self.gate(|dst| Operation::AddConst(dst, *wire, true)),
+                };
+                product = Some(match product {
+                    None => literal,
+                    Some(product) => self.gate(|dst| Operation::Mul(dst, product, literal)),
+                });
+            }
+            // a cube without literals is the constant one
+            let product = match product {
+                Some(product) => product,
+                None => self.gate(|dst| Operation::Const(dst, true)),
+            };
+            cover = Some(match cover {
+                None => product,
+                Some(cover) => self.or(cover, product),
+            });
+        }
+
+        match (cover, names.onset) {
+            (None, onset) => self.gate(|dst| Operation::Const(dst, !onset)),
+            (Some(cover), true) => self.gate(|dst| Operation::AddConst(dst, cover, false)),
+            (Some(cover), false) => self.gate(|dst| Operation::AddConst(dst, cover, true)),
+        }
+    }
+}
+
+/// Parses a combinational BLIF model (`.inputs`, `.outputs` and `.names` covers).
+///
+/// Covers may appear in any order; they are lowered in topological order with one AND gate
+/// per literal of every cube and per cube of every cover. Latches and subcircuits are not supported.
+pub fn parse_blif<R: BufRead>(reader: R) -> io::Result<BlifCircuit> {
+    let mut inputs: Vec<String> = vec![];
+    let mut outputs: Vec<String> = vec![];
+    let mut covers: Vec<Names> = vec![];
+    let mut model = false;
+
+    for (n, line) in logical_lines(reader)? {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens[0] {
+            ".model" if model => return Err(invalid_line(n, "multiple models are not supported")),
+            ".model" => model = true,
+            ".inputs" => inputs.extend(tokens[1..].iter().map(|s| s.to_string())),
+            ".outputs" => outputs.extend(tokens[1..].iter().map(|s| s.to_string())),
+            ".names" => match tokens[1..].split_last() {
+                Some((output, ins)) => covers.push(Names {
+                    line: n,
+                    inputs: ins.iter().map(|s| s.to_string()).collect(),
+                    output: output.to_string(),
+                    cubes: vec![],
+                    onset: true,
+                }),
+                None => return Err(invalid_line(n, ".names without an output")),
+            },
+            ".end" => break,
+            ".default_input_arrival"
+            | ".default_output_required"
+            | ".input_arrival"
+            | ".output_required"
+            | ".area"
+            | ".delay"
+            | ".wire_load_slope" => (),
+            directive if directive.starts_with('.') => {
+                return Err(invalid_line(n, format!("unsupported {}", directive)))
+            }
+            _ => {
+                // a row of the last cover
+                let names = covers
+                    .last_mut()
+                    .ok_or_else(|| invalid_line(n, "cube outside of .names"))?;
+                let (plane, value) = match (names.inputs.len(), &tokens[..]) {
+                    (0, [value]) => ("", *value),
+                    (_, [plane, value]) => (*plane, *value),
+                    _ => return Err(invalid_line(n, "malformed cube")),
+                };
+                let onset = match value {
+                    "1" => true,
+                    "0" => false,
+                    _ => return Err(invalid_line(n, format!("invalid output '{}'", value))),
+                };
+                if !names.cubes.is_empty() && names.onset != onset {
+                    return Err(invalid_line(n, "cover mixes on-set and off-set cubes"));
+                }
+                names.onset = onset;
+                names.cubes.push(cube(n, plane, names.inputs.len())?);
+            }
+        }
+    }
+
+    // signal name -> wire (inputs) or index of the defining cover
+    let mut wires: HashMap<&str, usize> = HashMap::new();
+    for (wire, name) in inputs.iter().enumerate() {
+        if wires.insert(name, wire).is_some() {
+            return Err(invalid(format!("input '{}' is declared twice", name)));
+        }
+    }
+    let mut defs: HashMap<&str, usize> = HashMap::new();
+    for (idx, names) in covers.iter().enumerate() {
+        if wires.contains_key(names.output.as_str()) || defs.insert(&names.output, idx).is_some() {
+            return Err(invalid_line(
+                names.line,
+                format!("signal '{}' is defined twice", names.output),
+            ));
+        }
+    }
+
+    let mut lowering = Lowering {
+        program: (0..inputs.len())
+            .map(|wire| CombineOperation::GF2(Operation::Input(wire)))
+            .collect(),
+        next_wire: inputs.len(),
+    };
+
+    // depth-first over the covers the outputs depend on (explicitly, netlists may be deep)
+    let mut visiting = vec![false; covers.len()];
+    for output in outputs.iter() {
+        let mut stack = vec![output.as_str()];
+        while let Some(&signal) = stack.last() {
+            if wires.contains_key(signal) {
+                stack.pop();
+                continue;
+            }
+            let idx = *defs
+                .get(signal)
+                .ok_or_else(|| invalid(format!("signal '{}' is not defined", signal)))?;
+            let names = &covers[idx];
+            visiting[idx] = true;
+            match names
+                .inputs
+                .iter()
+                .find(|s| !wires.contains_key(s.as_str()))
+            {
+                Some(input) => {
+                    if defs.get(input.as_str()).is_some_and(|i| visiting[*i]) {
+                        return Err(invalid_line(
+                            names.line,
+                            format!("combinational cycle through '{}'", input),
+                        ));
+                    }
+                    stack.push(input);
+                }
+                None => {
+                    let ins: Vec<usize> = names.inputs.iter().map(|s| wires[s.as_str()]).collect();
+                    let wire = lowering.names(names, &ins);
+                    wires.insert(signal, wire);
+                    visiting[idx] = false;
+                    stack.pop();
+                }
+            }
+        }
+    }
+
+    let outputs = outputs
+        .iter()
+        .map(|name| (name.clone(), wires[name.as_str()]))
+        .collect();
+    Ok(BlifCircuit {
+        program: lowering.program,
+        wires: lowering.next_wire,
+        inputs,
+        outputs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::{OutputValue, Proof, ProverConfig};
+    use std::sync::Arc;
+
+    // full adder, covers out of order
+    const ADDER: &str = "\
+# full adder
+.model adder
+.inputs a b \\
+  cin
+.outputs sum cout
+.names t cin sum
+10 1
+01 1
+.names a b t
+10 1
+01 1
+.names a b cin cout
+11- 1
+1-1 1
+-11 1
+.end
+";
+
+    #[test]
+    fn test_blif_adder() {
+        let circuit = parse_blif(ADDER.as_bytes()).unwrap();
+        assert_eq!(circuit.inputs, vec!["a", "b", "cin"]);
+
+        let outputs = circuit.output_wires();
+        let program = Arc::new(circuit.program);
+        for x in 0..8u8 {
+            let wit_gf2 = Arc::new((0..3).map(|i| (x >> i) & 1 == 1).collect());
+            let proof = Proof::new_with_config(
+                program.clone(),
+                &outputs,
+                wit_gf2,
+                Arc::new(vec![]),
+                (0, circuit.wires),
+                &ProverConfig::default(),
+            );
+            let values = proof
+                .verify_with_outputs(program.clone(), &outputs, (0, circuit.wires))
+                .unwrap();
+            let sum = (x & 1) + ((x >> 1) & 1) + (x >> 2);
+            assert_eq!(values["sum"], OutputValue::GF2(sum & 1 == 1));
+            assert_eq!(values["cout"], OutputValue::GF2(sum >> 1 == 1));
+        }
+    }
+
+    #[test]
+    fn test_blif_covers() {
+        // off-set cover (nand), constants and a buffered input
+        let src = ".model m\n.inputs a b\n.outputs n one zero buf\n\
+                   .names a b n\n11 0\n.names one\n1\n.names zero\n.names a buf\n1 1\n.end\n";
+        let circuit = parse_blif(src.as_bytes()).unwrap();
+        assert!(matches!(
+            circuit.program[2..],
+            [
+                CombineOperation::GF2(Operation::Mul(2, 0, 1)),
+                CombineOperation::GF2(Operation::AddConst(3, 2, true)),
+                CombineOperation::GF2(Operation::Const(4, true)),
+                CombineOperation::GF2(Operation::AddConst(5, 4, false)),
+                CombineOperation::GF2(Operation::Const(6, false)),
+                CombineOperation::GF2(Operation::AddConst(7, 0, false)),
+            ]
+        ));
+        assert_eq!(circuit.outputs[0], ("n".to_string(), 3));
+    }
+
+    #[test]
+    fn test_blif_errors() {
+        let parse = |body: &str| {
+            let src = format!(".model m\n.inputs a\n.outputs o\n{}\n.end\n", body);
+            parse_blif(src.as_bytes())
+        };
+        assert!(parse(".names a o\n1 1").is_ok());
+        assert!(parse(".names b o\n1 1").is_err()); // undefined signal
+        assert!(parse(".names t o\n1 1\n.names o t\n1 1").is_err()); // cycle
+        assert!(parse(".names a o\n1 1\n0 0").is_err()); // mixed cover
+        assert!(parse(".names a o\n11 1").is_err()); // wrong width
+        assert!(parse(".latch a o re clk 0").is_err()); // sequential
+    }
+}
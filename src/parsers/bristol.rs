@@ -0,0 +1,310 @@
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+use super::{invalid, invalid_line};
+use crate::proof::Output;
+use crate::{CombineOperation, Operation};
+
+/// A circuit in (standard) Bristol Fashion.
+///
+/// The input values occupy the first wires and the output values the last wires of the circuit,
+/// each value is a block of consecutive bits.
+#[derive(Debug, Clone)]
+pub struct BristolCircuit {
+    /// Gf2 program: an `Input` for every input wire (in order) followed by the gates.
+    pub program: Vec<CombineOperation>,
+
+    /// Total number of wires, including the temporaries.
+    pub wires: usize,
+
+    /// Wires appended after the wires of the circuit to lower NAND, OR and XNOR gates.
+    pub temporaries: usize,
+
+    /// Number of bits of every input value.
+    pub inputs: Vec<usize>,
+
+    /// Number of bits of every output value.
+    pub outputs: Vec<usize>,
+}
+
+impl BristolCircuit {
+    /// Output wires labelled "out{value}[{bit}]".
+    pub fn output_wires(&self) -> Vec<Output> {
+        let mut wire = self.wires - self.temporaries - self.outputs.iter().sum::<usize>();
+        let mut outputs = vec![];
+        for (value, bits) in self.outputs.iter().enumerate() {
+            for bit in 0..*bits {
+                outputs.push(Output::gf2(format!("out{}[{}]", value, bit), wire));
+                wire += 1;
+            }
+        }
+        outputs
+    }
+}
+
+fn numbers<T: FromStr>(line: usize, tokens: &[&str]) -> io::Result<Vec<T>> {
+    tokens
+        .iter()
+        .map(|t| {
+            t.parse()
+                .map_err(|_| invalid_line(line, format!("expected a number, found '{}'", t)))
+        })
+        .collect()
+}
+
+// parses "<count> <n_1> ... <n_count>"
+fn value_sizes(line: usize, tokens: &[&str]) -> io::Result<Vec<usize>> {
+    let nums: Vec<usize> = numbers(line, tokens)?;
+    match nums.split_first() {
+        Some((count, sizes)) if *count == sizes.len() => Ok(sizes.to_vec()),
+        _ => Err(invalid_line(line, "malformed input/output declaration")),
+    }
+}
+
+/// Parses a Bristol Fashion circuit (XOR, AND, INV/NOT, NAND, OR, XNOR, EQ, EQW, CONST0/CONST1
+/// and MAND gates).
+pub fn parse_bristol<R: BufRead>(reader: R) -> io::Result<BristolCircuit> {
+    let mut lines = reader
+        .lines()
+        .enumerate()
+        .map(|(n, line)| line.map(|line| (n, line)));
+    let mut next_line = || -> io::Result<Option<(usize, String)>> {
+        for line in lines.by_ref() {
+            let (n, line) = line?;
+            if !line.trim().is_empty() {
+                return Ok(Some((n, line)));
+            }
+        }
+        Ok(None)
+    };
+
+    // header
+    let mut header = vec![];
+    for _ in 0..3 {
+        match next_line()? {
+            Some(line) => header.push(line),
+            None => return Err(invalid("truncated header")),
+        }
+    }
+    let (n, ref line) = header[0];
+    let counts: Vec<usize> = numbers(n, &line.split_whitespace().collect::<Vec<_>>())?;
+    let (gates, wires) = match counts[..] {
+        [gates, wires] => (gates, wires),
+        _ => return Err(invalid_line(n, "expected '<gates> <wires>'")),
+    };
+    let (n, ref line) = header[1];
+    let inputs = value_sizes(n, &line.split_whitespace().collect::<Vec<_>>())?;
+    let (n, ref line) = header[2];
+    let outputs = value_sizes(n, &line.split_whitespace().collect::<Vec<_>>())?;
+
+    let input_wires: usize = inputs.iter().sum();
+    if input_wires + outputs.iter().sum::<usize>() > wires {
+        return Err(invalid("more input/output wires than wires"));
+    }
+
+    let mut program = Vec::with_capacity(input_wires + gates);
+    for wire in 0..input_wires {
+        program.push(CombineOperation::GF2(Operation::Input(wire)));
+    }
+
+    // gates
+    let mut temporaries = 0;
+    let mut temp = || {
+        temporaries += 1;
+        wires + temporaries - 1
+    };
+    let mut parsed = 0;
+    while let Some((n, line)) = next_line()? {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let (kind, tokens) = tokens.split_last().unwrap();
+        let nums: Vec<usize> = numbers(n, tokens)?;
+        let (n_in, n_out, wires_io) = match nums[..] {
+            [n_in, n_out, ref wires_io @ ..] if wires_io.len() == n_in + n_out => {
+                (n_in, n_out, wires_io)
+            }
+            _ => return Err(invalid_line(n, "malformed gate")),
+        };
+        // the input of EQ is a constant
+        let checked = if *kind == "EQ" {
+            &wires_io[n_in..]
+        } else {
+            wires_io
+        };
+        if let Some(wire) = checked.iter().find(|w| **w >= wires) {
+            return Err(invalid_line(n, format!("wire {} out of range", wire)));
+        }
+        let (ins, outs) = wires_io.split_at(n_in);
+
+        let op = |op| CombineOperation::GF2(op);
+        match (*kind, n_in, n_out) {
+            ("XOR", 2, 1) => program.push(op(Operation::Add(outs[0], ins[0], ins[1]))),
+            ("AND", 2, 1) => program.push(op(Operation::Mul(outs[0], ins[0], ins[1]))),
+            ("INV", 1, 1) | ("NOT", 1, 1) => {
+                program.push(op(Operation::AddConst(outs[0], ins[0], true)))
+            }
+            ("NAND", 2, 1) => {
+                let t = temp();
+                program.push(op(Operation::Mul(t, ins[0], ins[1])));
+                program.push(op(Operation::AddConst(outs[0], t, true)));
+            }
+            ("OR", 2, 1) => {
+                // a | b = !(!a & !b)
+                let (na, nb, t) = (temp(), temp(), temp());
+                program.push(op(Operation::AddConst(na, ins[0], true)));
+                program.push(op(Operation::AddConst(nb, ins[1], true)));
+                program.push(op(Operation::Mul(t, na, nb)));
+                program.push(op(Operation::AddConst(outs[0], t, true)));
+            }
+            ("XNOR", 2, 1) => {
+                let t = temp();
+                program.push(op(Operation::Add(t, ins[0], ins[1])));
+                program.push(op(Operation::AddConst(outs[0], t, true)));
+            }
+            ("EQW", 1, 1) => program.push(op(Operation::AddConst(outs[0], ins[0], false))),
+            ("EQ", 1, 1) => match ins[0] {
+                0 | 1 => program.push(op(Operation::Const(outs[0], ins[0] == 1))),
+                _ => return Err(invalid_line(n, "EQ constant must be 0 or 1")),
+            },
+            // dedicated constant wires (e.g. CBMC-GC)
+            ("CONST0", 0, 1) => program.push(op(Operation::Const(outs[0], false))),
+            ("CONST1", 0, 1) => program.push(op(Operation::Const(outs[0], true))),
+            // n_out ANDs: the left operands are followed by the right operands
+            ("MAND", _, _) if n_in == 2 * n_out && n_out > 0 => {
+                for (i, out) in outs.iter().enumerate() {
+                    program.push(op(Operation::Mul(*out, ins[i], ins[n_out + i])));
+                }
+            }
+            _ => {
+                return Err(invalid_line(
+                    n,
+                    format!("unsupported gate {} ({} in, {} out)", kind, n_in, n_out),
+                ))
+            }
+        }
+        parsed += 1;
+    }
+
+    if parsed != gates {
+        return Err(invalid(format!(
+            "expected {} gates, found {}",
+            gates, parsed
+        )));
+    }
+
+    Ok(BristolCircuit {
+        program,
+        wires: wires + temporaries,
+        temporaries,
+        inputs,
+        outputs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::{OutputValue, Proof, ProverConfig};
+    use std::sync::Arc;
+
+    // 2-bit adder (without carry out): out = a + b mod 4
+    const ADDER: &str = "\
+5 9
+2 2 2
+1 2
+
+2 1 0 2 4 XOR
+2 1 0 2 5 AND
+2 1 1 3 6 XOR
+1 1 4 7 EQW
+2 1 5 6 8 XOR
+";
+
+    #[test]
+    fn test_bristol_adder() {
+        let circuit = parse_bristol(ADDER.as_bytes()).unwrap();
+        assert_eq!(circuit.inputs, vec![2, 2]);
+        assert_eq!(circuit.outputs, vec![2]);
+        assert_eq!(circuit.program.len(), 4 + 5);
+
+        let outputs = circuit.output_wires();
+        let program = Arc::new(circuit.program);
+        for a in 0..4u8 {
+            for b in 0..4u8 {
+                let bits = [a & 1, a >> 1, b & 1, b >> 1];
+                let wit_gf2 = Arc::new(bits.iter().map(|b| *b == 1).collect());
+                let proof = Proof::new_with_config(
+                    program.clone(),
+                    &outputs,
+                    wit_gf2,
+                    Arc::new(vec![]),
+                    (0, circuit.wires),
+                    &ProverConfig::default(),
+                );
+                let values = proof
+                    .verify_with_outputs(program.clone(), &outputs, (0, circuit.wires))
+                    .unwrap();
+                let sum = (a + b) % 4;
+                assert_eq!(values["out0[0]"], OutputValue::GF2(sum & 1 == 1));
+                assert_eq!(values["out0[1]"], OutputValue::GF2(sum >> 1 == 1));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bristol_gates() {
+        let src = "5 8\n1 2\n1 1\n1 1 0 3 INV\n1 1 1 2 EQ\n4 2 0 1 3 2 4 5 MAND\n\
+                   0 1 6 CONST0\n0 1 7 CONST1\n";
+        let circuit = parse_bristol(src.as_bytes()).unwrap();
+        assert!(matches!(
+            circuit.program[2..],
+            [
+                CombineOperation::GF2(Operation::AddConst(3, 0, true)),
+                CombineOperation::GF2(Operation::Const(2, true)),
+                CombineOperation::GF2(Operation::Mul(4, 0, 3)),
+                CombineOperation::GF2(Operation::Mul(5, 1, 2)),
+                CombineOperation::GF2(Operation::Const(6, false)),
+                CombineOperation::GF2(Operation::Const(7, true)),
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_bristol_lowered_gates() {
+        // out = (NAND(a, b), OR(a, b), XNOR(a, b), NOT(a))
+        let src = "4 6\n2 1 1\n1 4\n2 1 0 1 2 NAND\n2 1 0 1 3 OR\n2 1 0 1 4 XNOR\n1 1 0 5 NOT\n";
+        let circuit = parse_bristol(src.as_bytes()).unwrap();
+        assert_eq!(circuit.temporaries, 5);
+        assert_eq!(circuit.wires, 6 + 5);
+
+        let outputs = circuit.output_wires();
+        let program = Arc::new(circuit.program);
+        for (a, b) in [(false, false), (false, true), (true, false), (true, true)] {
+            let proof = Proof::new_with_config(
+                program.clone(),
+                &outputs,
+                Arc::new(vec![a, b]),
+                Arc::new(vec![]),
+                (0, circuit.wires),
+                &ProverConfig::default(),
+            );
+            let values = proof
+                .verify_with_outputs(program.clone(), &outputs, (0, circuit.wires))
+                .unwrap();
+            assert_eq!(values["out0[0]"], OutputValue::GF2(!(a & b)));
+            assert_eq!(values["out0[1]"], OutputValue::GF2(a | b));
+            assert_eq!(values["out0[2]"], OutputValue::GF2(a == b));
+            assert_eq!(values["out0[3]"], OutputValue::GF2(!a));
+        }
+    }
+
+    #[test]
+    fn test_bristol_errors() {
+        // wrong gate count, unknown gate, out of range wire, malformed header, malformed MAND
+        assert!(parse_bristol("2 3\n1 1\n1 1\n1 1 0 1 INV\n".as_bytes()).is_err());
+        assert!(parse_bristol("1 3\n1 1\n1 1\n2 1 0 1 2 NOR\n".as_bytes()).is_err());
+        assert!(parse_bristol("1 3\n1 1\n1 1\n2 1 0 1 9 AND\n".as_bytes()).is_err());
+        assert!(parse_bristol("1\n1 1\n1 1\n".as_bytes()).is_err());
+        assert!(parse_bristol("1 5\n1 3\n1 1\n3 1 0 1 2 4 MAND\n".as_bytes()).is_err());
+        assert!(parse_bristol("1 3\n1 2\n1 1\n0 0 MAND\n".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,409 @@
+use std::io::{self, Read, Write};
+
+use mcircuit::WireValue;
+use serde::{Deserialize, Serialize};
+
+use super::invalid;
+use crate::proof::{Output, OutputWire};
+use crate::{largest_wires, CombineOperation, Operation};
+
+/// Domain of a wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Domain {
+    Gf2,
+    Z64,
+}
+
+/// Number of wires (or inputs) in every domain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonCounts {
+    pub gf2: usize,
+    pub z64: usize,
+}
+
+/// A labelled output wire.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonOutput {
+    pub label: String,
+    pub domain: Domain,
+    pub wire: usize,
+}
+
+/// A gate, e.g. `{"op": "mul", "domain": "gf2", "dst": 3, "a": 1, "b": 2}`.
+///
+/// Constants are integers (0 or 1 in gf2), `b2a` converts the gf2 bits
+/// `src, .., src + 63` (least significant first) to the z64 wire `dst`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum JsonGate {
+    Input {
+        domain: Domain,
+        dst: usize,
+    },
+    Random {
+        domain: Domain,
+        dst: usize,
+    },
+    Const {
+        domain: Domain,
+        dst: usize,
+        value: u64,
+    },
+    Add {
+        domain: Domain,
+        dst: usize,
+        a: usize,
+        b: usize,
+    },
+    Sub {
+        domain: Domain,
+        dst: usize,
+        a: usize,
+        b: usize,
+    },
+    Mul {
+        domain: Domain,
+        dst: usize,
+        a: usize,
+        b: usize,
+    },
+    AddConst {
+        domain: Domain,
+        dst: usize,
+        a: usize,
+        value: u64,
+    },
+    SubConst {
+        domain: Domain,
+        dst: usize,
+        a: usize,
+        value: u64,
+    },
+    MulConst {
+        domain: Domain,
+        dst: usize,
+        a: usize,
+        value: u64,
+    },
+    AssertZero {
+        domain: Domain,
+        a: usize,
+    },
+    B2a {
+        dst: usize,
+        src: usize,
+    },
+}
+
+/// A circuit in the JSON schema: wire counts, input/output declarations and gates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonCircuit {
+    /// Number of wires in every domain, all wire indices must be below these.
+    pub wires: JsonCounts,
+
+    /// Number of `input` gates in every domain (the length of the witness).
+    pub inputs: JsonCounts,
+
+    #[serde(default)]
+    pub outputs: Vec<JsonOutput>,
+
+    pub gates: Vec<JsonGate>,
+}
+
+fn gate<T: WireValue>(op: Operation<T>, domain: Domain, value: impl Fn(T) -> u64) -> JsonGate {
+    match op {
+        Operation::Input(dst) => JsonGate::Input { domain, dst },
+        Operation::Random(dst) => JsonGate::Random { domain, dst },
+        Operation::Const(dst, c) => JsonGate::Const {
+            domain,
+            dst,
+            value: value(c),
+        },
+        Operation::Add(dst, a, b) => JsonGate::Add { domain, dst, a, b },
+        Operation::Sub(dst, a, b) => JsonGate::Sub { domain, dst, a, b },
+        Operation::Mul(dst, a, b) => JsonGate::Mul { domain, dst, a, b },
+        Operation::AddConst(dst, a, c) => JsonGate::AddConst {
+            domain,
+            dst,
+            a,
+            value: value(c),
+        },
+        Operation::SubConst(dst, a, c) => JsonGate::SubConst {
+            domain,
+            dst,
+            a,
+            value: value(c),
+        },
+        Operation::MulConst(dst, a, c) => JsonGate::MulConst {
+            domain,
+            dst,
+            a,
+            value: value(c),
+        },
+        Operation::AssertZero(a) => JsonGate::AssertZero { domain, a },
+    }
+}
+
+impl JsonCircuit {
+    /// Describes a program (`SizeHint` operations are dropped).
+    pub fn from_program(program: &[CombineOperation], outputs: &[Output]) -> Self {
+        let (z64, gf2) = largest_wires(program);
+        let mut inputs = JsonCounts::default();
+        let mut gates = Vec::with_capacity(program.len());
+        for op in program {
+            gates.push(match *op {
+                CombineOperation::GF2(op) => {
+                    inputs.gf2 += matches!(op, Operation::Input(_)) as usize;
+                    gate(op, Domain::Gf2, u64::from)
+                }
+                CombineOperation::Z64(op) => {
+                    inputs.z64 += matches!(op, Operation::Input(_)) as usize;
+                    gate(op, Domain::Z64, |c| c)
+                }
+                CombineOperation::B2A(dst, src) => JsonGate::B2a { dst, src },
+                CombineOperation::SizeHint(_, _) => continue,
+            });
+        }
+        let outputs = outputs
+            .iter()
+            .map(|o| match o.wire {
+                OutputWire::GF2(wire) => JsonOutput {
+                    label: o.label.clone(),
+                    domain: Domain::Gf2,
+                    wire,
+                },
+                OutputWire::Z64(wire) => JsonOutput {
+                    label: o.label.clone(),
+                    domain: Domain::Z64,
+                    wire,
+                },
+            })
+            .collect();
+        JsonCircuit {
+            wires: JsonCounts { gf2, z64 },
+            inputs,
+            outputs,
+            gates,
+        }
+    }
+
+    /// Lowers the gates to a program, checking wire indices, constants and the input counts.
+    pub fn program(&self) -> io::Result<Vec<CombineOperation>> {
+        let mut inputs = JsonCounts::default();
+        let mut program = Vec::with_capacity(self.gates.len());
+        for (idx, gate) in self.gates.iter().enumerate() {
+            let error = |msg: String| invalid(format!("gate {}: {}", idx, msg));
+            let wire = |domain: Domain, wire: usize| -> io::Result<usize> {
+                let count = match domain {
+                    Domain::Gf2 => self.wires.gf2,
+                    Domain::Z64 => self.wires.z64,
+                };
+                if wire >= count {
+                    return Err(error(format!("{:?} wire {} out of range", domain, wire)));
+                }
+                Ok(wire)
+            };
+            let bit = |value: u64| -> io::Result<bool> {
+                match value {
+                    0 | 1 => Ok(value == 1),
+                    _ => Err(error(format!("gf2 constant {} is not a bit", value))),
+                }
+            };
+
+            let domain = match gate {
+                JsonGate::B2a { dst, src } => {
+                    wire(Domain::Z64, *dst)?;
+                    let last = src.checked_add(63).ok_or_else(|| {
+                        error(format!("{:?} wire {} + 63 out of range", Domain::Gf2, src))
+                    })?;
+                    wire(Domain::Gf2, last)?;
+                    program.push(CombineOperation::B2A(*dst, *src));
+                    continue;
+                }
+                JsonGate::Input { domain, .. }
+                | JsonGate::Random { domain, .. }
+                | JsonGate::Const { domain, .. }
+                | JsonGate::Add { domain, .. }
+                | JsonGate::Sub { domain, .. }
+                | JsonGate::Mul { domain, .. }
+                | JsonGate::AddConst { domain, .. }
+                | JsonGate::SubConst { domain, .. }
+                | JsonGate::MulConst { domain, .. }
+                | JsonGate::AssertZero { domain, .. } => *domain,
+            };
+            let w = |index: usize| wire(domain, index);
+
+            // lowered with the constant as a u64, then converted to the domain
+            let op: Operation<u64> = match *gate {
+                JsonGate::Input { dst, .. } => {
+                    match domain {
+                        Domain::Gf2 => inputs.gf2 += 1,
+                        Domain::Z64 => inputs.z64 += 1,
+                    }
+                    Operation::Input(w(dst)?)
+                }
+                JsonGate::Random { dst, .. } => Operation::Random(w(dst)?),
+                JsonGate::Const { dst, value, .. } => Operation::Const(w(dst)?, value),
+                JsonGate::Add { dst, a, b, .. } => Operation::Add(w(dst)?, w(a)?, w(b)?),
+                JsonGate::Sub { dst, a, b, .. } => Operation::Sub(w(dst)?, w(a)?, w(b)?),
+                JsonGate::Mul { dst, a, b, .. } => Operation::Mul(w(dst)?, w(a)?, w(b)?),
+                JsonGate::AddConst { dst, a, value, .. } => {
+                    Operation::AddConst(w(dst)?, w(a)?, value)
+                }
+                JsonGate::SubConst { dst, a, value, .. } => {
+                    Operation::SubConst(w(dst)?, w(a)?, value)
+                }
+                JsonGate::MulConst { dst, a, value, .. } => {
+                    Operation::MulConst(w(dst)?, w(a)?, value)
+                }
+                JsonGate::AssertZero { a, .. } => Operation::AssertZero(w(a)?),
+                JsonGate::B2a { .. } => unreachable!(),
+            };
+            program.push(match domain {
+                Domain::Z64 => CombineOperation::Z64(op),
+                Domain::Gf2 => CombineOperation::GF2(match op {
+                    Operation::Input(d) => Operation::Input(d),
+                    Operation::Random(d) => Operation::Random(d),
+                    Operation::Const(d, c) => Operation::Const(d, bit(c)?),
+                    Operation::Add(d, a, b) => Operation::Add(d, a, b),
+                    Operation::Sub(d, a, b) => Operation::Sub(d, a, b),
+                    Operation::Mul(d, a, b) => Operation::Mul(d, a, b),
+                    Operation::AddConst(d, a, c) => Operation::AddConst(d, a, bit(c)?),
+                    Operation::SubConst(d, a, c) => Operation::SubConst(d, a, bit(c)?),
+                    Operation::MulConst(d, a, c) => Operation::MulConst(d, a, bit(c)?),
+                    Operation::AssertZero(a) => Operation::AssertZero(a),
+                }),
+            });
+        }
+
+        if inputs != self.inputs {
+            return Err(invalid(format!(
+                "declared {} gf2 and {} z64 inputs, found {} and {}",
+                self.inputs.gf2, self.inputs.z64, inputs.gf2, inputs.z64
+            )));
+        }
+        for output in self.outputs.iter() {
+            let count = match output.domain {
+                Domain::Gf2 => self.wires.gf2,
+                Domain::Z64 => self.wires.z64,
+            };
+            if output.wire >= count {
+                return Err(invalid(format!(
+                    "output '{}': wire {} out of range",
+                    output.label, output.wire
+                )));
+            }
+        }
+        Ok(program)
+    }
+
+    /// The declared outputs.
+    pub fn output_wires(&self) -> Vec<Output> {
+        self.outputs
+            .iter()
+            .map(|o| match o.domain {
+                Domain::Gf2 => Output::gf2(o.label.clone(), o.wire),
+                Domain::Z64 => Output::z64(o.label.clone(), o.wire),
+            })
+            .collect()
+    }
+
+    /// Wire counts in the order used by the prover: (z64, gf2).
+    pub fn wire_counts(&self) -> (usize, usize) {
+        (self.wires.z64, self.wires.gf2)
+    }
+}
+
+/// Parses (and validates) a circuit in the JSON schema.
+pub fn parse_json<R: Read>(reader: R) -> io::Result<JsonCircuit> {
+    let circuit: JsonCircuit = serde_json::from_reader(reader)?;
+    circuit.program()?;
+    Ok(circuit)
+}
+
+/// Writes a circuit in the JSON schema.
+pub fn write_json<W: Write>(writer: W, circuit: &JsonCircuit) -> io::Result<()> {
+    serde_json::to_writer_pretty(writer, circuit)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::{OutputValue, Proof, ProverConfig};
+    use std::sync::Arc;
+
+    const CIRCUIT: &str = r#"{
+        "wires": {"gf2": 3, "z64": 3},
+        "inputs": {"gf2": 2, "z64": 1},
+        "outputs": [
+            {"label": "and", "domain": "gf2", "wire": 2},
+            {"label": "square", "domain": "z64", "wire": 2}
+        ],
+        "gates": [
+            {"op": "input", "domain": "gf2", "dst": 0},
+            {"op": "input", "domain": "gf2", "dst": 1},
+            {"op": "mul", "domain": "gf2", "dst": 2, "a": 0, "b": 1},
+            {"op": "input", "domain": "z64", "dst": 0},
+            {"op": "mul", "domain": "z64", "dst": 1, "a": 0, "b": 0},
+            {"op": "add_const", "domain": "z64", "dst": 2, "a": 1, "value": 1}
+        ]
+    }"#;
+
+    #[test]
+    fn test_json_prove() {
+        let circuit = parse_json(CIRCUIT.as_bytes()).unwrap();
+        let outputs = circuit.output_wires();
+        let program = Arc::new(circuit.program().unwrap());
+        let proof = Proof::new_with_config(
+            program.clone(),
+            &outputs,
+            Arc::new(vec![true, true]),
+            Arc::new(vec![5]),
+            circuit.wire_counts(),
+            &ProverConfig::default(),
+        );
+        let values = proof
+            .verify_with_outputs(program, &outputs, circuit.wire_counts())
+            .unwrap();
+        assert_eq!(values["and"], OutputValue::GF2(true));
+        assert_eq!(values["square"], OutputValue::Z64(26));
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        let circuit = parse_json(CIRCUIT.as_bytes()).unwrap();
+        let mut program = circuit.program().unwrap();
+        program.push(CombineOperation::B2A(2, 0));
+        program.push(CombineOperation::GF2(Operation::Const(63, true)));
+
+        let emitted = JsonCircuit::from_program(&program, &circuit.output_wires());
+        assert_eq!(emitted.wires, JsonCounts { gf2: 64, z64: 3 });
+        assert_eq!(emitted.inputs, circuit.inputs);
+        assert_eq!(emitted.outputs, circuit.outputs);
+
+        let mut json = vec![];
+        write_json(&mut json, &emitted).unwrap();
+        assert_eq!(parse_json(&json[..]).unwrap(), emitted);
+    }
+
+    #[test]
+    fn test_json_errors() {
+        let parse = |gates: &str| {
+            let src = format!(
+                r#"{{"wires": {{"gf2": 2, "z64": 1}}, "inputs": {{"gf2": 1, "z64": 0}},
+                    "gates": [{{"op": "input", "domain": "gf2", "dst": 0}}, {}]}}"#,
+                gates
+            );
+            parse_json(src.as_bytes())
+        };
+        assert!(parse(r#"{"op": "const", "domain": "gf2", "dst": 1, "value": 1}"#).is_ok());
+        assert!(parse(r#"{"op": "const", "domain": "gf2", "dst": 1, "value": 2}"#).is_err());
+        assert!(parse(r#"{"op": "add", "domain": "gf2", "dst": 2, "a": 0, "b": 0}"#).is_err());
+        assert!(parse(r#"{"op": "input", "domain": "gf2", "dst": 1}"#).is_err());
+        assert!(parse(r#"{"op": "input", "domain": "z64", "dst": 0}"#).is_err());
+        assert!(parse(r#"{"op": "nand", "domain": "gf2", "dst": 1}"#).is_err());
+        assert!(parse(r#"{"op": "b2a", "dst": 0, "src": 18446744073709551615}"#).is_err());
+    }
+}
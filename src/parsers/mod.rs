@@ -0,0 +1,26 @@
+//! Frontends lowering external circuit formats to `CombineOperation` programs.
+
+mod aiger;
+mod binary;
+mod blif;
+mod bristol;
+mod json;
+mod sieve;
+
+pub use aiger::{parse_aiger, AigerCircuit};
+pub use binary::{read_program, write_program, ProgramReader, PROGRAM_CHUNK_SIZE, PROGRAM_MAGIC};
+pub use blif::{parse_blif, BlifCircuit};
+pub use bristol::{parse_bristol, BristolCircuit};
+pub use json::{parse_json, write_json, Domain, JsonCircuit, JsonCounts, JsonGate, JsonOutput};
+pub use sieve::{parse_sieve_text, SieveCircuit};
+
+use std::io;
+
+fn invalid<S: Into<String>>(msg: S) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
+}
+
+// error for line `line` (0-indexed)
+fn invalid_line<S: AsRef<str>>(line: usize, msg: S) -> io::Error {
+    invalid(format!("line {}: {}", line + 1, msg.as_ref()))
+}
@@ -0,0 +1,978 @@
+//! SIEVE IR (text) frontend.
+//!
+//! Supports relations over GF(2) (`field characteristic 2`) and Z_2^64
+//! (`field characteristic 18446744073709551616`) using the gates
+//! `@add`, `@mul`, `@addc`, `@mulc`, `@and`, `@xor`, `@not`, constants, copies, `@assert_zero`,
+//! `@instance`, `@short_witness`, `@delete`, `@function`/`@call`, `@anon_call` and `@for`.
+//! Functions and loops are inlined (unrolled) while lowering; instance values become constants.
+
+use std::collections::HashMap;
+use std::io;
+
+use super::invalid;
+use crate::{CombineOperation, Operation};
+
+const Z64_CHARACTERISTIC: u128 = 1 << 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String), // keywords (with leading '@') and names
+    Num(u128),
+    Wire(u64),
+    WireExpr, // "$(", an iterator expression follows
+    Arrow,
+    Lt,
+    Gt,
+    LParen,
+    RParen,
+    Comma,
+    Semi,
+    Colon,
+    Ellipsis,
+    Plus,
+    Minus,
+    Star,
+}
+
+fn parse_num(s: &str) -> Option<u128> {
+    match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u128::from_str_radix(hex, 16).ok(),
+        None => s.parse().ok(),
+    }
+}
+
+fn tokenize(src: &str) -> io::Result<Vec<Token>> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '/' && next == Some('*') {
+            i += 2;
+            while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                i += 1;
+            }
+            i += 2;
+        } else if c == '$' && next == Some('(') {
+            tokens.push(Token::WireExpr);
+            tokens.push(Token::LParen);
+            i += 2;
+        } else if c == '$' {
+            let start = i + 1;
+            i = start;
+            while i < chars.len() && chars[i].is_ascii_digit() {
+                i += 1;
+            }
+            let digits: String = chars[start..i].iter().collect();
+            let wire = digits
+                .parse()
+                .map_err(|_| invalid(format!("malformed wire '${}'", digits)))?;
+            tokens.push(Token::Wire(wire));
+        } else if c.is_ascii_digit() {
+            // numbers (version strings such as 1.0.0 become identifiers)
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric()
+                    || (chars[i] == '.' && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())))
+            {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            match parse_num(&word) {
+                Some(num) => tokens.push(Token::Num(num)),
+                None => tokens.push(Token::Ident(word)),
+            }
+        } else if c.is_alphabetic() || c == '@' || c == '_' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            let (token, len) = match (c, next) {
+                ('<', Some('-')) => (Token::Arrow, 2),
+                ('.', Some('.')) if chars.get(i + 2) == Some(&'.') => (Token::Ellipsis, 3),
+                ('<', _) => (Token::Lt, 1),
+                ('>', _) => (Token::Gt, 1),
+                ('(', _) => (Token::LParen, 1),
+                (')', _) => (Token::RParen, 1),
+                (',', _) => (Token::Comma, 1),
+                (';', _) => (Token::Semi, 1),
+                (':', _) => (Token::Colon, 1),
+                ('+', _) => (Token::Plus, 1),
+                ('-', _) => (Token::Minus, 1),
+                ('*', _) => (Token::Star, 1),
+                _ => return Err(invalid(format!("unexpected character '{}'", c))),
+            };
+            tokens.push(token);
+            i += len;
+        }
+    }
+    Ok(tokens)
+}
+
+// iterator expressions (in @for loops)
+#[derive(Debug, Clone)]
+enum Expr {
+    Num(u64),
+    Var(String),
+    Add(Box<Expr>, Box<Expr>),
+    Sub(Box<Expr>, Box<Expr>),
+    Mul(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn eval(&self, env: &HashMap<String, u64>) -> io::Result<u64> {
+        Ok(match self {
+            Expr::Num(n) => *n,
+            Expr::Var(v) => *env
+                .get(v)
+                .ok_or_else(|| invalid(format!("unknown iterator '{}'", v)))?,
+            Expr::Add(a, b) => a.eval(env)?.wrapping_add(b.eval(env)?),
+            Expr::Sub(a, b) => a.eval(env)?.wrapping_sub(b.eval(env)?),
+            Expr::Mul(a, b) => a.eval(env)?.wrapping_mul(b.eval(env)?),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+enum WireRef {
+    Lit(u64),
+    Expr(Expr),
+}
+
+impl WireRef {
+    fn eval(&self, env: &HashMap<String, u64>) -> io::Result<u64> {
+        match self {
+            WireRef::Lit(w) => Ok(*w),
+            WireRef::Expr(e) => e.eval(env),
+        }
+    }
+}
+
+// a single wire or an inclusive range "$a ... $b"
+#[derive(Debug, Clone)]
+struct WireRange(WireRef, Option<WireRef>);
+
+impl WireRange {
+    fn eval(&self, env: &HashMap<String, u64>) -> io::Result<Vec<u64>> {
+        let first = self.0.eval(env)?;
+        let last = match &self.1 {
+            Some(last) => last.eval(env)?,
+            None => first,
+        };
+        if last < first {
+            return Err(invalid(format!(
+                "empty wire range ${} ... ${}",
+                first, last
+            )));
+        }
+        Ok((first..=last).collect())
+    }
+}
+
+fn eval_ranges(ranges: &[WireRange], env: &HashMap<String, u64>) -> io::Result<Vec<u64>> {
+    let mut wires = vec![];
+    for range in ranges {
+        wires.extend(range.eval(env)?);
+    }
+    Ok(wires)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Gate {
+    Add,
+    Mul,
+    And,
+    Xor,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ConstGate {
+    AddConst,
+    MulConst,
+}
+
+#[derive(Debug, Clone)]
+enum Rhs {
+    Instance,
+    Witness,
+    Const(u128),
+    Copy(WireRef),
+    Gate(Gate, WireRef, WireRef),
+    ConstGate(ConstGate, WireRef, u128),
+    Not(WireRef),
+    Call(String, Vec<WireRange>),
+    AnonCall(Vec<WireRange>, Vec<Directive>),
+    For(String, u64, u64, Box<Directive>),
+}
+
+#[derive(Debug, Clone)]
+enum Directive {
+    Assign(Vec<WireRange>, Rhs),
+    AssertZero(WireRef),
+    Call(String, Vec<WireRange>),
+    Function(Function),
+    Delete,
+}
+
+#[derive(Debug, Clone)]
+struct Function {
+    name: String,
+    outs: usize,
+    ins: usize,
+    body: Vec<Directive>,
+}
+
+struct TokenStream {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl TokenStream {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> io::Result<Token> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| invalid("unexpected end of input"))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> io::Result<()> {
+        let token = self.next()?;
+        if token != expected {
+            return Err(invalid(format!(
+                "token {}: expected {:?}, found {:?}",
+                self.pos - 1,
+                expected,
+                token
+            )));
+        }
+        Ok(())
+    }
+
+    fn eat(&mut self, expected: &Token) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(s)) if s == keyword)
+    }
+
+    fn keyword(&mut self, keyword: &str) -> io::Result<()> {
+        self.expect(Token::Ident(keyword.to_string()))
+    }
+
+    fn ident(&mut self) -> io::Result<String> {
+        match self.next()? {
+            Token::Ident(s) => Ok(s),
+            token => Err(invalid(format!("expected a name, found {:?}", token))),
+        }
+    }
+
+    fn num(&mut self) -> io::Result<u128> {
+        match self.next()? {
+            Token::Num(n) => Ok(n),
+            token => Err(invalid(format!("expected a number, found {:?}", token))),
+        }
+    }
+
+    // "<" num ">"
+    fn field_value(&mut self) -> io::Result<u128> {
+        self.expect(Token::Lt)?;
+        let num = self.num()?;
+        self.expect(Token::Gt)?;
+        Ok(num)
+    }
+
+    // "@keyword" ":" num
+    fn count(&mut self, keywords: &[&str]) -> io::Result<usize> {
+        let name = self.ident()?;
+        if !keywords.contains(&name.as_str()) {
+            return Err(invalid(format!(
+                "expected {:?}, found '{}'",
+                keywords, name
+            )));
+        }
+        self.expect(Token::Colon)?;
+        Ok(self.num()? as usize)
+    }
+
+    fn expr_atom(&mut self) -> io::Result<Expr> {
+        match self.next()? {
+            Token::Num(n) => Ok(Expr::Num(n as u64)),
+            Token::Ident(v) => Ok(Expr::Var(v)),
+            Token::LParen => {
+                let e = self.expr()?;
+                self.expect(Token::RParen)?;
+                Ok(e)
+            }
+            token => Err(invalid(format!("unexpected {:?} in expression", token))),
+        }
+    }
+
+    fn expr_term(&mut self) -> io::Result<Expr> {
+        let mut e = self.expr_atom()?;
+        while self.eat(&Token::Star) {
+            e = Expr::Mul(Box::new(e), Box::new(self.expr_atom()?));
+        }
+        Ok(e)
+    }
+
+    fn expr(&mut self) -> io::Result<Expr> {
+        let mut e = self.expr_term()?;
+        loop {
+            if self.eat(&Token::Plus) {
+                e = Expr::Add(Box::new(e), Box::new(self.expr_term()?));
+            } else if self.eat(&Token::Minus) {
+                e = Expr::Sub(Box::new(e), Box::new(self.expr_term()?));
+            } else {
+                return Ok(e);
+            }
+        }
+    }
+
+    fn wire(&mut self) -> io::Result<WireRef> {
+        match self.next()? {
+            Token::Wire(w) => Ok(WireRef::Lit(w)),
+            Token::WireExpr => {
+                self.expect(Token::LParen)?;
+                let e = self.expr()?;
+                self.expect(Token::RParen)?;
+                Ok(WireRef::Expr(e))
+            }
+            token => Err(invalid(format!("expected a wire, found {:?}", token))),
+        }
+    }
+
+    fn is_wire(&self) -> bool {
+        matches!(self.peek(), Some(Token::Wire(_)) | Some(Token::WireExpr))
+    }
+
+    fn wire_range(&mut self) -> io::Result<WireRange> {
+        let first = self.wire()?;
+        if self.eat(&Token::Ellipsis) {
+            Ok(WireRange(first, Some(self.wire()?)))
+        } else {
+            Ok(WireRange(first, None))
+        }
+    }
+
+    // comma separated wire ranges (possibly empty)
+    fn wire_list(&mut self) -> io::Result<Vec<WireRange>> {
+        let mut list = vec![];
+        if !self.is_wire() {
+            return Ok(list);
+        }
+        list.push(self.wire_range()?);
+        while self.peek() == Some(&Token::Comma)
+            && self
+                .tokens
+                .get(self.pos + 1)
+                .is_some_and(|t| matches!(t, Token::Wire(_) | Token::WireExpr))
+        {
+            self.pos += 1;
+            list.push(self.wire_range()?);
+        }
+        Ok(list)
+    }
+
+    fn binary(&mut self) -> io::Result<(WireRef, WireRef)> {
+        self.expect(Token::LParen)?;
+        let a = self.wire()?;
+        self.expect(Token::Comma)?;
+        let b = self.wire()?;
+        self.expect(Token::RParen)?;
+        Ok((a, b))
+    }
+
+    fn binary_const(&mut self) -> io::Result<(WireRef, u128)> {
+        self.expect(Token::LParen)?;
+        let a = self.wire()?;
+        self.expect(Token::Comma)?;
+        let c = self.field_value()?;
+        self.expect(Token::RParen)?;
+        Ok((a, c))
+    }
+
+    // "(" name ["," wires] ")"
+    fn call(&mut self) -> io::Result<(String, Vec<WireRange>)> {
+        self.expect(Token::LParen)?;
+        let name = self.ident()?;
+        let args = if self.eat(&Token::Comma) {
+            self.wire_list()?
+        } else {
+            vec![]
+        };
+        self.expect(Token::RParen)?;
+        Ok((name, args))
+    }
+
+    // "@begin" directives "@end"
+    fn block(&mut self) -> io::Result<Vec<Directive>> {
+        self.keyword("@begin")?;
+        let mut body = vec![];
+        while !self.is_keyword("@end") {
+            body.push(self.directive()?);
+        }
+        self.keyword("@end")?;
+        Ok(body)
+    }
+
+    fn anon_call(&mut self) -> io::Result<Rhs> {
+        self.expect(Token::LParen)?;
+        let args = self.wire_list()?;
+        if !args.is_empty() {
+            self.expect(Token::Comma)?;
+        }
+        self.count(&["@instance"])?;
+        self.expect(Token::Comma)?;
+        self.count(&["@short_witness", "@witness"])?;
+        self.expect(Token::RParen)?;
+        Ok(Rhs::AnonCall(args, self.block()?))
+    }
+
+    fn rhs(&mut self) -> io::Result<Rhs> {
+        if self.is_wire() {
+            return Ok(Rhs::Copy(self.wire()?));
+        }
+        if self.peek() == Some(&Token::Lt) {
+            return Ok(Rhs::Const(self.field_value()?));
+        }
+        let name = self.ident()?;
+        Ok(match name.as_str() {
+            "@instance" => Rhs::Instance,
+            "@short_witness" | "@witness" => Rhs::Witness,
+            "@add" | "@mul" | "@and" | "@xor" => {
+                let gate = match name.as_str() {
+                    "@add" => Gate::Add,
+                    "@mul" => Gate::Mul,
+                    "@and" => Gate::And,
+                    _ => Gate::Xor,
+                };
+                let (a, b) = self.binary()?;
+                Rhs::Gate(gate, a, b)
+            }
+            "@addc" | "@mulc" => {
+                let gate = if name == "@addc" {
+                    ConstGate::AddConst
+                } else {
+                    ConstGate::MulConst
+                };
+                let (a, c) = self.binary_const()?;
+                Rhs::ConstGate(gate, a, c)
+            }
+            "@not" => {
+                self.expect(Token::LParen)?;
+                let a = self.wire()?;
+                self.expect(Token::RParen)?;
+                Rhs::Not(a)
+            }
+            "@call" => {
+                let (name, args) = self.call()?;
+                Rhs::Call(name, args)
+            }
+            "@anon_call" => self.anon_call()?,
+            "@for" => {
+                let var = self.ident()?;
+                self.keyword("@first")?;
+                let first = self.num()? as u64;
+                self.keyword("@last")?;
+                let last = self.num()? as u64;
+                let body = self.directive()?;
+                self.keyword("@end")?;
+                Rhs::For(var, first, last, Box::new(body))
+            }
+            _ => return Err(invalid(format!("unsupported gate '{}'", name))),
+        })
+    }
+
+    fn directive(&mut self) -> io::Result<Directive> {
+        if self.is_wire() {
+            let outputs = self.wire_list()?;
+            self.expect(Token::Arrow)?;
+            let rhs = self.rhs()?;
+            // blocks are not terminated by a semicolon
+            if !matches!(rhs, Rhs::AnonCall(_, _) | Rhs::For(_, _, _, _)) {
+                self.expect(Token::Semi)?;
+            } else {
+                self.eat(&Token::Semi);
+            }
+            return Ok(Directive::Assign(outputs, rhs));
+        }
+
+        let name = self.ident()?;
+        match name.as_str() {
+            "@assert_zero" => {
+                self.expect(Token::LParen)?;
+                let wire = self.wire()?;
+                self.expect(Token::RParen)?;
+                self.expect(Token::Semi)?;
+                Ok(Directive::AssertZero(wire))
+            }
+            "@delete" => {
+                self.expect(Token::LParen)?;
+                self.wire_range()?;
+                self.expect(Token::RParen)?;
+                self.expect(Token::Semi)?;
+                Ok(Directive::Delete)
+            }
+            "@call" => {
+                let (name, args) = self.call()?;
+                self.expect(Token::Semi)?;
+                Ok(Directive::Call(name, args))
+            }
+            "@function" => {
+                self.expect(Token::LParen)?;
+                let name = self.ident()?;
+                self.expect(Token::Comma)?;
+                let outs = self.count(&["@out"])?;
+                self.expect(Token::Comma)?;
+                let ins = self.count(&["@in"])?;
+                self.expect(Token::Comma)?;
+                self.count(&["@instance"])?;
+                self.expect(Token::Comma)?;
+                self.count(&["@short_witness", "@witness"])?;
+                self.expect(Token::RParen)?;
+                let body = self.block()?;
+                Ok(Directive::Function(Function {
+                    name,
+                    outs,
+                    ins,
+                    body,
+                }))
+            }
+            _ => Err(invalid(format!("unexpected '{}'", name))),
+        }
+    }
+}
+
+// the header of every file: version, field and the resource type; returns (characteristic, kind)
+fn header(ts: &mut TokenStream) -> io::Result<(u128, String)> {
+    ts.keyword("version")?;
+    ts.next()?;
+    ts.expect(Token::Semi)?;
+    ts.keyword("field")?;
+    ts.keyword("characteristic")?;
+    let characteristic = ts.num()?;
+    ts.keyword("degree")?;
+    if ts.num()? != 1 {
+        return Err(invalid("only prime (degree 1) fields are supported"));
+    }
+    ts.expect(Token::Semi)?;
+    let kind = ts.ident()?;
+    Ok((characteristic, kind))
+}
+
+// parses an instance or witness file
+fn parse_values(src: &str, kind: &str, characteristic: u128) -> io::Result<Vec<u128>> {
+    let mut ts = TokenStream {
+        tokens: tokenize(src)?,
+        pos: 0,
+    };
+    let (chr, found) = header(&mut ts)?;
+    if found != kind && !(kind == "short_witness" && found == "witness") {
+        return Err(invalid(format!(
+            "expected a {} file, found {}",
+            kind, found
+        )));
+    }
+    if chr != characteristic {
+        return Err(invalid(format!(
+            "{} field (characteristic {}) differs from the relation ({})",
+            kind, chr, characteristic
+        )));
+    }
+    ts.keyword("@begin")?;
+    let mut values = vec![];
+    while !ts.is_keyword("@end") {
+        values.push(ts.field_value()?);
+        ts.expect(Token::Semi)?;
+    }
+    Ok(values)
+}
+
+struct Lowering<'a> {
+    boolean: bool,
+    functions: HashMap<String, Function>,
+    instance: std::slice::Iter<'a, u128>,
+    program: Vec<CombineOperation>,
+    next_wire: usize,
+}
+
+type Scope = HashMap<u64, usize>;
+
+impl<'a> Lowering<'a> {
+    fn fresh(&mut self) -> usize {
+        let wire = self.next_wire;
+        self.next_wire += 1;
+        wire
+    }
+
+    // constants are reduced into the domain (mod 2^64 first)
+    fn push(&mut self, op: Operation<u64>) {
+        if !self.boolean {
+            self.program.push(CombineOperation::Z64(op));
+            return;
+        }
+        let bit = |c: u64| c & 1 == 1;
+        self.program.push(CombineOperation::GF2(match op {
+            Operation::Input(d) => Operation::Input(d),
+            Operation::Random(d) => Operation::Random(d),
+            Operation::Add(d, a, b) => Operation::Add(d, a, b),
+            Operation::Sub(d, a, b) => Operation::Sub(d, a, b),
+            Operation::Mul(d, a, b) => Operation::Mul(d, a, b),
+            Operation::AddConst(d, a, c) => Operation::AddConst(d, a, bit(c)),
+            Operation::SubConst(d, a, c) => Operation::SubConst(d, a, bit(c)),
+            Operation::MulConst(d, a, c) => Operation::MulConst(d, a, bit(c)),
+            Operation::AssertZero(a) => Operation::AssertZero(a),
+            Operation::Const(d, c) => Operation::Const(d, bit(c)),
+        }));
+    }
+
+    fn get(scope: &Scope, wire: u64) -> io::Result<usize> {
+        scope
+            .get(&wire)
+            .copied()
+            .ok_or_else(|| invalid(format!("wire ${} is not assigned", wire)))
+    }
+
+    // inlines a function body: outputs are fresh, inputs alias the arguments
+    fn call(
+        &mut self,
+        scope: &mut Scope,
+        env: &HashMap<String, u64>,
+        outputs: &[u64],
+        args: &[WireRange],
+        body: &[Directive],
+    ) -> io::Result<()> {
+        let mut inner = Scope::new();
+        for (i, wire) in eval_ranges(args, env)?.into_iter().enumerate() {
+            inner.insert((outputs.len() + i) as u64, Self::get(scope, wire)?);
+        }
+        for directive in body {
+            self.directive(&mut inner, &HashMap::new(), directive)?;
+        }
+        for (i, wire) in outputs.iter().enumerate() {
+            let value = Self::get(&inner, i as u64)?;
+            scope.insert(*wire, value);
+        }
+        Ok(())
+    }
+
+    fn named_call(
+        &mut self,
+        scope: &mut Scope,
+        env: &HashMap<String, u64>,
+        outputs: &[u64],
+        name: &str,
+        args: &[WireRange],
+    ) -> io::Result<()> {
+        let function = self
+            .functions
+            .get(name)
+            .cloned()
+            .ok_or_else(|| invalid(format!("unknown function '{}'", name)))?;
+        let n_args = eval_ranges(args, env)?.len();
+        if function.outs != outputs.len() || function.ins != n_args {
+            return Err(invalid(format!(
+                "function '{}' takes {} inputs and has {} outputs (called with {} and {})",
+                name,
+                function.ins,
+                function.outs,
+                n_args,
+                outputs.len()
+            )));
+        }
+        self.call(scope, env, outputs, args, &function.body)
+    }
+
+    fn directive(
+        &mut self,
+        scope: &mut Scope,
+        env: &HashMap<String, u64>,
+        directive: &Directive,
+    ) -> io::Result<()> {
+        match directive {
+            Directive::Function(function) => {
+                self.functions
+                    .insert(function.name.clone(), function.clone());
+            }
+            Directive::Delete => (),
+            Directive::AssertZero(wire) => {
+                let src = Self::get(scope, wire.eval(env)?)?;
+                self.push(Operation::AssertZero(src));
+            }
+            Directive::Call(name, args) => self.named_call(scope, env, &[], name, args)?,
+            Directive::Assign(outputs, rhs) => {
+                let outputs = eval_ranges(outputs, env)?;
+                let single = || -> io::Result<u64> {
+                    match outputs[..] {
+                        [wire] => Ok(wire),
+                        _ => Err(invalid("gate with more than one output")),
+                    }
+                };
+                match rhs {
+                    Rhs::Call(name, args) => self.named_call(scope, env, &outputs, name, args)?,
+                    Rhs::AnonCall(args, body) => self.call(scope, env, &outputs, args, body)?,
+                    Rhs::For(var, first, last, body) => {
+                        let mut env = env.clone();
+                        for i in *first..=*last {
+                            env.insert(var.clone(), i);
+                            self.directive(scope, &env, body)?;
+                        }
+                    }
+                    Rhs::Copy(src) => {
+                        let src = Self::get(scope, src.eval(env)?)?;
+                        scope.insert(single()?, src);
+                    }
+                    _ => {
+                        let dst = self.fresh();
+                        let op = match rhs {
+                            Rhs::Instance => Operation::Const(
+                                dst,
+                                *self
+                                    .instance
+                                    .next()
+                                    .ok_or_else(|| invalid("instance exhausted"))?
+                                    as u64,
+                            ),
+                            Rhs::Witness => Operation::Input(dst),
+                            Rhs::Const(c) => Operation::Const(dst, *c as u64),
+                            Rhs::Gate(gate, a, b) => {
+                                let a = Self::get(scope, a.eval(env)?)?;
+                                let b = Self::get(scope, b.eval(env)?)?;
+                                match (gate, self.boolean) {
+                                    (Gate::Add, _) | (Gate::Xor, true) => Operation::Add(dst, a, b),
+                                    (Gate::Mul, _) | (Gate::And, true) => Operation::Mul(dst, a, b),
+                                    _ => {
+                                        return Err(invalid(
+                                            "boolean gate in an arithmetic relation",
+                                        ))
+                                    }
+                                }
+                            }
+                            Rhs::ConstGate(gate, a, c) => {
+                                let a = Self::get(scope, a.eval(env)?)?;
+                                match gate {
+                                    ConstGate::AddConst => Operation::AddConst(dst, a, *c as u64),
+                                    ConstGate::MulConst => Operation::MulConst(dst, a, *c as u64),
+                                }
+                            }
+                            Rhs::Not(a) => {
+                                if !self.boolean {
+                                    return Err(invalid("boolean gate in an arithmetic relation"));
+                                }
+                                let a = Self::get(scope, a.eval(env)?)?;
+                                Operation::AddConst(dst, a, 1)
+                            }
+                            Rhs::Call(_, _) | Rhs::AnonCall(_, _) | Rhs::For(_, _, _, _) => {
+                                unreachable!()
+                            }
+                            Rhs::Copy(_) => unreachable!(),
+                        };
+                        self.push(op);
+                        scope.insert(single()?, dst);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A SIEVE IR relation lowered to a program (in a single domain).
+#[derive(Debug, Clone)]
+pub struct SieveCircuit {
+    /// The lowered relation: instance values are constants, witness values are inputs.
+    pub program: Vec<CombineOperation>,
+
+    /// The witness (if provided), in the order consumed by the program.
+    pub wit_gf2: Vec<bool>,
+    pub wit_z64: Vec<u64>,
+}
+
+/// Parses a SIEVE IR text relation along with its instance (and, when proving, witness).
+pub fn parse_sieve_text(
+    relation: &str,
+    instance: &str,
+    witness: Option<&str>,
+) -> io::Result<SieveCircuit> {
+    let mut ts = TokenStream {
+        tokens: tokenize(relation)?,
+        pos: 0,
+    };
+    let (characteristic, kind) = header(&mut ts)?;
+    if kind != "relation" {
+        return Err(invalid(format!("expected a relation, found {}", kind)));
+    }
+    let boolean = match characteristic {
+        2 => true,
+        Z64_CHARACTERISTIC => false,
+        _ => {
+            return Err(invalid(format!(
+                "unsupported field characteristic {}",
+                characteristic
+            )))
+        }
+    };
+
+    // gate_set and features are informational: unsupported gates are rejected while parsing
+    while !ts.is_keyword("@begin") {
+        ts.next()?;
+    }
+    let body = ts.block()?;
+
+    let instance = parse_values(instance, "instance", characteristic)?;
+    let mut lowering = Lowering {
+        boolean,
+        functions: HashMap::new(),
+        instance: instance.iter(),
+        program: vec![],
+        next_wire: 0,
+    };
+    let mut scope = Scope::new();
+    for directive in body.iter() {
+        lowering.directive(&mut scope, &HashMap::new(), directive)?;
+    }
+
+    let mut circuit = SieveCircuit {
+        program: lowering.program,
+        wit_gf2: vec![],
+        wit_z64: vec![],
+    };
+    if let Some(witness) = witness {
+        let values = parse_values(witness, "short_witness", characteristic)?;
+        if boolean {
+            circuit.wit_gf2 = values.iter().map(|v| v & 1 == 1).collect();
+        } else {
+            circuit.wit_z64 = values.iter().map(|v| *v as u64).collect();
+        }
+    }
+    Ok(circuit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::Proof;
+
+    const BOOL_RELATION: &str = "
+version 1.0.0;
+field characteristic 2 degree 1;
+relation
+gate_set: boolean;
+features: @for, @function;
+@begin
+  // and of an instance bit and a witness bit
+  @function(and_not, @out: 1, @in: 2, @instance: 0, @short_witness: 0)
+  @begin
+    $3 <- @and($1, $2);
+    $0 <- @not($3);
+  @end
+
+  $0 <- @instance;
+  $1 ... $4 <- @for i @first 1 @last 4
+    $(i) <- @anon_call($(i - 1), @instance: 0, @short_witness: 1)
+    @begin
+      $2 <- @short_witness;
+      $0 <- @xor($1, $2);
+    @end
+  @end
+  $5 <- @call(and_not, $4, $0);
+  $6 <- $5;
+  @assert_zero($6);
+  @delete($0 ... $6);
+@end
+";
+
+    const BOOL_INSTANCE: &str = "
+version 1.0.0;
+field characteristic 2 degree 1;
+instance
+@begin
+  < 1 >;
+@end
+";
+
+    #[test]
+    fn test_sieve_boolean() {
+        // not(and(1 ^ w1 ^ w2 ^ w3 ^ w4, 1)) = 0
+        let witness = "version 1.0.0; field characteristic 2 degree 1; short_witness
+            @begin <1>; <1>; <0>; <0>; @end";
+        let circuit = parse_sieve_text(BOOL_RELATION, BOOL_INSTANCE, Some(witness)).unwrap();
+        assert_eq!(circuit.wit_gf2, vec![true, true, false, false]);
+        Proof::from_program(&circuit.program, &circuit.wit_gf2, &[])
+            .verify_program(&circuit.program)
+            .unwrap();
+
+        // the verifier lowers the same program without the witness
+        let public = parse_sieve_text(BOOL_RELATION, BOOL_INSTANCE, None).unwrap();
+        assert_eq!(public.program.len(), circuit.program.len());
+        assert!(public.wit_gf2.is_empty());
+    }
+
+    #[test]
+    fn test_sieve_arithmetic() {
+        let relation = "
+version 1.0.0;
+field characteristic 18446744073709551616 degree 1;
+relation
+gate_set: arithmetic;
+features: simple;
+@begin
+  $0 <- @short_witness;
+  $1 <- @instance;
+  $2 <- @mul($0, $0);
+  $3 <- @mulc($1, <0xffffffffffffffff>);
+  $4 <- @add($2, $3);
+  @assert_zero($4);
+@end
+";
+        let instance = "version 1.0.0; field characteristic 18446744073709551616 degree 1;
+            instance @begin <49>; @end";
+        let witness = "version 1.0.0; field characteristic 18446744073709551616 degree 1;
+            short_witness @begin <7>; @end";
+        let circuit = parse_sieve_text(relation, instance, Some(witness)).unwrap();
+        assert_eq!(circuit.wit_z64, vec![7]);
+        Proof::from_program(&circuit.program, &[], &circuit.wit_z64)
+            .verify_program(&circuit.program)
+            .unwrap();
+
+        // boolean gates are rejected in arithmetic relations
+        let relation = relation.replace("@mul($0, $0)", "@and($0, $0)");
+        assert!(parse_sieve_text(&relation, instance, None).is_err());
+    }
+
+    #[test]
+    fn test_sieve_errors() {
+        let header = "version 1.0.0; field characteristic 2 degree 1; relation @begin ";
+        let instance = "version 1.0.0; field characteristic 2 degree 1; instance @begin @end";
+        let parse =
+            |body: &str| parse_sieve_text(&format!("{}{} @end", header, body), instance, None);
+        assert!(parse("$0 <- @short_witness; @assert_zero($0);").is_ok());
+        assert!(parse("@assert_zero($0);").is_err()); // unassigned wire
+        assert!(parse("$0 <- @instance;").is_err()); // instance exhausted
+        assert!(parse("$0 <- @call(f);").is_err()); // unknown function
+        assert!(parse("$0 <- @switch($1);").is_err()); // unsupported
+    }
+}
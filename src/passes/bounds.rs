@@ -0,0 +1,82 @@
+use mcircuit::WireValue;
+
+use super::op_wires;
+use crate::algebra::z64::BIT_SIZE;
+use crate::error::{Error, Result};
+use crate::proof::{Output, OutputWire};
+use crate::{CombineOperation, Operation};
+
+fn check_wire(
+    at: &dyn Fn() -> String,
+    domain: &'static str,
+    wire: usize,
+    wires: usize,
+) -> Result<()> {
+    if wire < wires {
+        return Ok(());
+    }
+    Err(Error::WireRange {
+        at: at(),
+        domain,
+        wire,
+        wires,
+    })
+}
+
+fn check_op<T: WireValue + Copy>(
+    at: &dyn Fn() -> String,
+    domain: &'static str,
+    op: &Operation<T>,
+    wires: usize,
+) -> Result<()> {
+    let (dst, srcs) = op_wires(op);
+    for wire in dst.iter().chain(srcs.iter().flatten()) {
+        check_wire(at, domain, *wire, wires)?;
+    }
+    Ok(())
+}
+
+/// The wire counts (z64, gf2) of a program, as extended by its `SizeHint` operations:
+/// checks that every wire of the program and its outputs is in range.
+///
+/// Shared by `validate` and the prover (see `proof::witness::check_witness`).
+pub(crate) struct WireBounds {
+    pub(crate) z64: usize,
+    pub(crate) gf2: usize,
+}
+
+impl WireBounds {
+    pub(crate) fn new(wire_counts: (usize, usize)) -> Self {
+        WireBounds {
+            z64: wire_counts.0,
+            gf2: wire_counts.1,
+        }
+    }
+
+    /// Checks the wires of the operation, or extends the counts for a `SizeHint`.
+    pub(crate) fn op(&mut self, at: &dyn Fn() -> String, op: &CombineOperation) -> Result<()> {
+        match *op {
+            CombineOperation::GF2(ref op) => check_op(at, "gf2", op, self.gf2),
+            CombineOperation::Z64(ref op) => check_op(at, "z64", op, self.z64),
+            CombineOperation::B2A(dst, src) => {
+                check_wire(at, "z64", dst, self.z64)?;
+                // the last of the bits read, which may overflow for a corrupt program
+                let last = src.saturating_add(BIT_SIZE - 1);
+                check_wire(at, "gf2", last, self.gf2)
+            }
+            CombineOperation::SizeHint(z64, gf2) => {
+                self.z64 = self.z64.max(z64);
+                self.gf2 = self.gf2.max(gf2);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) fn output(&self, output: &Output) -> Result<()> {
+        let at = || format!("output '{}'", output.label);
+        match output.wire {
+            OutputWire::GF2(wire) => check_wire(&at, "gf2", wire, self.gf2),
+            OutputWire::Z64(wire) => check_wire(&at, "z64", wire, self.z64),
+        }
+    }
+}
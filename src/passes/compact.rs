@@ -0,0 +1,374 @@
+use mcircuit::WireValue;
+
+use super::{op_rewire, op_wires};
+use crate::algebra::z64::BIT_SIZE;
+use crate::{CombineOperation, Operation};
+
+const NONE: usize = usize::MAX;
+
+const GF2: usize = 0;
+const Z64: usize = 1;
+
+// gf2 wires read by B2A must stay contiguous: they are mapped statically to [0, len)
+struct Pinned {
+    intervals: Vec<(usize, usize, usize)>, // (start, end, base)
+    len: usize,
+}
+
+impl Pinned {
+    fn new(program: &[CombineOperation]) -> Self {
+        let mut ranges: Vec<(usize, usize)> = program
+            .iter()
+            .filter_map(|op| match op {
+                CombineOperation::B2A(_, src) => Some((*src, *src + BIT_SIZE)),
+                _ => None,
+            })
+            .collect();
+        ranges.sort_unstable();
+
+        // merge overlapping ranges
+        let mut intervals: Vec<(usize, usize, usize)> = vec![];
+        let mut len = 0;
+        for (start, end) in ranges {
+            if let Some(last) = intervals.last_mut() {
+                if start <= last.1 {
+                    if end > last.1 {
+                        len += end - last.1;
+                        last.1 = end;
+                    }
+                    continue;
+                }
+            }
+            intervals.push((start, end, len));
+            len += end - start;
+        }
+        Pinned { intervals, len }
+    }
+
+    fn get(&self, wire: usize) -> Option<usize> {
+        let idx = self
+            .intervals
+            .partition_point(|(start, _, _)| *start <= wire);
+        if idx == 0 {
+            return None;
+        }
+        let (start, end, base) = self.intervals[idx - 1];
+        if wire < end {
+            Some(base + wire - start)
+        } else {
+            None
+        }
+    }
+}
+
+// assigns a fresh value id to every write (and to reads of never written wires)
+struct Versions {
+    current: [Vec<usize>; 2],
+    next: usize,
+}
+
+impl Versions {
+    fn new() -> Self {
+        Versions {
+            current: [vec![], vec![]],
+            next: 0,
+        }
+    }
+
+    fn slot(&mut self, dom: usize, wire: usize) -> &mut usize {
+        let current = &mut self.current[dom];
+        if wire >= current.len() {
+            current.resize(wire + 1, NONE);
+        }
+        &mut current[wire]
+    }
+
+    // returns the value id and whether the value is new (the wire was never written)
+    fn read(&mut self, dom: usize, wire: usize) -> (usize, bool) {
+        let next = self.next;
+        let slot = self.slot(dom, wire);
+        if *slot == NONE {
+            *slot = next;
+            self.next += 1;
+            (next, true)
+        } else {
+            (*slot, false)
+        }
+    }
+
+    fn write(&mut self, dom: usize, wire: usize) -> usize {
+        let next = self.next;
+        *self.slot(dom, wire) = next;
+        self.next += 1;
+        next
+    }
+}
+
+// records the last operation reading every value
+struct LastUse {
+    versions: Versions,
+    last: Vec<usize>,
+}
+
+impl LastUse {
+    fn read(&mut self, idx: usize, dom: usize, wire: usize) {
+        let (val, new) = self.versions.read(dom, wire);
+        if new {
+            self.last.push(idx);
+        }
+        self.last[val] = idx;
+    }
+
+    fn write(&mut self, idx: usize, dom: usize, wire: usize) {
+        let val = self.versions.write(dom, wire);
+        debug_assert_eq!(val, self.last.len());
+        self.last.push(idx);
+    }
+
+    fn op<T: WireValue + Copy>(
+        &mut self,
+        idx: usize,
+        dom: usize,
+        pinned: &Pinned,
+        op: &Operation<T>,
+    ) {
+        let (dst, srcs) = op_wires(op);
+        for src in srcs.iter().flatten() {
+            if dom == Z64 || pinned.get(*src).is_none() {
+                self.read(idx, dom, *src);
+            }
+        }
+        if let Some(dst) = dst {
+            if dom == Z64 || pinned.get(dst).is_none() {
+                self.write(idx, dom, dst);
+            }
+        }
+    }
+}
+
+// maps values to storage slots, reusing the slots of dead values
+struct Allocator<'a> {
+    versions: Versions,
+    last: &'a [usize],
+    pinned: &'a Pinned,
+    slots: Vec<usize>,
+    free: [Vec<usize>; 2],
+    fresh: [usize; 2],
+}
+
+impl<'a> Allocator<'a> {
+    fn fresh(&mut self, dom: usize) -> usize {
+        let slot = self.fresh[dom];
+        self.fresh[dom] += 1;
+        slot
+    }
+
+    // maps a source wire, returns the slot and the value if this is its last use
+    fn read(&mut self, idx: usize, dom: usize, wire: usize) -> (usize, Option<usize>) {
+        if dom == GF2 {
+            if let Some(slot) = self.pinned.get(wire) {
+                return (slot, None);
+            }
+        }
+        let (val, _) = self.versions.read(dom, wire);
+        if self.slots[val] == NONE {
+            // never written: use an untouched (zero) slot
+            self.slots[val] = self.fresh(dom);
+        }
+        let dying = if self.last[val] == idx {
+            Some(val)
+        } else {
+            None
+        };
+        (self.slots[val], dying)
+    }
+
+    // maps a destination wire
+    fn write(&mut self, idx: usize, dom: usize, wire: usize) -> usize {
+        if dom == GF2 {
+            if let Some(slot) = self.pinned.get(wire) {
+                return slot;
+            }
+        }
+        let val = self.versions.write(dom, wire);
+        let slot = match self.free[dom].pop() {
+            Some(slot) => slot,
+            None => self.fresh(dom),
+        };
+        self.slots[val] = slot;
+        if self.last[val] == idx {
+            // never read: the slot can be reused immediately
+            self.free[dom].push(slot);
+        }
+        slot
+    }
+
+    fn op<T: WireValue + Copy>(
+        &mut self,
+        idx: usize,
+        dom: usize,
+        op: &Operation<T>,
+    ) -> Operation<T> {
+        let (dst, srcs) = op_wires(op);
+
+        // map the sources
+        let mut new_srcs = [0, 0];
+        let mut dying: [Option<usize>; 2] = [None, None];
+        for (i, src) in srcs.iter().enumerate() {
+            if let Some(src) = src {
+                let (slot, val) = self.read(idx, dom, *src);
+                new_srcs[i] = slot;
+                if val.is_some() && !dying.contains(&val) {
+                    dying[i] = val;
+                }
+            }
+        }
+
+        // release the slots of values read for the last time
+        for val in dying.iter().flatten() {
+            self.free[dom].push(self.slots[*val]);
+        }
+
+        // map the destination (after releasing, the destination may reuse a source slot)
+        let new_dst = match dst {
+            Some(dst) => self.write(idx, dom, dst),
+            None => 0,
+        };
+        op_rewire(op, new_dst, new_srcs)
+    }
+}
+
+/// Renumbers the wires of a program such that storage of dead wires is reused.
+///
+/// The prover and verifier allocate storage for every wire index up to `largest_wires`,
+/// this pass assigns wires to slots based on their live ranges,
+/// reducing the required storage to the peak number of simultaneously live wires.
+/// The statement proven is unchanged, however the compacted program must be used for both
+/// proving and verification. `SizeHint` operations are dropped.
+pub fn compact_wires(program: &[CombineOperation]) -> Vec<CombineOperation> {
+    let pinned = Pinned::new(program);
+
+    // compute the last use of every value
+    let mut last_use = LastUse {
+        versions: Versions::new(),
+        last: vec![],
+    };
+    for (idx, op) in program.iter().enumerate() {
+        match op {
+            CombineOperation::GF2(op) => last_use.op(idx, GF2, &pinned, op),
+            CombineOperation::Z64(op) => last_use.op(idx, Z64, &pinned, op),
+            CombineOperation::B2A(dst, _) => last_use.write(idx, Z64, *dst),
+            CombineOperation::SizeHint(_, _) => (),
+        }
+    }
+
+    // assign slots in program order
+    let mut alloc = Allocator {
+        versions: Versions::new(),
+        slots: vec![NONE; last_use.last.len()],
+        last: &last_use.last,
+        pinned: &pinned,
+        free: [vec![], vec![]],
+        fresh: [pinned.len, 0],
+    };
+    let mut compacted = Vec::with_capacity(program.len());
+    for (idx, op) in program.iter().enumerate() {
+        match op {
+            CombineOperation::GF2(op) => {
+                compacted.push(CombineOperation::GF2(alloc.op(idx, GF2, op)));
+            }
+            CombineOperation::Z64(op) => {
+                compacted.push(CombineOperation::Z64(alloc.op(idx, Z64, op)));
+            }
+            CombineOperation::B2A(dst, src) => {
+                let dst = alloc.write(idx, Z64, *dst);
+                let src = pinned.get(*src).unwrap();
+                compacted.push(CombineOperation::B2A(dst, src));
+            }
+            CombineOperation::SizeHint(_, _) => (),
+        }
+    }
+    compacted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::largest_wires;
+    use crate::proof::Proof;
+
+    #[test]
+    fn test_compact_chain() {
+        // x_{i+1} = x_i * x_i + 1 with fresh wires for every step
+        let mut program = vec![CombineOperation::Z64(Operation::Input(0))];
+        let mut expected: u64 = 3;
+        for i in 0..100 {
+            program.push(CombineOperation::Z64(Operation::Mul(
+                2 * i + 1,
+                2 * i,
+                2 * i,
+            )));
+            program.push(CombineOperation::Z64(Operation::AddConst(
+                2 * i + 2,
+                2 * i + 1,
+                1,
+            )));
+            expected = expected.wrapping_mul(expected).wrapping_add(1);
+        }
+        program.push(CombineOperation::Z64(Operation::SubConst(
+            201, 200, expected,
+        )));
+        program.push(CombineOperation::Z64(Operation::AssertZero(201)));
+
+        let compacted = compact_wires(&program);
+        assert_eq!(compacted.len(), program.len());
+
+        let (z64_count, _) = largest_wires(&compacted);
+        assert!(z64_count <= 2, "z64 wires = {}", z64_count);
+
+        let wire_counts = largest_wires(&compacted);
+        let compacted = Arc::new(compacted);
+        let proof = Proof::new(
+            compacted.clone(),
+            Arc::new(vec![]),
+            Arc::new(vec![3]),
+            wire_counts,
+        );
+        proof.verify(compacted, wire_counts).unwrap();
+    }
+
+    #[test]
+    fn test_compact_b2a() {
+        // 64 input bits (scattered), converted to z64 and checked against a constant
+        let mut program = vec![];
+        for i in 0..BIT_SIZE {
+            program.push(CombineOperation::GF2(Operation::Input(1000 + i)));
+        }
+        program.push(CombineOperation::GF2(Operation::Input(5000)));
+        program.push(CombineOperation::GF2(Operation::Mul(5001, 5000, 5000)));
+        program.push(CombineOperation::GF2(Operation::AssertZero(5001)));
+        program.push(CombineOperation::B2A(77, 1000));
+        program.push(CombineOperation::Z64(Operation::SubConst(78, 77, 5)));
+        program.push(CombineOperation::Z64(Operation::AssertZero(78)));
+
+        let compacted = compact_wires(&program);
+        let wire_counts = largest_wires(&compacted);
+        assert!(wire_counts.0 <= 2);
+        assert!(wire_counts.1 <= BIT_SIZE + 2);
+
+        let mut wit_gf2 = vec![false; BIT_SIZE + 1];
+        wit_gf2[0] = true;
+        wit_gf2[2] = true;
+
+        let compacted = Arc::new(compacted);
+        let proof = Proof::new(
+            compacted.clone(),
+            Arc::new(wit_gf2),
+            Arc::new(vec![]),
+            wire_counts,
+        );
+        proof.verify(compacted, wire_counts).unwrap();
+    }
+}
@@ -0,0 +1,208 @@
+use std::fmt;
+use std::mem::size_of;
+use std::time::Duration;
+
+use super::Stats;
+use crate::algebra::{gf2, z64, Domain};
+use crate::capabilities::PresetInfo;
+use crate::crypto::hash::HASH_SIZE;
+use crate::crypto::prg::KEY_SIZE;
+use crate::interpreter::Wire;
+use crate::proof::{Output, OutputWire, HEADER_BYTES};
+use crate::CombineOperation;
+
+// elements recorded by a b2a: the 64-bit adder (63 ands) and the reconstruction of its result
+// in gf2, the correction of the random value in z64
+const B2A_GF2_CORRS: usize = z64::BIT_SIZE - 1;
+const B2A_GF2_RECONS: usize = 2 * z64::BIT_SIZE - 1;
+
+// prover time (ns) of an instruction for a batch of `PACKED` repetitions,
+// measured on a single core of a recent x86-64 machine with AVX2
+const NS_PER_OP: [(&str, f64); 18] = [
+    ("gf2 input", 34.0),
+    ("gf2 random", 20.0),
+    ("gf2 const", 5.0),
+    ("gf2 add/sub", 16.0),
+    ("gf2 add/sub const", 10.0),
+    ("gf2 mul const", 10.0),
+    ("gf2 mul", 54.0),
+    ("gf2 assert zero", 30.0),
+    ("z64 input", 357.0),
+    ("z64 random", 200.0),
+    ("z64 const", 20.0),
+    ("z64 add/sub", 189.0),
+    ("z64 add/sub const", 120.0),
+    ("z64 mul const", 190.0),
+    ("z64 mul", 855.0),
+    ("z64 assert zero", 400.0),
+    ("b2a", 7000.0),
+    ("size hint", 0.0),
+];
+const NS_PER_OUTPUT: (f64, f64) = (30.0, 400.0);
+
+/// Predicted cost of proving a program with a parameter preset (see [`estimate`]).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Estimate {
+    pub preset: &'static str,
+
+    /// Serialized size of the proof.
+    pub proof_bytes: u64,
+
+    /// Peak memory of the prover (program, wires and transcripts of the concurrent batches).
+    pub prover_memory_bytes: u64,
+
+    /// Wall-clock time of the prover.
+    pub prover_time: Duration,
+}
+
+// recorded elements of one domain (in every repetition)
+struct Recorded {
+    recons: usize,
+    corrs: usize,
+    inputs: usize,
+}
+
+/// Predicts the proof size, prover memory and prover time of a program from its statistics,
+/// without running the prover. Sizes are exact, times come from a per-instruction cost model
+/// and assume `threads` cores.
+pub fn estimate(
+    stats: &Stats,
+    outputs: &[Output],
+    preset: &PresetInfo,
+    threads: usize,
+) -> Estimate {
+    let count = |name| stats.categories.get(name).copied().unwrap_or(0);
+    let b2a = count("b2a");
+    let gf2 = Recorded {
+        recons: stats.muls.0
+            + count("gf2 assert zero")
+            + b2a * B2A_GF2_RECONS
+            + outputs
+                .iter()
+                .filter(|o| matches!(o.wire, OutputWire::GF2(_)))
+                .count(),
+        corrs: stats.muls.0 + b2a * B2A_GF2_CORRS,
+        inputs: stats.inputs.0,
+    };
+    let z64 = Recorded {
+        recons: stats.muls.1
+            + count("z64 assert zero")
+            + outputs
+                .iter()
+                .filter(|o| matches!(o.wire, OutputWire::Z64(_)))
+                .count(),
+        corrs: stats.muls.1 + b2a,
+        inputs: stats.inputs.1,
+    };
+
+    // proof: the header, the commitment, the program hash and, for each domain, the opened online and preprocessing
+    // repetitions (gf2 elements are packed into bytes, with a trailing byte)
+    let online_reps = preset.online_reps as u64;
+    let preprocessing_reps = (preset.total_reps - preset.online_reps) as u64;
+    let gf2_bytes = |n: usize| (n / 8 + 1) as u64;
+    let z64_bytes = |n: usize| (n * z64::BIT_SIZE / 8) as u64;
+    let online = 8 + online_reps * (1 + (preset.players * KEY_SIZE) as u64 + 3 * 8);
+    let preprocessing = 8 + preprocessing_reps * (KEY_SIZE + HASH_SIZE) as u64;
+    let proof_bytes = HEADER_BYTES
+        + 2 * HASH_SIZE as u64
+        + 2 * (online + preprocessing)
+        + online_reps * (gf2_bytes(gf2.recons) + gf2_bytes(gf2.corrs) + gf2_bytes(gf2.inputs))
+        + online_reps * (z64_bytes(z64.recons) + z64_bytes(z64.corrs) + z64_bytes(z64.inputs));
+
+    // every batch of `packed` repetitions holds all wires and its transcripts
+    let batches = preset.total_reps / preset.packed;
+    let concurrent = threads.clamp(1, batches);
+    let (z64_wires, gf2_wires) = stats.wire_counts;
+    let batch_bytes = gf2_wires * size_of::<Wire<gf2::Domain>>()
+        + z64_wires * size_of::<Wire<z64::Domain>>()
+        + gf2.recons * size_of::<<gf2::Domain as Domain>::Share>()
+        + (gf2.corrs + gf2.inputs) * size_of::<<gf2::Domain as Domain>::Recon>()
+        + z64.recons * size_of::<<z64::Domain as Domain>::Share>()
+        + (z64.corrs + z64.inputs) * size_of::<<z64::Domain as Domain>::Recon>();
+    let program_bytes = stats.categories.values().sum::<usize>() * size_of::<CombineOperation>();
+    let prover_memory_bytes = (program_bytes + concurrent * batch_bytes) as u64 + proof_bytes;
+
+    // batches are distributed over the threads
+    let batch_ns: f64 = NS_PER_OP
+        .iter()
+        .map(|(name, ns)| count(name) as f64 * ns)
+        .sum::<f64>()
+        + outputs
+            .iter()
+            .map(|o| match o.wire {
+                OutputWire::GF2(_) => NS_PER_OUTPUT.0,
+                OutputWire::Z64(_) => NS_PER_OUTPUT.1,
+            })
+            .sum::<f64>();
+    let rounds = batches.div_ceil(concurrent);
+    let prover_time = Duration::from_secs_f64(batch_ns * rounds as f64 / 1e9);
+
+    Estimate {
+        preset: preset.name,
+        proof_bytes,
+        prover_memory_bytes,
+        prover_time,
+    }
+}
+
+impl fmt::Display for Estimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<12} {:>16} {:>20} {:>16.3}",
+            self.preset,
+            self.proof_bytes,
+            self.prover_memory_bytes,
+            self.prover_time.as_secs_f64() * 1000.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::passes::stats;
+    use crate::proof::{Proof, ProverConfig};
+    use crate::{capabilities, largest_wires, Operation};
+
+    #[test]
+    fn test_estimate() {
+        let mut program = vec![];
+        for i in 0..70 {
+            program.push(CombineOperation::GF2(Operation::Input(i)));
+        }
+        program.extend([
+            CombineOperation::GF2(Operation::Mul(70, 0, 1)),
+            CombineOperation::GF2(Operation::Add(71, 70, 70)),
+            CombineOperation::GF2(Operation::AssertZero(71)),
+            CombineOperation::B2A(0, 0),
+            CombineOperation::Z64(Operation::Input(1)),
+            CombineOperation::Z64(Operation::Mul(2, 0, 1)),
+        ]);
+        let outputs = [Output::gf2("and", 70), Output::z64("product", 2)];
+        let wire_counts = largest_wires(&program);
+
+        let caps = capabilities();
+        let estimate = estimate(
+            &stats(&program),
+            &outputs,
+            caps.preset("default").unwrap(),
+            1,
+        );
+
+        let proof = Proof::new_with_config(
+            Arc::new(program),
+            &outputs,
+            Arc::new(vec![true; 70]),
+            Arc::new(vec![3]),
+            wire_counts,
+            &ProverConfig::default(),
+        );
+        assert_eq!(estimate.proof_bytes, proof.serialized_size());
+        assert!(estimate.prover_memory_bytes > estimate.proof_bytes);
+        assert!(estimate.prover_time > Duration::ZERO);
+        assert!(format!("{}", estimate).starts_with("default"));
+    }
+}
@@ -0,0 +1,57 @@
+//! Analyses and transformations over `CombineOperation` programs.
+
+mod bounds;
+mod compact;
+mod estimate;
+mod stats;
+mod validate;
+
+pub(crate) use bounds::WireBounds;
+pub use compact::compact_wires;
+pub use estimate::{estimate, Estimate};
+pub use stats::{stats, Stats};
+pub use validate::validate;
+
+use mcircuit::WireValue;
+
+use crate::Operation;
+
+/// Returns the destination wire (if any) and the source wires of an operation.
+pub(crate) fn op_wires<T: WireValue + Copy>(
+    op: &Operation<T>,
+) -> (Option<usize>, [Option<usize>; 2]) {
+    match op {
+        Operation::Input(dst) | Operation::Random(dst) | Operation::Const(dst, _) => {
+            (Some(*dst), [None, None])
+        }
+        Operation::Add(dst, src1, src2)
+        | Operation::Sub(dst, src1, src2)
+        | Operation::Mul(dst, src1, src2) => (Some(*dst), [Some(*src1), Some(*src2)]),
+        Operation::AddConst(dst, src, _)
+        | Operation::SubConst(dst, src, _)
+        | Operation::MulConst(dst, src, _) => (Some(*dst), [Some(*src), None]),
+        Operation::AssertZero(src) => (None, [Some(*src), None]),
+    }
+}
+
+/// Returns a copy of the operation with its wires replaced.
+///
+/// Unused positions of `dst`/`srcs` (see `op_wires`) are ignored.
+pub(crate) fn op_rewire<T: WireValue + Copy>(
+    op: &Operation<T>,
+    dst: usize,
+    srcs: [usize; 2],
+) -> Operation<T> {
+    match op {
+        Operation::Input(_) => Operation::Input(dst),
+        Operation::Random(_) => Operation::Random(dst),
+        Operation::Const(_, c) => Operation::Const(dst, *c),
+        Operation::Add(_, _, _) => Operation::Add(dst, srcs[0], srcs[1]),
+        Operation::Sub(_, _, _) => Operation::Sub(dst, srcs[0], srcs[1]),
+        Operation::Mul(_, _, _) => Operation::Mul(dst, srcs[0], srcs[1]),
+        Operation::AddConst(_, _, c) => Operation::AddConst(dst, srcs[0], *c),
+        Operation::SubConst(_, _, c) => Operation::SubConst(dst, srcs[0], *c),
+        Operation::MulConst(_, _, c) => Operation::MulConst(dst, srcs[0], *c),
+        Operation::AssertZero(_) => Operation::AssertZero(srcs[0]),
+    }
+}
@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use mcircuit::WireValue;
+
+use super::op_wires;
+use crate::algebra::z64::BIT_SIZE;
+use crate::profile::category;
+use crate::{largest_wires, CombineOperation, Operation};
+
+/// Static statistics of a program (see [`stats`]), to estimate the cost of proving it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of instructions by category (as in profiling reports).
+    pub categories: BTreeMap<&'static str, usize>,
+
+    /// Witness values consumed (gf2, z64).
+    pub inputs: (usize, usize),
+
+    /// Multiplications (gf2 AND gates, z64 multiplications).
+    pub muls: (usize, usize),
+
+    /// Longest chain of multiplications (B2A counts as one).
+    pub mul_depth: usize,
+
+    /// Assertions (`AssertZero` operations).
+    pub assertions: usize,
+
+    /// Wire counts (z64, gf2) needed to prove the program.
+    pub wire_counts: (usize, usize),
+
+    /// Largest number of wires holding a value which is read later.
+    pub peak_live_wires: usize,
+}
+
+// per-domain wire state: multiplicative depth and liveness
+#[derive(Default)]
+struct Wires {
+    depth: Vec<usize>,
+    live: Vec<bool>,
+    count: usize,
+}
+
+impl Wires {
+    fn depth(&self, wire: usize) -> usize {
+        self.depth.get(wire).copied().unwrap_or(0)
+    }
+
+    fn set_depth(&mut self, wire: usize, depth: usize) {
+        if self.depth.len() <= wire {
+            self.depth.resize(wire + 1, 0);
+        }
+        self.depth[wire] = depth;
+    }
+
+    fn set_live(&mut self, wire: usize, live: bool) {
+        if self.live.len() <= wire {
+            self.live.resize(wire + 1, false);
+        }
+        if self.live[wire] != live {
+            self.live[wire] = live;
+            if live {
+                self.count += 1;
+            } else {
+                self.count -= 1;
+            }
+        }
+    }
+
+    // returns the depth of the destination
+    fn forward<T: WireValue + Copy>(&mut self, op: &Operation<T>) -> usize {
+        let (dst, srcs) = op_wires(op);
+        let mut depth = srcs.iter().flatten().map(|src| self.depth(*src)).max();
+        if let Operation::Mul(_, _, _) = op {
+            depth = Some(depth.unwrap_or(0) + 1);
+        }
+        let depth = depth.unwrap_or(0);
+        if let Some(dst) = dst {
+            self.set_depth(dst, depth);
+        }
+        depth
+    }
+
+    // backwards liveness: the destination dies, the sources are live before
+    fn backward<T: WireValue + Copy>(&mut self, op: &Operation<T>) {
+        let (dst, srcs) = op_wires(op);
+        if let Some(dst) = dst {
+            self.set_live(dst, false);
+        }
+        for src in srcs.iter().flatten() {
+            self.set_live(*src, true);
+        }
+    }
+}
+
+/// Counts the instructions of a program and computes its multiplicative depth
+/// and the peak number of live wires.
+pub fn stats(program: &[CombineOperation]) -> Stats {
+    let mut stats = Stats {
+        wire_counts: largest_wires(program),
+        ..Stats::default()
+    };
+
+    let (mut gf2, mut z64) = (Wires::default(), Wires::default());
+    for op in program {
+        let name = match op {
+            CombineOperation::GF2(op) => category(0, op),
+            CombineOperation::Z64(op) => category(1, op),
+            CombineOperation::B2A(_, _) => "b2a",
+            CombineOperation::SizeHint(_, _) => "size hint",
+        };
+        *stats.categories.entry(name).or_default() += 1;
+
+        let depth = match op {
+            CombineOperation::GF2(op) => {
+                match op {
+                    Operation::Input(_) => stats.inputs.0 += 1,
+                    Operation::Mul(_, _, _) => stats.muls.0 += 1,
+                    Operation::AssertZero(_) => stats.assertions += 1,
+                    _ => (),
+                }
+                gf2.forward(op)
+            }
+            CombineOperation::Z64(op) => {
+                match op {
+                    Operation::Input(_) => stats.inputs.1 += 1,
+                    Operation::Mul(_, _, _) => stats.muls.1 += 1,
+                    Operation::AssertZero(_) => stats.assertions += 1,
+                    _ => (),
+                }
+                z64.forward(op)
+            }
+            CombineOperation::B2A(dst, src) => {
+                let depth = (*src..*src + BIT_SIZE)
+                    .map(|bit| gf2.depth(bit))
+                    .max()
+                    .unwrap_or(0)
+                    + 1;
+                z64.set_depth(*dst, depth);
+                depth
+            }
+            CombineOperation::SizeHint(_, _) => 0,
+        };
+        stats.mul_depth = stats.mul_depth.max(depth);
+    }
+
+    // peak over the live sets before and after every instruction
+    let (mut gf2, mut z64) = (Wires::default(), Wires::default());
+    for op in program.iter().rev() {
+        match op {
+            CombineOperation::GF2(op) => gf2.backward(op),
+            CombineOperation::Z64(op) => z64.backward(op),
+            CombineOperation::B2A(dst, src) => {
+                z64.set_live(*dst, false);
+                for bit in *src..*src + BIT_SIZE {
+                    gf2.set_live(bit, true);
+                }
+            }
+            CombineOperation::SizeHint(_, _) => (),
+        }
+        stats.peak_live_wires = stats.peak_live_wires.max(gf2.count + z64.count);
+    }
+    stats
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<24} {:>12}", "category", "count")?;
+        for (name, count) in self.categories.iter() {
+            writeln!(f, "{:<24} {:>12}", name, count)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{:<24} {:>12}", "gf2 inputs", self.inputs.0)?;
+        writeln!(f, "{:<24} {:>12}", "z64 inputs", self.inputs.1)?;
+        writeln!(f, "{:<24} {:>12}", "gf2 muls (and)", self.muls.0)?;
+        writeln!(f, "{:<24} {:>12}", "z64 muls", self.muls.1)?;
+        writeln!(f, "{:<24} {:>12}", "mul depth", self.mul_depth)?;
+        writeln!(f, "{:<24} {:>12}", "assertions", self.assertions)?;
+        writeln!(f, "{:<24} {:>12}", "gf2 wires", self.wire_counts.1)?;
+        writeln!(f, "{:<24} {:>12}", "z64 wires", self.wire_counts.0)?;
+        writeln!(f, "{:<24} {:>12}", "peak live wires", self.peak_live_wires)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gf2(op: Operation<bool>) -> CombineOperation {
+        CombineOperation::GF2(op)
+    }
+
+    #[test]
+    fn test_stats() {
+        let program = vec![
+            gf2(Operation::Input(0)),
+            gf2(Operation::Input(1)),
+            gf2(Operation::Input(2)),
+            gf2(Operation::Mul(3, 0, 1)),
+            gf2(Operation::Mul(4, 3, 2)),
+            gf2(Operation::Add(5, 4, 0)),
+            gf2(Operation::AssertZero(5)),
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::MulConst(1, 0, 3)),
+        ];
+        let stats = stats(&program);
+        assert_eq!(stats.categories["gf2 input"], 3);
+        assert_eq!(stats.categories["gf2 mul"], 2);
+        assert_eq!(stats.categories["z64 mul const"], 1);
+        assert_eq!(stats.inputs, (3, 1));
+        assert_eq!(stats.muls, (2, 0));
+        assert_eq!(stats.mul_depth, 2);
+        assert_eq!(stats.assertions, 1);
+        assert_eq!(stats.wire_counts, (2, 6));
+
+        // before the first multiplication: wires 0, 1 and 2
+        assert_eq!(stats.peak_live_wires, 3);
+    }
+}
@@ -0,0 +1,155 @@
+use mcircuit::WireValue;
+
+use super::{op_wires, WireBounds};
+use crate::algebra::z64::BIT_SIZE;
+use crate::error::{Error, Result};
+use crate::proof::{Output, OutputWire};
+use crate::{CombineOperation, Operation};
+
+// assignment state of the wires of one domain, within the bounds checked by `WireBounds`
+struct Wires {
+    name: &'static str,
+    assigned: Vec<bool>,
+}
+
+impl Wires {
+    fn new(name: &'static str, count: usize) -> Self {
+        Wires {
+            name,
+            assigned: vec![false; count],
+        }
+    }
+
+    fn resize(&mut self, count: usize) {
+        if self.assigned.len() < count {
+            self.assigned.resize(count, false);
+        }
+    }
+
+    fn read(&self, at: &dyn Fn() -> String, wire: usize) -> Result<()> {
+        if self.assigned[wire] {
+            return Ok(());
+        }
+        Err(Error::Unassigned {
+            at: at(),
+            domain: self.name,
+            wire,
+        })
+    }
+
+    fn step<T: WireValue + Copy>(
+        &mut self,
+        at: &dyn Fn() -> String,
+        op: &Operation<T>,
+    ) -> Result<()> {
+        let (dst, srcs) = op_wires(op);
+        for src in srcs.iter().flatten() {
+            self.read(at, *src)?;
+        }
+        if let Some(dst) = dst {
+            self.assigned[dst] = true;
+        }
+        Ok(())
+    }
+}
+
+/// Checks that a program can be proven with the given wire counts (z64, gf2): every wire
+/// index is in range (`SizeHint` operations extend the counts), every wire is assigned before
+/// it is read and every output wire is assigned by the program.
+///
+/// The error names the first offending instruction, e.g. "instruction 7: gf2 wire 3 is read
+/// before it is assigned" (see `regions::locate` to attribute it to a region).
+pub fn validate(
+    program: &[CombineOperation],
+    outputs: &[Output],
+    wire_counts: (usize, usize),
+) -> Result<()> {
+    let mut bounds = WireBounds::new(wire_counts);
+    let mut z64 = Wires::new("z64", wire_counts.0);
+    let mut gf2 = Wires::new("gf2", wire_counts.1);
+
+    for (idx, op) in program.iter().enumerate() {
+        let at = || format!("instruction {}", idx);
+        bounds.op(&at, op)?;
+        match op {
+            CombineOperation::GF2(op) => gf2.step(&at, op)?,
+            CombineOperation::Z64(op) => z64.step(&at, op)?,
+            CombineOperation::B2A(dst, src) => {
+                for src in *src..*src + BIT_SIZE {
+                    gf2.read(&at, src)?;
+                }
+                z64.assigned[*dst] = true;
+            }
+            CombineOperation::SizeHint(_, _) => {
+                z64.resize(bounds.z64);
+                gf2.resize(bounds.gf2);
+            }
+        }
+    }
+
+    for output in outputs {
+        bounds.output(output)?;
+        let at = || format!("output '{}'", output.label);
+        match output.wire {
+            OutputWire::GF2(wire) => gf2.read(&at, wire)?,
+            OutputWire::Z64(wire) => z64.read(&at, wire)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::largest_wires;
+
+    fn gf2(op: Operation<bool>) -> CombineOperation {
+        CombineOperation::GF2(op)
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut program = vec![
+            gf2(Operation::Input(0)),
+            gf2(Operation::Input(1)),
+            gf2(Operation::Mul(2, 0, 1)),
+            gf2(Operation::AssertZero(2)),
+        ];
+        let outputs = [Output::gf2("and", 2)];
+        validate(&program, &outputs, largest_wires(&program)).unwrap();
+
+        // out of range
+        assert!(matches!(
+            validate(&program, &[], (0, 2)),
+            Err(Error::WireRange { wire: 2, .. })
+        ));
+        assert_eq!(
+            validate(&program, &[], (0, 2)).unwrap_err().to_string(),
+            "instruction 2: gf2 wire 2 out of range (2 wires)"
+        );
+
+        // unassigned source and output
+        program.push(gf2(Operation::Add(4, 3, 0)));
+        assert_eq!(
+            validate(&program, &[], (0, 5)).unwrap_err().to_string(),
+            "instruction 4: gf2 wire 3 is read before it is assigned"
+        );
+        assert_eq!(
+            validate(&program[..4], &[Output::z64("x", 0)], (1, 3))
+                .unwrap_err()
+                .to_string(),
+            "output 'x': z64 wire 0 is read before it is assigned"
+        );
+
+        // B2A reads 64 bits
+        let program = vec![
+            CombineOperation::SizeHint(1, 64),
+            gf2(Operation::Input(0)),
+            CombineOperation::B2A(0, 0),
+        ];
+        assert_eq!(
+            validate(&program, &[], (0, 0)).unwrap_err().to_string(),
+            "instruction 2: gf2 wire 1 is read before it is assigned"
+        );
+    }
+}
@@ -0,0 +1,280 @@
+//! Attributes prover time and proof size to instruction categories and program regions.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use mcircuit::WireValue;
+use rand::rngs::OsRng;
+use rand::RngCore;
+
+use crate::algebra::{gf2, z64};
+use crate::crypto::prg::KEY_SIZE;
+use crate::interpreter::{CombineInstance, Instance};
+use crate::proof::{Output, OutputWire};
+use crate::regions::Region;
+use crate::transcript::ProverTranscript;
+use crate::{CombineOperation, Operation, ONLINE_REPS, PACKED, PACKED_REPS};
+
+// bits of an element recorded by a transcript, per repetition
+const GF2_BITS: usize = 1;
+const Z64_BITS: usize = 64;
+
+// proof bits recorded so far (per online repetition)
+fn recorded_bits<I1, I2>(
+    ins: &CombineInstance<ProverTranscript<gf2::Domain, I1>, ProverTranscript<z64::Domain, I2>>,
+) -> usize
+where
+    I1: Iterator<Item = gf2::Recon>,
+    I2: Iterator<Item = z64::Recon>,
+{
+    let (gf2, z64) = ins.transcripts();
+    gf2.recorded() * GF2_BITS + z64.recorded() * Z64_BITS
+}
+
+/// Cost of a set of instructions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Cost {
+    /// Number of instructions.
+    pub count: usize,
+
+    /// Prover time for a single batch of `PACKED` repetitions.
+    pub time: Duration,
+
+    /// Proof bits contributed to every online repetition.
+    pub bits: usize,
+}
+
+impl Cost {
+    fn add(&mut self, time: Duration, bits: usize) {
+        self.count += 1;
+        self.time += time;
+        self.bits += bits;
+    }
+
+    /// Estimated prover time for the full proof (all `PACKED_REPS` batches, sequentially).
+    pub fn total_time(&self) -> Duration {
+        self.time * PACKED_REPS as u32
+    }
+
+    /// Proof bytes contributed by the instructions (over all online repetitions).
+    pub fn proof_bytes(&self) -> usize {
+        (self.bits * ONLINE_REPS).div_ceil(8)
+    }
+}
+
+/// Profiling report (see [`profile`]).
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub categories: BTreeMap<&'static str, Cost>,
+    pub regions: Vec<(String, Cost)>,
+}
+
+pub(crate) fn category<T: WireValue>(domain: usize, op: &Operation<T>) -> &'static str {
+    const NAMES: [[&str; 8]; 2] = [
+        [
+            "gf2 input",
+            "gf2 random",
+            "gf2 const",
+            "gf2 add/sub",
+            "gf2 add/sub const",
+            "gf2 mul const",
+            "gf2 mul",
+            "gf2 assert zero",
+        ],
+        [
+            "z64 input",
+            "z64 random",
+            "z64 const",
+            "z64 add/sub",
+            "z64 add/sub const",
+            "z64 mul const",
+            "z64 mul",
+            "z64 assert zero",
+        ],
+    ];
+    let idx = match op {
+        Operation::Input(_) => 0,
+        Operation::Random(_) => 1,
+        Operation::Const(_, _) => 2,
+        Operation::Add(_, _, _) | Operation::Sub(_, _, _) => 3,
+        Operation::AddConst(_, _, _) | Operation::SubConst(_, _, _) => 4,
+        Operation::MulConst(_, _, _) => 5,
+        Operation::Mul(_, _, _) => 6,
+        Operation::AssertZero(_) => 7,
+    };
+    NAMES[domain][idx]
+}
+
+/// Runs the prover on a single batch of repetitions,
+/// recording the time and proof size of every instruction (and output).
+/// The regions are usually obtained by stripping a structured program (see [`crate::regions`]).
+///
+/// The witness must satisfy the program.
+pub fn profile(
+    program: &[CombineOperation],
+    outputs: &[Output],
+    regions: &[Region],
+    wit_gf2: &[bool],
+    wit_z64: &[u64],
+    wire_counts: (usize, usize),
+) -> Profile {
+    let (z64_count, gf2_count) = wire_counts;
+
+    let mut keys = [[0u8; KEY_SIZE]; PACKED];
+    for key in keys.iter_mut() {
+        OsRng.fill_bytes(key);
+    }
+    let mut ins = CombineInstance::new(
+        Instance::new(
+            ProverTranscript::new(wit_gf2.iter().map(|b| (*b).into()), keys),
+            gf2_count,
+        ),
+        Instance::new(
+            ProverTranscript::new(wit_z64.iter().map(|b| (*b).into()), keys),
+            z64_count,
+        ),
+    );
+
+    let mut report = Profile {
+        categories: BTreeMap::new(),
+        regions: regions
+            .iter()
+            .map(|region| (region.label.clone(), Cost::default()))
+            .collect(),
+    };
+
+    for (idx, op) in program.iter().enumerate() {
+        let name = match op {
+            CombineOperation::GF2(op) => category(0, op),
+            CombineOperation::Z64(op) => category(1, op),
+            CombineOperation::B2A(_, _) => "b2a",
+            CombineOperation::SizeHint(_, _) => "size hint",
+        };
+
+        let before = recorded_bits(&ins);
+        let start = Instant::now();
+        ins.step(op);
+        let time = start.elapsed();
+        let bits = recorded_bits(&ins) - before;
+
+        report.categories.entry(name).or_default().add(time, bits);
+        for (region, (_, cost)) in regions.iter().zip(report.regions.iter_mut()) {
+            if region.range.contains(&idx) {
+                cost.add(time, bits);
+            }
+        }
+    }
+
+    for output in outputs.iter() {
+        let name = match output.wire {
+            OutputWire::GF2(_) => "gf2 output",
+            OutputWire::Z64(_) => "z64 output",
+        };
+        let before = recorded_bits(&ins);
+        let start = Instant::now();
+        ins.output(output.wire);
+        let time = start.elapsed();
+        let bits = recorded_bits(&ins) - before;
+        report.categories.entry(name).or_default().add(time, bits);
+    }
+
+    report
+}
+
+impl Profile {
+    /// Sum of the costs of all categories.
+    pub fn total(&self) -> Cost {
+        let mut total = Cost::default();
+        for cost in self.categories.values() {
+            total.count += cost.count;
+            total.time += cost.time;
+            total.bits += cost.bits;
+        }
+        total
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn row(f: &mut fmt::Formatter<'_>, name: &str, cost: &Cost) -> fmt::Result {
+            writeln!(
+                f,
+                "{:<24} {:>12} {:>14.3} {:>14}",
+                name,
+                cost.count,
+                cost.total_time().as_secs_f64() * 1000.0,
+                cost.proof_bytes()
+            )
+        }
+
+        writeln!(
+            f,
+            "{:<24} {:>12} {:>14} {:>14}",
+            "category", "count", "time (ms)", "proof bytes"
+        )?;
+        for (name, cost) in self.categories.iter() {
+            row(f, name, cost)?;
+        }
+        row(f, "total", &self.total())?;
+
+        if !self.regions.is_empty() {
+            writeln!(f)?;
+            writeln!(
+                f,
+                "{:<24} {:>12} {:>14} {:>14}",
+                "region", "count", "time (ms)", "proof bytes"
+            )?;
+            for (name, cost) in self.regions.iter() {
+                row(f, name, cost)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile() {
+        let program = vec![
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::GF2(Operation::Input(1)),
+            CombineOperation::GF2(Operation::Mul(2, 0, 1)),
+            CombineOperation::GF2(Operation::Add(3, 2, 1)),
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Mul(1, 0, 0)),
+            CombineOperation::Z64(Operation::Mul(2, 1, 1)),
+        ];
+        let regions = [Region {
+            label: "square".to_string(),
+            range: 4..6,
+        }];
+        let report = profile(
+            &program,
+            &[Output::z64("x^4", 2)],
+            &regions,
+            &[true, false],
+            &[3],
+            (3, 4),
+        );
+
+        let cost = |name| report.categories[name];
+        assert_eq!(cost("gf2 input").count, 2);
+        assert_eq!(cost("gf2 input").bits, 2);
+        assert_eq!(cost("gf2 mul").bits, 2); // correction and reconstruction
+        assert_eq!(cost("gf2 add/sub").bits, 0);
+        assert_eq!(cost("z64 mul").count, 2);
+        assert_eq!(cost("z64 mul").bits, 4 * 64);
+        assert_eq!(cost("z64 output").bits, 64);
+        assert_eq!(report.total().count, program.len() + 1);
+
+        let (label, region) = &report.regions[0];
+        assert_eq!(label, "square");
+        assert_eq!(region.count, 2);
+        assert_eq!(region.bits, 3 * 64);
+        assert!(format!("{}", report).contains("square"));
+    }
+}
@@ -0,0 +1,246 @@
+use std::io;
+use std::sync::Arc;
+
+use mcircuit::WireValue;
+#[cfg(not(debug_assertions))]
+use rayon::prelude::*;
+
+use super::{Output, Outputs, ProgramSource, Proof, ProverConfig, Statement};
+use crate::crypto::hash::HASH_SIZE;
+use crate::error::{Error, Result};
+use crate::passes::{op_rewire, op_wires};
+use crate::{CombineOperation, Operation};
+
+// the hash and wire counts (z64, gf2) of a program
+type Summary = ([u8; HASH_SIZE], (usize, usize));
+
+// the operation with every wire shifted by `offset`
+fn shift<T: WireValue + Copy>(op: &Operation<T>, offset: usize) -> Operation<T> {
+    let (dst, srcs) = op_wires(op);
+    op_rewire(
+        op,
+        dst.map_or(0, |dst| dst + offset),
+        srcs.map(|src| src.map_or(0, |src| src + offset)),
+    )
+}
+
+/// The program running `instances` copies of `program` one after the other, each on its own
+/// wires (instance `i` uses the wires `i * stride + w`) and consuming its own witness.
+///
+/// Returns the program and its wire counts (z64, gf2).
+fn replicate(
+    program: &[CombineOperation],
+    instances: usize,
+    wire_counts: (usize, usize),
+) -> (Vec<CombineOperation>, (usize, usize)) {
+    // the wires of an instance, including those added by size hints
+    let stride = program.iter().fold(wire_counts, |(z64, gf2), op| match op {
+        CombineOperation::SizeHint(z, g) => (z64.max(*z), gf2.max(*g)),
+        _ => (z64, gf2),
+    });
+
+    let mut replicated = Vec::with_capacity(instances * program.len());
+    for i in 0..instances {
+        let (z64, gf2) = (i * stride.0, i * stride.1);
+        replicated.extend(program.iter().filter_map(|op| match op {
+            CombineOperation::GF2(op) => Some(CombineOperation::GF2(shift(op, gf2))),
+            CombineOperation::Z64(op) => Some(CombineOperation::Z64(shift(op, z64))),
+            CombineOperation::B2A(dst, src) => Some(CombineOperation::B2A(dst + z64, src + gf2)),
+            CombineOperation::SizeHint(_, _) => None,
+        }));
+    }
+    (replicated, (instances * stride.0, instances * stride.1))
+}
+
+impl Proof {
+    /// Proves that every witness satisfies the same program, with a single proof.
+    ///
+    /// The instances are proven side by side in the same repetitions: they share the
+    /// commitments, the challenge and the seeds of the opened repetitions, which the proof of
+    /// a single instance spends on its own. Each instance is masked with fresh randomness.
+    pub fn new_batch(
+        program: &[CombineOperation],
+        witnesses: &[(Vec<bool>, Vec<u64>)], // gf2 and z64 witness of every instance
+        wire_counts: (usize, usize),
+        config: &ProverConfig,
+    ) -> Result<Self> {
+        // check every witness on its own: the concatenation could be split wrongly
+        for (wit_gf2, wit_z64) in witnesses {
+            let lens = (wit_gf2.len(), wit_z64.len());
+            super::witness::check_witness(program, &[], &[], lens, wire_counts)?;
+        }
+
+        let (replicated, wire_counts) = replicate(program, witnesses.len(), wire_counts);
+        let wit_gf2 = witnesses.iter().flat_map(|w| w.0.iter().copied()).collect();
+        let wit_z64 = witnesses.iter().flat_map(|w| w.1.iter().copied()).collect();
+        Self::from_source(
+            replicated.as_slice(),
+            &[],
+            Arc::new(wit_gf2),
+            Arc::new(wit_z64),
+            wire_counts,
+            config,
+        )
+    }
+
+    /// Verifies a proof created by `new_batch` for the given number of instances.
+    pub fn verify_batch(
+        &self,
+        program: &[CombineOperation],
+        instances: usize,
+        wire_counts: (usize, usize),
+    ) -> Result<()> {
+        let (replicated, wire_counts) = replicate(program, instances, wire_counts);
+        self.verify_source(replicated.as_slice(), &[], wire_counts)
+            .map(|_| ())
+    }
+}
+
+/// Verifies independent proofs (of the same or different programs, with the same outputs)
+/// concurrently, returns the result of every proof in order.
+///
+/// The hash and wire counts of every distinct program (by address) are computed only once,
+/// instead of once per proof. See `Proof::verify_batch` for a single proof of several instances.
+pub fn verify_many<S: ProgramSource + ?Sized>(
+    proofs: &[(&S, &Proof)],
+    outputs: &[Output],
+) -> Vec<Result<Outputs>> {
+    // the distinct programs, with their hash and wire counts
+    let mut programs: Vec<&S> = vec![];
+    for (source, _) in proofs {
+        if !programs
+            .iter()
+            .any(|program| std::ptr::eq(*program, *source))
+        {
+            programs.push(*source);
+        }
+    }
+    let summaries: Vec<io::Result<Summary>> = programs
+        .iter()
+        .map(|program| Ok((program.program_hash()?, program.wire_counts()?)))
+        .collect();
+
+    let statement = Statement {
+        outputs,
+        ..Statement::default()
+    };
+
+    #[cfg(debug_assertions)]
+    let items = proofs.iter();
+
+    #[cfg(not(debug_assertions))]
+    let items = proofs.par_iter();
+
+    items
+        .map(|(source, proof)| {
+            let idx = programs
+                .iter()
+                .position(|program| std::ptr::eq(*program, *source))
+                .unwrap();
+            match &summaries[idx] {
+                Ok((hash, wire_counts)) => {
+                    proof.verify_hashed(*source, *hash, &statement, *wire_counts, None)
+                }
+                Err(e) => Err(Error::Io(io::Error::new(e.kind(), e.to_string()))),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::largest_wires;
+    use crate::proof::OutputValue;
+
+    #[test]
+    fn test_verify_many() {
+        let square = vec![
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Mul(1, 0, 0)),
+        ];
+        let cube = vec![
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Mul(1, 0, 0)),
+            CombineOperation::Z64(Operation::Mul(1, 1, 0)),
+        ];
+        let outputs = [Output::z64("y", 1)];
+        let prove = |program: &[CombineOperation], x: u64| {
+            Proof::from_source(
+                program,
+                &outputs,
+                Arc::new(vec![]),
+                Arc::new(vec![x]),
+                largest_wires(program),
+                &ProverConfig::default(),
+            )
+            .unwrap()
+        };
+        let (a, b, c) = (prove(&square, 3), prove(&cube, 2), prove(&square, 5));
+
+        let results = verify_many(
+            &[
+                (square.as_slice(), &a),
+                (cube.as_slice(), &b),
+                (square.as_slice(), &c),
+                (cube.as_slice(), &c),
+            ],
+            &outputs,
+        );
+        assert_eq!(results.len(), 4);
+        let value = |i: usize| results[i].as_ref().unwrap()["y"];
+        assert_eq!(value(0), OutputValue::Z64(9));
+        assert_eq!(value(1), OutputValue::Z64(8));
+        assert_eq!(value(2), OutputValue::Z64(25));
+        assert!(matches!(results[3], Err(Error::Program { .. })));
+    }
+
+    #[test]
+    fn test_batch() {
+        // x * x = 49 (z64) and a & b = 0 (gf2), on the same wires
+        let program = vec![
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Mul(1, 0, 0)),
+            CombineOperation::Z64(Operation::SubConst(2, 1, 49)),
+            CombineOperation::Z64(Operation::AssertZero(2)),
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::GF2(Operation::Input(1)),
+            CombineOperation::GF2(Operation::Mul(2, 0, 1)),
+            CombineOperation::GF2(Operation::AssertZero(2)),
+        ];
+        let wire_counts = largest_wires(&program);
+        let witnesses = vec![
+            (vec![true, false], vec![7]),
+            (vec![false, true], vec![7u64.wrapping_neg()]),
+            (vec![false, false], vec![7]),
+        ];
+        let config = ProverConfig::default();
+
+        let proof = Proof::new_batch(&program, &witnesses, wire_counts, &config).unwrap();
+        proof.verify_batch(&program, 3, wire_counts).unwrap();
+        assert!(proof.verify_batch(&program, 2, wire_counts).is_err());
+
+        // smaller than three proofs
+        let single = Proof::new_batch(&program, &witnesses[..1], wire_counts, &config).unwrap();
+        single.verify_batch(&program, 1, wire_counts).unwrap();
+        assert!(proof.serialized_size() < 3 * single.serialized_size());
+
+        // every instance must be satisfied: the assertion of the second instance fails
+        let mut invalid = witnesses.clone();
+        invalid[1].0 = vec![true, true];
+        assert_eq!(
+            Proof::new_batch(&program, &invalid, wire_counts, &config)
+                .unwrap_err()
+                .to_string(),
+            "instruction 15: gf2 assertion fails (wire 5 is not zero)"
+        );
+
+        // and have a witness of its own
+        invalid[1].0 = vec![true, false, true];
+        invalid[2].0 = vec![false];
+        assert!(matches!(
+            Proof::new_batch(&program, &invalid, wire_counts, &config),
+            Err(Error::WitnessLength { got: 3, .. })
+        ));
+    }
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use super::{ProgramSource, Proof, ProverConfig};
+use crate::error::Result;
+use crate::parsers::read_program;
+use crate::CombineOperation;
+
+/// Decodes a program in the compact binary format (see `parsers::write_program`).
+pub(crate) fn decode_program(bytes: &[u8]) -> Result<Vec<CombineOperation>> {
+    Ok(read_program(bytes)?)
+}
+
+/// Proves a decoded program for the witness, returns the proof as written by `Proof::serialize`.
+pub(crate) fn prove(
+    program: &[CombineOperation],
+    wit_gf2: Vec<bool>,
+    wit_z64: Vec<u64>,
+) -> Result<Vec<u8>> {
+    let proof = Proof::from_source(
+        program,
+        &[],
+        Arc::new(wit_gf2),
+        Arc::new(wit_z64),
+        program.wire_counts()?,
+        &ProverConfig::default(),
+    )?;
+    let mut bytes = vec![];
+    proof.serialize(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Verifies a serialized proof of a decoded program.
+pub(crate) fn verify(program: &[CombineOperation], proof: &[u8]) -> Result<()> {
+    let proof = Proof::deserialize(proof)?;
+    proof.verify_source(program, &[], program.wire_counts()?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsers::write_program;
+    use crate::Operation;
+
+    #[test]
+    fn test_bytes() {
+        let program = vec![
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::GF2(Operation::AddConst(1, 0, true)),
+            CombineOperation::GF2(Operation::AssertZero(1)),
+        ];
+        let mut encoded = vec![];
+        write_program(&mut encoded, &program, 16).unwrap();
+
+        let program = decode_program(&encoded).unwrap();
+        let proof = prove(&program, vec![true], vec![]).unwrap();
+        verify(&program, &proof).unwrap();
+        assert!(decode_program(&encoded[..4]).is_err());
+        assert!(verify(&program, &proof[..proof.len() - 1]).is_err());
+        assert!(prove(&program, vec![true, false], vec![]).is_err());
+    }
+}
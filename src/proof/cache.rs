@@ -0,0 +1,168 @@
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tempfile::NamedTempFile;
+
+use super::{Output, Proof, ProverConfig};
+use crate::crypto::hash::{Backend, Hash, HashBackend};
+use crate::crypto::prg::{self, PrgBackend, KEY_SIZE};
+use crate::error::Result;
+use crate::{CombineOperation, ONLINE_REPS, PACKED, PLAYERS, TOTAL_REPS};
+
+const CTX_CACHE_KEY: &str = "reverie proof cache key";
+
+/// On-disk cache of proofs, keyed by a digest of the circuit, outputs, witness and parameters.
+///
+/// Proving is randomized: without the cache, repeating a request yields a fresh proof.
+/// With the cache, an identical request returns the proof created by the first run,
+/// which is what idempotent batch pipelines want, but means repeated proofs are linkable.
+///
+/// The cache file names are derived from the witness: the salt is mixed into every key
+/// and should be kept secret if the witness has low entropy.
+#[derive(Debug, Clone)]
+pub struct ProofCache {
+    dir: PathBuf,
+    salt: Vec<u8>,
+}
+
+impl ProofCache {
+    pub fn new<P: Into<PathBuf>>(dir: P, salt: &[u8]) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(ProofCache {
+            dir,
+            salt: salt.to_vec(),
+        })
+    }
+
+    pub(crate) fn key(
+        &self,
+        circuit: &[CombineOperation],
+        outputs: &[Output],
+        wit_gf2: &[bool],
+        wit_z64: &[u64],
+        wire_counts: (usize, usize),
+    ) -> Hash {
+        fn update_len(hasher: &mut blake3::Hasher, len: usize) {
+            hasher.update(&(len as u64).to_le_bytes());
+        }
+
+        let mut hasher = blake3::Hasher::new_derive_key(CTX_CACHE_KEY);
+
+        // salt and parameters
+        update_len(&mut hasher, self.salt.len());
+        hasher.update(&self.salt);
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        for param in [PLAYERS, PACKED, TOTAL_REPS, ONLINE_REPS, KEY_SIZE] {
+            update_len(&mut hasher, param);
+        }
+        hasher.update(&[Backend::ID, prg::Backend::ID]);
+
+        // statement
+        let circuit = bincode::serialize(circuit).unwrap();
+        let outputs = bincode::serialize(outputs).unwrap();
+        update_len(&mut hasher, circuit.len());
+        hasher.update(&circuit);
+        update_len(&mut hasher, outputs.len());
+        hasher.update(&outputs);
+        update_len(&mut hasher, wire_counts.0);
+        update_len(&mut hasher, wire_counts.1);
+
+        // witness
+        update_len(&mut hasher, wit_gf2.len());
+        for bit in wit_gf2.iter() {
+            hasher.update(&[*bit as u8]);
+        }
+        update_len(&mut hasher, wit_z64.len());
+        for value in wit_z64.iter() {
+            hasher.update(&value.to_le_bytes());
+        }
+        hasher.finalize()
+    }
+
+    pub(crate) fn path(&self, key: &Hash) -> PathBuf {
+        self.dir.join(format!("{}.proof", key.to_hex()))
+    }
+
+    /// Returns the cached proof for the request, or creates (and caches) a new one.
+    ///
+    /// A cache entry which cannot be deserialized is replaced.
+    pub fn get_or_prove(
+        &self,
+        circuit: Arc<Vec<CombineOperation>>,
+        outputs: &[Output],
+        wit_gf2: Arc<Vec<bool>>,
+        wit_z64: Arc<Vec<u64>>,
+        wire_counts: (usize, usize),
+        config: &ProverConfig,
+    ) -> Result<Proof> {
+        let key = self.key(&circuit, outputs, &wit_gf2, &wit_z64, wire_counts);
+        let path = self.path(&key);
+
+        if let Ok(file) = File::open(&path) {
+            if let Ok(proof) = Proof::deserialize(BufReader::new(file)) {
+                return Ok(proof);
+            }
+        }
+
+        let proof = Proof::from_source(
+            circuit.as_slice(),
+            outputs,
+            wit_gf2,
+            wit_z64,
+            wire_counts,
+            config,
+        )?;
+
+        // write to a temporary file first: concurrent readers never see a partial proof
+        let mut tmp = NamedTempFile::new_in(&self.dir)?;
+        let mut writer = BufWriter::new(tmp.as_file_mut());
+        proof.serialize(&mut writer)?;
+        writer.flush()?;
+        drop(writer);
+        tmp.persist(&path).map_err(|e| e.error)?;
+        Ok(proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Operation;
+
+    #[test]
+    fn test_proof_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = ProofCache::new(dir.path(), b"salt").unwrap();
+
+        let circuit = Arc::new(vec![
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Mul(1, 0, 0)),
+        ]);
+        let config = ProverConfig::default();
+        let prove = |cache: &ProofCache, wit: u64| {
+            cache
+                .get_or_prove(
+                    circuit.clone(),
+                    &[],
+                    Arc::new(vec![]),
+                    Arc::new(vec![wit]),
+                    (2, 0),
+                    &config,
+                )
+                .unwrap()
+        };
+
+        // identical requests hit the cache (proving is randomized)
+        let proof = prove(&cache, 3);
+        proof.verify(circuit.clone(), (2, 0)).unwrap();
+        assert_eq!(prove(&cache, 3), proof);
+
+        // a different witness or salt misses
+        assert_ne!(prove(&cache, 4), proof);
+        let other = ProofCache::new(dir.path(), b"pepper").unwrap();
+        assert_ne!(prove(&other, 3), proof);
+    }
+}
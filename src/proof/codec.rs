@@ -0,0 +1,96 @@
+use std::io::{Read, Write};
+
+use serde_json::error::Category;
+
+use super::Proof;
+use crate::error::{Error, Result};
+
+// errors of the reader/writer stay I/O errors
+fn json_error(e: serde_json::Error) -> Error {
+    match e.classify() {
+        Category::Io => Error::Io(e.into()),
+        _ => Error::Codec {
+            format: "JSON",
+            reason: e.to_string(),
+        },
+    }
+}
+
+/// Proofs in other encodings than `Proof::serialize`, for protocols mandating one.
+///
+/// These hold the plain proof, without the header of `serialize`: a proof for other
+/// parameters decodes, but is rejected by the verifier (`Error::Format`).
+/// Limit the reader (`Read::take`) to bound the size of untrusted proofs.
+impl Proof {
+    pub fn serialize_json<W: Write>(&self, writer: W) -> Result<()> {
+        serde_json::to_writer(writer, self).map_err(json_error)
+    }
+
+    pub fn deserialize_json<R: Read>(reader: R) -> Result<Self> {
+        serde_json::from_reader(reader).map_err(json_error)
+    }
+
+    #[cfg(feature = "cbor")]
+    pub fn serialize_cbor<W: Write>(&self, writer: W) -> Result<()> {
+        ciborium::ser::into_writer(self, writer).map_err(|e| match e {
+            ciborium::ser::Error::Io(e) => Error::Io(e),
+            e => Error::Codec {
+                format: "CBOR",
+                reason: e.to_string(),
+            },
+        })
+    }
+
+    #[cfg(feature = "cbor")]
+    pub fn deserialize_cbor<R: Read>(reader: R) -> Result<Self> {
+        ciborium::de::from_reader(reader).map_err(|e| match e {
+            ciborium::de::Error::Io(e) => Error::Io(e),
+            e => Error::Codec {
+                format: "CBOR",
+                reason: e.to_string(),
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CombineOperation, Operation};
+
+    #[test]
+    fn test_codecs() {
+        let program = vec![
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Mul(1, 0, 0)),
+        ];
+        let proof = Proof::from_program(&program, &[], &[3]);
+
+        let mut json = vec![];
+        proof.serialize_json(&mut json).unwrap();
+        let decoded = Proof::deserialize_json(json.as_slice()).unwrap();
+        assert_eq!(decoded, proof);
+        decoded.verify_program(&program).unwrap();
+        assert!(matches!(
+            Proof::deserialize_json(&json[..json.len() - 1]),
+            Err(Error::Codec { format: "JSON", .. })
+        ));
+
+        #[cfg(feature = "cbor")]
+        {
+            let mut cbor = vec![];
+            proof.serialize_cbor(&mut cbor).unwrap();
+            assert_eq!(Proof::deserialize_cbor(cbor.as_slice()).unwrap(), proof);
+        }
+
+        // bounded decoding
+        let mut bytes = vec![];
+        proof.serialize(&mut bytes).unwrap();
+        let size = proof.serialized_size() - crate::proof::HEADER_BYTES;
+        Proof::deserialize_with_limit(bytes.as_slice(), size).unwrap();
+        assert!(matches!(
+            Proof::deserialize_with_limit(bytes.as_slice(), size - 1),
+            Err(Error::TooLarge { .. })
+        ));
+    }
+}
@@ -0,0 +1,34 @@
+use std::path::PathBuf;
+
+use super::CancelToken;
+
+/// Tuning knobs for the prover which do not affect the proof itself.
+#[derive(Debug, Clone, Default)]
+pub struct ProverConfig {
+    /// Maximum number of masked inputs each transcript keeps in memory.
+    ///
+    /// Once exceeded, the masked witness stream is appended to an anonymous temporary file
+    /// and read back when the proof is extracted. `None` keeps everything in memory,
+    /// as does every threshold on wasm32 (there is no file system).
+    ///
+    /// Note that every packed repetition (and every domain) has its own transcript.
+    pub input_spill_threshold: Option<usize>,
+
+    /// Directory in which spill files are created (defaults to the system temporary directory).
+    pub spill_dir: Option<PathBuf>,
+
+    /// Derive the prover randomness from this seed instead of the OS: proving the same
+    /// statement and witness with the same seed yields the same proof.
+    ///
+    /// For debugging and regression testing only, a known seed breaks zero-knowledge.
+    pub seed: Option<[u8; 32]>,
+
+    /// Stop proving (with `Error::Cancelled`) once the token is cancelled or times out.
+    pub cancel: Option<CancelToken>,
+}
+
+impl ProverConfig {
+    pub(crate) fn spill_dir(&self) -> PathBuf {
+        self.spill_dir.clone().unwrap_or_else(std::env::temp_dir)
+    }
+}
@@ -0,0 +1,205 @@
+//! Canonical encoding of a proof, written by `Proof::serialize` after the header.
+//!
+//! The layout is fixed (it does not depend on the options of a serialization library),
+//! so that other implementations can read proofs and hashes of proofs are stable.
+//! All integers are little-endian:
+//!
+//! ```text
+//! proof         = comm [32] | program [32] | domain (gf2) | domain (z64)
+//! domain        = u64 count | count * online | u64 count | count * preprocessing
+//! online        = omit u8 | seeds [PLAYERS * KEY_SIZE] | bytes (recons) | bytes (corrs) | bytes (inputs)
+//! preprocessing = seed [KEY_SIZE] | comm_online [32]
+//! bytes         = u64 length | length * u8
+//! ```
+//!
+//! This is the layout bincode (with its default options) used for proofs of format version 2.
+//! Later versions only extend the header: version 3 with the seed length (`KEY_SIZE`),
+//! version 4 with the hash function (`HashBackend::ID`), version 5 with the PRG
+//! (`PrgBackend::ID`).
+use std::io::{self, Read, Write};
+
+use super::{OpenOnline, OpenPreprocessing, Proof, ProofSingle};
+use crate::crypto::hash::HASH_SIZE;
+use crate::crypto::prg::KEY_SIZE;
+use crate::error::{Error, Result};
+use crate::PLAYERS;
+
+const ONLINE_FIXED_BYTES: u64 = 1 + (PLAYERS * KEY_SIZE) as u64 + 3 * 8;
+const PREPROCESSING_BYTES: u64 = (KEY_SIZE + HASH_SIZE) as u64;
+
+fn write_bytes<W: Write>(writer: &mut W, bytes: &[u8]) -> io::Result<()> {
+    writer.write_all(&(bytes.len() as u64).to_le_bytes())?;
+    writer.write_all(bytes)
+}
+
+fn write_domain<W: Write>(writer: &mut W, domain: &ProofSingle) -> io::Result<()> {
+    writer.write_all(&(domain.online.len() as u64).to_le_bytes())?;
+    for online in domain.online.iter() {
+        writer.write_all(&[online.omit])?;
+        for seed in online.seeds.iter() {
+            writer.write_all(seed)?;
+        }
+        write_bytes(writer, &online.recons)?;
+        write_bytes(writer, &online.corrs)?;
+        write_bytes(writer, &online.inputs)?;
+    }
+    writer.write_all(&(domain.preprocessing.len() as u64).to_le_bytes())?;
+    for preprocessing in domain.preprocessing.iter() {
+        writer.write_all(&preprocessing.seed)?;
+        writer.write_all(&preprocessing.comm_online)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn write_proof<W: Write>(writer: &mut W, proof: &Proof) -> io::Result<()> {
+    writer.write_all(&proof.comm)?;
+    writer.write_all(&proof.program)?;
+    write_domain(writer, &proof.gf2)?;
+    write_domain(writer, &proof.z64)
+}
+
+/// Sizes of the encoded online and preprocessing openings of a domain (with their counts).
+pub(crate) fn domain_sizes(domain: &ProofSingle) -> (u64, u64) {
+    let online = domain.online.iter().fold(8, |size, online| {
+        size + ONLINE_FIXED_BYTES
+            + (online.recons.len() + online.corrs.len() + online.inputs.len()) as u64
+    });
+    let preprocessing = 8 + domain.preprocessing.len() as u64 * PREPROCESSING_BYTES;
+    (online, preprocessing)
+}
+
+/// Size of the encoded proof (without the header).
+pub(crate) fn encoded_size(proof: &Proof) -> u64 {
+    let (gf2_online, gf2_preprocessing) = domain_sizes(&proof.gf2);
+    let (z64_online, z64_preprocessing) = domain_sizes(&proof.z64);
+    2 * HASH_SIZE as u64 + gf2_online + gf2_preprocessing + z64_online + z64_preprocessing
+}
+
+// reads at most `limit` bytes from the underlying reader
+struct Decoder<R> {
+    reader: R,
+    limit: u64,
+    remaining: u64,
+}
+
+impl<R: Read> Decoder<R> {
+    fn reserve(&mut self, len: u64) -> Result<()> {
+        if len > self.remaining {
+            return Err(Error::TooLarge { limit: self.limit });
+        }
+        self.remaining -= len;
+        Ok(())
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        self.reserve(N as u64)?;
+        let mut array = [0u8; N];
+        self.reader.read_exact(&mut array)?;
+        Ok(array)
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn bytes(&mut self) -> Result<Vec<u8>> {
+        let len = self.u64()?;
+        self.reserve(len)?;
+        // grows with the data read: a corrupt length does not allocate up front
+        let mut bytes = vec![];
+        (&mut self.reader).take(len).read_to_end(&mut bytes)?;
+        if bytes.len() as u64 != len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(bytes)
+    }
+
+    fn domain(&mut self) -> Result<ProofSingle> {
+        let mut online = vec![];
+        for _ in 0..self.u64()? {
+            let omit = self.array::<1>()?[0];
+            let mut seeds = [[0u8; KEY_SIZE]; PLAYERS];
+            for seed in seeds.iter_mut() {
+                *seed = self.array()?;
+            }
+            online.push(OpenOnline {
+                omit,
+                seeds,
+                recons: self.bytes()?,
+                corrs: self.bytes()?,
+                inputs: self.bytes()?,
+            });
+        }
+        let mut preprocessing = vec![];
+        for _ in 0..self.u64()? {
+            preprocessing.push(OpenPreprocessing {
+                seed: self.array()?,
+                comm_online: self.array()?,
+            });
+        }
+        Ok(ProofSingle {
+            online,
+            preprocessing,
+        })
+    }
+}
+
+/// Reads a proof written by `write_proof`, rejecting encodings larger than `limit` bytes.
+pub(crate) fn read_proof<R: Read>(reader: R, limit: u64) -> Result<Proof> {
+    let mut decoder = Decoder {
+        reader,
+        limit,
+        remaining: limit,
+    };
+    Ok(Proof {
+        comm: decoder.array()?,
+        program: decoder.array()?,
+        gf2: decoder.domain()?,
+        z64: decoder.domain()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use super::*;
+    use crate::{CombineOperation, Operation};
+
+    #[test]
+    fn test_canonical_encoding() {
+        let program = vec![
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Mul(1, 0, 0)),
+        ];
+        let proof = Proof::from_program(&program, &[true], &[3]);
+
+        let mut bytes = vec![];
+        write_proof(&mut bytes, &proof).unwrap();
+        assert_eq!(bytes.len() as u64, encoded_size(&proof));
+        assert_eq!(read_proof(bytes.as_slice(), u64::MAX).unwrap(), proof);
+
+        // the layout of the fields
+        assert_eq!(bytes[..HASH_SIZE], proof.comm);
+        assert_eq!(bytes[HASH_SIZE..2 * HASH_SIZE], proof.program);
+        let count = u64::from_le_bytes(<[u8; 8]>::try_from(&bytes[64..72]).unwrap());
+        assert_eq!(count, proof.gf2.online.len() as u64);
+        assert_eq!(bytes[72], proof.gf2.online[0].omit);
+        assert_eq!(bytes[73..73 + KEY_SIZE], proof.gf2.online[0].seeds[0]);
+
+        // compatible with the proofs written with bincode (format version 2)
+        assert_eq!(bytes, bincode::serialize(&proof).unwrap());
+
+        // truncated or too large
+        assert!(matches!(
+            read_proof(&bytes[..bytes.len() - 1], u64::MAX),
+            Err(Error::Io(_))
+        ));
+        let limit = bytes.len() as u64 - 1;
+        assert!(matches!(
+            read_proof(bytes.as_slice(), limit),
+            Err(Error::TooLarge { limit: found }) if found == limit
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+use std::io::{Read, Write};
+
+use super::Parameters;
+use crate::crypto::hash::{Backend, HashBackend};
+use crate::crypto::prg::{self, PrgBackend, KEY_SIZE};
+use crate::error::{Error, Result};
+
+/// Magic number at the start of every serialized proof.
+pub const PROOF_MAGIC: [u8; 4] = *b"RVZK";
+
+/// Version of the serialized proof format, incremented on incompatible changes.
+///
+/// Version 2: the proof holds the hash of the program.
+/// Version 3: the header records the length of the seeds.
+/// Version 4: the header records the hash function.
+/// Version 5: the header records the PRG.
+pub const PROOF_VERSION: u16 = 5;
+
+/// Identifier of the domains proven: a GF2 and a Z64 program combined with B2A conversions.
+pub const PROOF_DOMAINS: u8 = 1;
+
+/// Size of the header: magic, version, domains, seed length, hash function, PRG
+/// and parameters (three u64).
+pub(crate) const HEADER_BYTES: u64 = 4 + 2 + 1 + 1 + 1 + 1 + 3 * 8;
+
+/// Writes the header preceding the encoded proof (see `encoding`).
+pub(crate) fn write_header<W: Write>(writer: &mut W, params: &Parameters) -> Result<()> {
+    writer.write_all(&PROOF_MAGIC)?;
+    writer.write_all(&PROOF_VERSION.to_le_bytes())?;
+    writer.write_all(&[PROOF_DOMAINS, KEY_SIZE as u8, Backend::ID, prg::Backend::ID])?;
+    for value in [params.players, params.total_reps, params.online_reps] {
+        writer.write_all(&(value as u64).to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Reads and checks the header, returns the parameters of the proof (not validated).
+pub(crate) fn read_header<R: Read>(reader: &mut R) -> Result<Parameters> {
+    let mut header = [0u8; HEADER_BYTES as usize];
+    reader.read_exact(&mut header[..4])?;
+    if header[..4] != PROOF_MAGIC {
+        return Err(Error::Magic);
+    }
+    reader.read_exact(&mut header[4..])?;
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if version != PROOF_VERSION {
+        return Err(Error::Version {
+            found: version,
+            supported: PROOF_VERSION,
+        });
+    }
+    if header[6] != PROOF_DOMAINS {
+        return Err(Error::Domains {
+            found: header[6],
+            expected: PROOF_DOMAINS,
+        });
+    }
+    if header[7] as usize != KEY_SIZE {
+        return Err(Error::KeySize {
+            found: header[7],
+            expected: KEY_SIZE as u8,
+        });
+    }
+    if header[8] != Backend::ID {
+        return Err(Error::HashFunction {
+            found: header[8],
+            expected: Backend::ID,
+        });
+    }
+    if header[9] != prg::Backend::ID {
+        return Err(Error::Prg {
+            found: header[9],
+            expected: prg::Backend::ID,
+        });
+    }
+    let mut values = header[10..].chunks_exact(8).map(|bytes| {
+        let mut value = [0u8; 8];
+        value.copy_from_slice(bytes);
+        u64::from_le_bytes(value) as usize
+    });
+    Ok(Parameters {
+        players: values.next().unwrap(),
+        total_reps: values.next().unwrap(),
+        online_reps: values.next().unwrap(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proof::Proof;
+    use crate::{CombineOperation, Operation};
+
+    #[test]
+    fn test_envelope() {
+        let program = vec![
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::GF2(Operation::AssertZero(0)),
+        ];
+        let proof = Proof::from_program(&program, &[false], &[]);
+        let mut bytes = vec![];
+        proof.serialize(&mut bytes).unwrap();
+        assert_eq!(bytes.len() as u64, proof.serialized_size());
+        assert_eq!(bytes[..4], PROOF_MAGIC);
+
+        let corrupt = |offset: usize| {
+            let mut bytes = bytes.clone();
+            bytes[offset] ^= 1;
+            Proof::deserialize(bytes.as_slice()).unwrap_err()
+        };
+        assert!(matches!(corrupt(0), Error::Magic));
+        assert!(matches!(
+            corrupt(4),
+            Error::Version {
+                found: 4,
+                supported: PROOF_VERSION
+            }
+        ));
+        assert!(matches!(corrupt(6), Error::Domains { found: 0, .. }));
+        assert!(matches!(corrupt(7), Error::KeySize { .. }));
+        assert!(matches!(corrupt(8), Error::HashFunction { .. }));
+        assert!(matches!(corrupt(9), Error::Prg { .. }));
+        assert!(matches!(corrupt(10), Error::Parameters { .. }));
+        assert_eq!(
+            corrupt(0).to_string(),
+            "not a proof (bad magic number), or a proof from an older version"
+        );
+
+        // a proof without the header (as written by previous versions)
+        let raw = bincode::serialize(&proof).unwrap();
+        assert!(matches!(
+            Proof::deserialize(raw.as_slice()),
+            Err(Error::Magic)
+        ));
+    }
+}
@@ -0,0 +1,123 @@
+use super::witness::check_witness;
+use super::{OutputValue, OutputWire, Outputs, ProgramSource, Public, Statement};
+use crate::error::Result;
+use crate::hints::Evaluator;
+
+/// Evaluates the program in the clear (without creating a proof), returns the output values.
+///
+/// Checks the witness like the prover (see `Proof::from_source`) and every assertion:
+/// for a program without `Random` operations, a witness which evaluates without error
+/// yields a proof which verifies, with the same output values.
+///
+/// `Random` wires evaluate to zero, while the proof draws them at random:
+/// assertions and outputs which depend on them are only checked for that one value.
+pub fn evaluate<S: ProgramSource + ?Sized>(
+    source: &S,
+    statement: &Statement,
+    wit_gf2: &[bool],
+    wit_z64: &[u64],
+    wire_counts: (usize, usize), // (z64, gf2)
+) -> Result<Outputs> {
+    check_witness(
+        source,
+        statement.public,
+        statement.outputs,
+        (wit_gf2.len(), wit_z64.len()),
+        wire_counts,
+    )?;
+
+    let mut evaluator = Evaluator::new(wit_gf2, wit_z64, &[]);
+    for input in statement.public {
+        match *input {
+            Public::GF2(wire, value) => evaluator.set_gf2(wire, value),
+            Public::Z64(wire, value) => evaluator.set_z64(wire, value),
+        }
+    }
+    for (idx, op) in source.rewind()?.enumerate() {
+        evaluator.step(idx, &op?)?;
+    }
+
+    Ok(statement
+        .outputs
+        .iter()
+        .map(|output| {
+            let value = match output.wire {
+                OutputWire::GF2(wire) => OutputValue::GF2(evaluator.gf2(wire)),
+                OutputWire::Z64(wire) => OutputValue::Z64(evaluator.z64(wire)),
+            };
+            (output.label.clone(), value)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::error::Error;
+    use crate::largest_wires;
+    use crate::proof::{Output, Proof, ProverConfig};
+    use crate::{CombineOperation, Operation};
+
+    #[test]
+    fn test_evaluate() {
+        // x * x - y = 0 for the public y, reveals x & b
+        let program = vec![
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Mul(1, 0, 0)),
+            CombineOperation::Z64(Operation::Sub(3, 1, 2)),
+            CombineOperation::Z64(Operation::AssertZero(3)),
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::GF2(Operation::Input(1)),
+            CombineOperation::GF2(Operation::Mul(2, 0, 1)),
+        ];
+        let outputs = [Output::gf2("and", 2), Output::z64("square", 1)];
+        let public = [Public::Z64(2, 49)];
+        let statement = Statement {
+            public: &public,
+            outputs: &outputs,
+            ..Statement::default()
+        };
+        let wire_counts = largest_wires(&program);
+        let eval = |wit_gf2: &[bool], wit_z64: &[u64]| {
+            evaluate(
+                program.as_slice(),
+                &statement,
+                wit_gf2,
+                wit_z64,
+                wire_counts,
+            )
+        };
+
+        // the same output values as the proof
+        let values = eval(&[true, true], &[7]).unwrap();
+        assert_eq!(values["and"], OutputValue::GF2(true));
+        assert_eq!(values["square"], OutputValue::Z64(49));
+        let proof = Proof::from_statement(
+            program.as_slice(),
+            &statement,
+            Arc::new(vec![true, true]),
+            Arc::new(vec![7]),
+            wire_counts,
+            &ProverConfig::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            proof
+                .verify_statement(program.as_slice(), &statement, wire_counts)
+                .unwrap(),
+            values
+        );
+
+        // an unsatisfied assertion, a witness of the wrong length
+        assert_eq!(
+            eval(&[true, true], &[6]).unwrap_err().to_string(),
+            "instruction 3: z64 assertion fails (wire 3 is not zero)"
+        );
+        assert!(matches!(
+            eval(&[true], &[7]),
+            Err(Error::WitnessLength { domain: "gf2", .. })
+        ));
+    }
+}
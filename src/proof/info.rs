@@ -0,0 +1,96 @@
+use std::fmt;
+
+use super::{encoding, Proof, ProofSingle};
+use crate::crypto::hash::{Hash, HASH_SIZE};
+
+/// Sizes of the part of a proof covering one domain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DomainInfo {
+    /// Opened online repetitions.
+    pub online_reps: usize,
+    /// Repetitions checked by opening the preprocessing.
+    pub preprocessing_reps: usize,
+    /// Serialized size of the online openings.
+    pub online_bytes: u64,
+    /// Serialized size of the preprocessing openings.
+    pub preprocessing_bytes: u64,
+    /// Packed reconstructions, corrections and masked inputs (summed over repetitions).
+    pub recons_bytes: usize,
+    pub corrs_bytes: usize,
+    pub inputs_bytes: usize,
+}
+
+impl DomainInfo {
+    fn new(proof: &ProofSingle) -> Self {
+        let (online_bytes, preprocessing_bytes) = encoding::domain_sizes(proof);
+        DomainInfo {
+            online_reps: proof.online.len(),
+            preprocessing_reps: proof.preprocessing.len(),
+            online_bytes,
+            preprocessing_bytes,
+            recons_bytes: proof.online.iter().map(|o| o.recons.len()).sum(),
+            corrs_bytes: proof.online.iter().map(|o| o.corrs.len()).sum(),
+            inputs_bytes: proof.online.iter().map(|o| o.inputs.len()).sum(),
+        }
+    }
+}
+
+/// Metadata of a proof (see `Proof::info`), to debug proofs which do not verify.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofInfo {
+    /// Commitment to all repetitions (from which the opened repetitions are derived).
+    pub commitment: [u8; HASH_SIZE],
+    /// Hash of the program the proof is for.
+    pub program: [u8; HASH_SIZE],
+    pub gf2: DomainInfo,
+    pub z64: DomainInfo,
+    /// Serialized size of the whole proof.
+    pub total_bytes: u64,
+}
+
+impl Proof {
+    pub fn info(&self) -> ProofInfo {
+        ProofInfo {
+            commitment: self.comm,
+            program: self.program,
+            gf2: DomainInfo::new(&self.gf2),
+            z64: DomainInfo::new(&self.z64),
+            total_bytes: self.serialized_size(),
+        }
+    }
+}
+
+impl fmt::Display for ProofInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "commitment: {}", Hash::from(self.commitment).to_hex())?;
+        writeln!(f, "program: {}", Hash::from(self.program).to_hex())?;
+        writeln!(f, "total bytes: {}", self.total_bytes)?;
+        writeln!(
+            f,
+            "{:<8} {:>7} {:>7} {:>14} {:>14} {:>12} {:>12} {:>12}",
+            "domain",
+            "online",
+            "preproc",
+            "online bytes",
+            "preproc bytes",
+            "recons",
+            "corrs",
+            "inputs"
+        )?;
+        for (name, info) in [("gf2", &self.gf2), ("z64", &self.z64)] {
+            writeln!(
+                f,
+                "{:<8} {:>7} {:>7} {:>14} {:>14} {:>12} {:>12} {:>12}",
+                name,
+                info.online_reps,
+                info.preprocessing_reps,
+                info.online_bytes,
+                info.preprocessing_bytes,
+                info.recons_bytes,
+                info.corrs_bytes,
+                info.inputs_bytes
+            )?;
+        }
+        Ok(())
+    }
+}
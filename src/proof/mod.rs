@@ -1,21 +1,67 @@
 use std::collections::HashMap;
 use std::convert::TryFrom;
+use std::io::{Read, Write};
 use std::sync::Arc;
 
 use rand::rngs::OsRng;
-use rand::RngCore;
+use rand::{CryptoRng, RngCore};
 use serde::{Deserialize, Serialize};
+use tracing::{debug, info_span};
 
 use crate::crypto::hash::{Hash, Hasher, HASH_SIZE};
 use crate::crypto::prg::{Key, KEY_SIZE};
 use crate::crypto::ro::RandomOracle;
+use crate::error::{Error, Result};
 use crate::interpreter::{CombineInstance, Instance};
 use crate::transcript::{ProverTranscript, VerifierTranscriptOnline, VerifierTranscriptPreprocess};
 use crate::{
-    CombineOperation, ONLINE_REPS, PACKED, PACKED_REPS, PLAYERS, PREPROCESSING_REPS, TOTAL_REPS,
+    largest_wires, CombineOperation, ONLINE_REPS, PACKED, PACKED_REPS, PLAYERS, PREPROCESSING_REPS,
+    TOTAL_REPS,
 };
 
+mod batch;
+// shared by the language bindings
+#[cfg(any(feature = "ffi", feature = "python", feature = "wasm"))]
+pub(crate) mod bytes;
+#[cfg(not(target_arch = "wasm32"))]
+mod cache;
+mod cancel;
+mod codec;
+mod config;
+mod encoding;
+mod envelope;
+mod evaluate;
+mod info;
+mod output;
+mod params;
+mod progress;
+mod public;
+mod source;
+mod statement;
+mod witness;
+
+pub use batch::verify_many;
+#[cfg(not(target_arch = "wasm32"))]
+pub use cache::ProofCache;
+pub use cancel::CancelToken;
+pub use config::ProverConfig;
+pub(crate) use envelope::HEADER_BYTES;
+pub use envelope::{PROOF_DOMAINS, PROOF_MAGIC, PROOF_VERSION};
+pub use evaluate::evaluate;
+pub use info::{DomainInfo, ProofInfo};
+pub use output::{Output, OutputValue, OutputWire, Outputs};
+pub use params::{Parameters, Preset};
+use progress::PassProgress;
+pub use progress::PROGRESS_TARGET;
+pub use public::Public;
+#[cfg(not(target_arch = "wasm32"))]
+pub use source::ProgramMap;
+pub use source::{ProgramFile, ProgramFilePass, ProgramSource};
+pub use statement::Statement;
+pub use witness::{BitOrder, WitnessBuilder};
+
 const CTX_CHALLENGE: &str = "random-oracle challenge";
+const CTX_PROVER_SEED: &str = "deterministic prover seed";
 
 // parallelize in release mode only (for easier debugging)
 #[cfg(not(debug_assertions))]
@@ -61,6 +107,7 @@ struct ProofSingle {
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Proof {
     comm: [u8; HASH_SIZE],
+    program: [u8; HASH_SIZE], // hash of the program, see `ProgramSource::program_hash`
     gf2: ProofSingle,
     z64: ProofSingle,
 }
@@ -108,8 +155,44 @@ pub(crate) fn combine_hashes<'a, I: Iterator<Item = &'a Hash>>(hashes: I) -> Has
 }
 
 impl ProofSingle {
-    pub fn check_format(&self) -> bool {
-        self.online.len() == ONLINE_REPS && self.preprocessing.len() == PREPROCESSING_REPS
+    pub fn check_format(&self, domain: &'static str) -> Result<()> {
+        let check = |what, expected, found| {
+            if found != expected {
+                return Err(Error::Format {
+                    domain,
+                    what,
+                    expected,
+                    found,
+                });
+            }
+            Ok(())
+        };
+        check("online repetitions", ONLINE_REPS, self.online.len())?;
+        check(
+            "preprocessing repetitions",
+            PREPROCESSING_REPS,
+            self.preprocessing.len(),
+        )
+    }
+
+    /// Checks that the online openings omit the players chosen by the challenge.
+    ///
+    /// The openings are ordered by repetition, `online` lists the (sorted) online repetitions.
+    pub fn check_challenge(
+        &self,
+        domain: &'static str,
+        online: &[usize],
+        open: &HashMap<usize, usize>,
+    ) -> Result<()> {
+        for (opening, repetition) in self.online.iter().zip(online.iter()) {
+            if opening.omit as usize != open[repetition] {
+                return Err(Error::Challenge {
+                    domain,
+                    repetition: *repetition,
+                });
+            }
+        }
+        Ok(())
     }
 }
 
@@ -122,39 +205,284 @@ impl Proof {
         wit_z64: Arc<Vec<u64>>,              // z64 witness
         wire_counts: (usize, usize),         // Sizes for instances
     ) -> Self {
+        Self::new_with_config(
+            circuit,
+            &[],
+            wit_gf2,
+            wit_z64,
+            wire_counts,
+            &ProverConfig::default(),
+        )
+    }
+
+    /// Like `new`, for the given application context (see `Statement::context`):
+    /// the proof only verifies with `verify_with_context` and the same context.
+    pub fn new_with_context(
+        circuit: Arc<Vec<CombineOperation>>, // combined circuit
+        context: &[u8],                      // application context
+        wit_gf2: Arc<Vec<bool>>,             // gf2 witness
+        wit_z64: Arc<Vec<u64>>,              // z64 witness
+        wire_counts: (usize, usize),         // Sizes for instances
+    ) -> Self {
+        let statement = Statement {
+            context,
+            ..Statement::default()
+        };
+        Self::from_statement(
+            circuit.as_slice(),
+            &statement,
+            wit_gf2,
+            wit_z64,
+            wire_counts,
+            &ProverConfig::default(),
+        )
+        .unwrap_or_else(|e| panic!("cannot prove: {}", e))
+    }
+
+    /// Proves an in-memory program.
+    ///
+    /// Panics if the witness does not match the program (one value per `Input` operation),
+    /// does not satisfy its assertions, a wire is beyond `wire_counts`
+    /// or the masked inputs cannot be spilled to disk: `from_source` returns the error instead.
+    pub fn new_with_config(
+        circuit: Arc<Vec<CombineOperation>>, // combined circuit
+        outputs: &[Output],                  // wires revealed to the verifier
+        wit_gf2: Arc<Vec<bool>>,             // gf2 witness
+        wit_z64: Arc<Vec<u64>>,              // z64 witness
+        wire_counts: (usize, usize),         // Sizes for instances
+        config: &ProverConfig,               // memory/spill settings
+    ) -> Self {
+        Self::from_source(
+            circuit.as_slice(),
+            outputs,
+            wit_gf2,
+            wit_z64,
+            wire_counts,
+            config,
+        )
+        .unwrap_or_else(|e| panic!("cannot prove: {}", e))
+    }
+
+    /// Like `new_with_config`, but draws the prover randomness from `rng` instead of the OS.
+    ///
+    /// The randomness of the prover is what makes the proof zero-knowledge:
+    /// `rng` must be a cryptographically secure generator with a secret seed.
+    /// Panics like `new_with_config`, see `from_source_with_rng` for a fallible variant.
+    pub fn new_with_rng<R: RngCore + CryptoRng>(
+        circuit: Arc<Vec<CombineOperation>>, // combined circuit
+        outputs: &[Output],                  // wires revealed to the verifier
+        wit_gf2: Arc<Vec<bool>>,             // gf2 witness
+        wit_z64: Arc<Vec<u64>>,              // z64 witness
+        wire_counts: (usize, usize),         // Sizes for instances
+        config: &ProverConfig,               // memory/spill settings
+        rng: &mut R,                         // prover randomness
+    ) -> Self {
+        Self::from_source_with_rng(
+            circuit.as_slice(),
+            outputs,
+            wit_gf2,
+            wit_z64,
+            wire_counts,
+            config,
+            rng,
+        )
+        .unwrap_or_else(|e| panic!("cannot prove: {}", e))
+    }
+
+    /// Proves a program read from a `ProgramSource` (one pass per packed repetition).
+    ///
+    /// The prover randomness comes from the OS, or from `config.seed` if set.
+    pub fn from_source<S: ProgramSource + ?Sized>(
+        source: &S,                  // combined circuit
+        outputs: &[Output],          // wires revealed to the verifier
+        wit_gf2: Arc<Vec<bool>>,     // gf2 witness
+        wit_z64: Arc<Vec<u64>>,      // z64 witness
+        wire_counts: (usize, usize), // Sizes for instances
+        config: &ProverConfig,       // memory/spill settings
+    ) -> Result<Self> {
+        let statement = Statement {
+            outputs,
+            ..Statement::default()
+        };
+        Self::from_statement(source, &statement, wit_gf2, wit_z64, wire_counts, config)
+    }
+
+    /// Proves a program read from a `ProgramSource` for the given public inputs,
+    /// which are assigned to their wires before the first operation of the program.
+    pub fn from_source_public<S: ProgramSource + ?Sized>(
+        source: &S,                  // combined circuit
+        public: &[Public],           // public inputs of the statement
+        outputs: &[Output],          // wires revealed to the verifier
+        wit_gf2: Arc<Vec<bool>>,     // gf2 witness
+        wit_z64: Arc<Vec<u64>>,      // z64 witness
+        wire_counts: (usize, usize), // Sizes for instances
+        config: &ProverConfig,       // memory/spill settings
+    ) -> Result<Self> {
+        let statement = Statement {
+            public,
+            outputs,
+            ..Statement::default()
+        };
+        Self::from_statement(source, &statement, wit_gf2, wit_z64, wire_counts, config)
+    }
+
+    /// Proves a program read from a `ProgramSource` for a `Statement`
+    /// (public inputs, outputs and application context).
+    pub fn from_statement<S: ProgramSource + ?Sized>(
+        source: &S,                  // combined circuit
+        statement: &Statement,       // public inputs, outputs and context
+        wit_gf2: Arc<Vec<bool>>,     // gf2 witness
+        wit_z64: Arc<Vec<u64>>,      // z64 witness
+        wire_counts: (usize, usize), // Sizes for instances
+        config: &ProverConfig,       // memory/spill settings
+    ) -> Result<Self> {
+        match config.seed {
+            Some(seed) => {
+                let mut ro = RandomOracle::new(CTX_PROVER_SEED, &seed);
+                Self::prove(
+                    source,
+                    statement,
+                    wit_gf2,
+                    wit_z64,
+                    wire_counts,
+                    config,
+                    |key| ro.fill(key),
+                )
+            }
+            None => Self::prove(
+                source,
+                statement,
+                wit_gf2,
+                wit_z64,
+                wire_counts,
+                config,
+                |key| OsRng.fill_bytes(key),
+            ),
+        }
+    }
+
+    /// Proves a program read from a `ProgramSource`, drawing the prover randomness from `rng`
+    /// (`config.seed` is ignored), see `new_with_rng`.
+    pub fn from_source_with_rng<S: ProgramSource + ?Sized, R: RngCore + CryptoRng>(
+        source: &S,                  // combined circuit
+        outputs: &[Output],          // wires revealed to the verifier
+        wit_gf2: Arc<Vec<bool>>,     // gf2 witness
+        wit_z64: Arc<Vec<u64>>,      // z64 witness
+        wire_counts: (usize, usize), // Sizes for instances
+        config: &ProverConfig,       // memory/spill settings
+        rng: &mut R,                 // prover randomness
+    ) -> Result<Self> {
+        let statement = Statement {
+            outputs,
+            ..Statement::default()
+        };
+        Self::prove(
+            source,
+            &statement,
+            wit_gf2,
+            wit_z64,
+            wire_counts,
+            config,
+            |key| rng.fill_bytes(key),
+        )
+    }
+
+    fn prove<S: ProgramSource + ?Sized>(
+        source: &S,
+        statement: &Statement,
+        wit_gf2: Arc<Vec<bool>>,
+        wit_z64: Arc<Vec<u64>>,
+        wire_counts: (usize, usize),
+        config: &ProverConfig,
+        fill: impl FnMut(&mut Key),
+    ) -> Result<Self> {
+        // the transcripts of the prover do not check the assertions: an unsatisfied witness
+        // is reported (with the failing instruction) before the much slower proving passes
+        evaluate(source, statement, &wit_gf2, &wit_z64, wire_counts)?;
+        Self::prove_evaluated(
+            source,
+            statement,
+            wit_gf2,
+            wit_z64,
+            wire_counts,
+            config,
+            fill,
+        )
+    }
+
+    // proves a witness which has been checked by `evaluate`
+    fn prove_evaluated<S: ProgramSource + ?Sized>(
+        source: &S,
+        statement: &Statement,
+        wit_gf2: Arc<Vec<bool>>,
+        wit_z64: Arc<Vec<u64>>,
+        wire_counts: (usize, usize),
+        config: &ProverConfig,
+        mut fill: impl FnMut(&mut Key),
+    ) -> Result<Self> {
+        let Statement {
+            public, outputs, ..
+        } = *statement;
         let (z64_count, gf2_count) = wire_counts;
-        // execute every instance in parallel
-        let instances: Vec<([Hash; PACKED], (_, _))> =
-            parallel_iter!((0..PACKED_REPS).collect::<Vec<usize>>())
-                .map(|_i| {
-                    // generate key-material for each instance in the batch
-                    let mut keys = [[0u8; KEY_SIZE]; PACKED];
-                    for key in keys.iter_mut().take(PACKED) {
-                        OsRng.fill_bytes(key);
-                    }
+        let _span = info_span!("prove", gf2_count, z64_count).entered();
+        let program = source.program_hash()?;
+        if let Some(cancel) = &config.cancel {
+            cancel.check()?;
+        }
+        progress::start(PACKED_REPS);
+
+        // generate key-material for each instance in the batch
+        let keys: Vec<[Key; PACKED]> = (0..PACKED_REPS)
+            .map(|_| {
+                let mut keys = [[0u8; KEY_SIZE]; PACKED];
+                keys.iter_mut().for_each(&mut fill);
+                keys
+            })
+            .collect();
 
-                    //
-                    let instance_gf2 = Instance::new(
-                        ProverTranscript::new(wit_gf2.iter().map(|b| (*b).into()), keys),
-                        gf2_count,
-                    );
-
-                    //
-                    let instance_z64 = Instance::new(
-                        ProverTranscript::new(wit_z64.iter().map(|b| (*b).into()), keys),
-                        z64_count,
-                    );
-
-                    // process every instruction in the circuit
-                    let mut ins = CombineInstance::new(instance_gf2, instance_z64);
-                    for op in circuit.iter() {
-                        ins.step(op);
-                    }
-                    let hash = ins.hash();
-                    let (gf2_ins, z64_ins) = ins.split();
-                    (hash, (gf2_ins.extract(), z64_ins.extract()))
-                })
-                .collect();
+        // execute every instance in parallel
+        let instances: Vec<([Hash; PACKED], (_, _))> = parallel_iter!(keys)
+            .map(|keys| -> Result<_> {
+                //
+                let instance_gf2 = Instance::new(
+                    ProverTranscript::new_with_config(
+                        wit_gf2.iter().map(|b| (*b).into()),
+                        keys,
+                        config,
+                    ),
+                    gf2_count,
+                );
+
+                //
+                let instance_z64 = Instance::new(
+                    ProverTranscript::new_with_config(
+                        wit_z64.iter().map(|b| (*b).into()),
+                        keys,
+                        config,
+                    ),
+                    z64_count,
+                );
+
+                // process every instruction in the circuit
+                let mut ins = CombineInstance::new(instance_gf2, instance_z64);
+                let mut progress = PassProgress::new("prove", config.cancel.as_ref());
+                for input in public {
+                    ins.step(&input.op());
+                }
+                for op in source.rewind()? {
+                    let op = op?;
+                    ins.step(&op);
+                    progress.step(&op)?;
+                }
+                for output in outputs.iter() {
+                    ins.output(output.wire);
+                }
+                progress.finish();
+                let hash = ins.hash();
+                let (gf2_ins, z64_ins) = ins.split();
+                Ok((hash, (gf2_ins.extract(), z64_ins.extract())))
+            })
+            .collect::<Result<_>>()?;
 
         // compute challenge
         let mut comms = vec![];
@@ -164,8 +492,10 @@ impl Proof {
             transcripts.push(extractions);
         }
 
-        // commit to transcript states
-        let comm = combine_hashes(comms.iter());
+        // commit to transcript states (and the program and statement)
+        comms.push(Hash::from(program));
+        let comm = combine_hashes(comms.iter().chain(statement.hashes().iter()));
+        debug!(comm = %comm.to_hex(), "committed to all repetitions");
 
         // ask random oracle which players to open
         let open = challenge_to_opening(comm.as_bytes());
@@ -192,8 +522,8 @@ impl Proof {
             (Vec<OpenOnline>, Vec<OpenPreprocessing>),
             (Vec<OpenOnline>, Vec<OpenPreprocessing>),
         )> = ext
-            .map(|((gf2, z64), open)| (gf2.extract(open), z64.extract(open)))
-            .collect();
+            .map(|((gf2, z64), open)| -> Result<_> { Ok((gf2.extract(open)?, z64.extract(open)?)) })
+            .collect::<Result<_>>()?;
 
         // collect all the online/preprocessing openings into a single vector
 
@@ -214,28 +544,212 @@ impl Proof {
             z64.preprocessing.extend(rep.1 .1.into_iter());
         }
 
-        Proof {
+        Ok(Proof {
             comm: comm.into(),
+            program,
             gf2,
             z64,
-        }
+        })
+    }
+
+    /// Hash of the program the proof is for, see `ProgramSource::program_hash`.
+    pub fn program_hash(&self) -> [u8; HASH_SIZE] {
+        self.program
+    }
+
+    /// Proves a mixed GF2/Z64 program in one call: the wire counts are computed from the program.
+    pub fn from_program(program: &[CombineOperation], wit_gf2: &[bool], wit_z64: &[u64]) -> Self {
+        Self::new(
+            Arc::new(program.to_vec()),
+            Arc::new(wit_gf2.to_vec()),
+            Arc::new(wit_z64.to_vec()),
+            largest_wires(program),
+        )
+    }
+
+    /// Verifies a proof created with `from_program`.
+    pub fn verify_program(&self, program: &[CombineOperation]) -> Result<()> {
+        self.verify(Arc::new(program.to_vec()), largest_wires(program))
+    }
+
+    pub fn verify(
+        &self,
+        circuit: Arc<Vec<CombineOperation>>,
+        wire_counts: (usize, usize),
+    ) -> Result<()> {
+        self.verify_with_outputs(circuit, &[], wire_counts)
+            .map(|_| ())
+    }
+
+    /// Verifies a proof created by `new_with_context` with the same application context.
+    pub fn verify_with_context(
+        &self,
+        circuit: Arc<Vec<CombineOperation>>,
+        context: &[u8],
+        wire_counts: (usize, usize),
+    ) -> Result<()> {
+        let statement = Statement {
+            context,
+            ..Statement::default()
+        };
+        self.verify_statement(circuit.as_slice(), &statement, wire_counts)
+            .map(|_| ())
+    }
+
+    /// Verifies the proof and returns the values of the output wires keyed by label.
+    ///
+    /// The outputs must be the same as those the proof was created with.
+    pub fn verify_with_outputs(
+        &self,
+        circuit: Arc<Vec<CombineOperation>>,
+        outputs: &[Output],
+        wire_counts: (usize, usize),
+    ) -> Result<Outputs> {
+        self.verify_source(circuit.as_slice(), outputs, wire_counts)
+    }
+
+    /// Verifies the proof and checks that the outputs have the expected values,
+    /// returns the values of all the output wires (see `verify_with_outputs`).
+    ///
+    /// Every label of `expected` must be revealed with that value,
+    /// outputs without an expected value are returned unchecked.
+    pub fn verify_with_expected_outputs(
+        &self,
+        circuit: Arc<Vec<CombineOperation>>,
+        outputs: &[Output],
+        expected: &Outputs,
+        wire_counts: (usize, usize),
+    ) -> Result<Outputs> {
+        self.verify_source_expected(circuit.as_slice(), outputs, expected, wire_counts)
     }
 
-    pub fn verify(&self, circuit: Arc<Vec<CombineOperation>>, wire_counts: (usize, usize)) -> bool {
-        if !self.gf2.check_format() {
-            return false;
+    /// Verifies the proof against a program read from a `ProgramSource` and checks the outputs,
+    /// see `verify_with_expected_outputs`.
+    pub fn verify_source_expected<S: ProgramSource + ?Sized>(
+        &self,
+        source: &S,
+        outputs: &[Output],
+        expected: &Outputs,
+        wire_counts: (usize, usize),
+    ) -> Result<Outputs> {
+        let values = self.verify_source(source, outputs, wire_counts)?;
+        for (label, expected) in expected.iter() {
+            match values.get(label) {
+                None => {
+                    return Err(Error::OutputMissing {
+                        label: label.clone(),
+                    })
+                }
+                Some(found) if found != expected => {
+                    return Err(Error::OutputMismatch {
+                        label: label.clone(),
+                        expected: *expected,
+                        found: *found,
+                    })
+                }
+                Some(_) => (),
+            }
         }
-        if !self.z64.check_format() {
-            return false;
+        Ok(values)
+    }
+
+    /// Verifies the proof against a program read from a `ProgramSource`, see `verify_with_outputs`.
+    pub fn verify_source<S: ProgramSource + ?Sized>(
+        &self,
+        source: &S,
+        outputs: &[Output],
+        wire_counts: (usize, usize),
+    ) -> Result<Outputs> {
+        let statement = Statement {
+            outputs,
+            ..Statement::default()
+        };
+        self.verify_statement(source, &statement, wire_counts)
+    }
+
+    /// Verifies the proof for the given public inputs, see `from_source_public`.
+    pub fn verify_source_public<S: ProgramSource + ?Sized>(
+        &self,
+        source: &S,
+        public: &[Public],
+        outputs: &[Output],
+        wire_counts: (usize, usize),
+    ) -> Result<Outputs> {
+        let statement = Statement {
+            public,
+            outputs,
+            ..Statement::default()
+        };
+        self.verify_statement(source, &statement, wire_counts)
+    }
+
+    /// Verifies the proof of a program for a `Statement`, see `from_statement`,
+    /// returns the values of the output wires keyed by label.
+    pub fn verify_statement<S: ProgramSource + ?Sized>(
+        &self,
+        source: &S,
+        statement: &Statement,
+        wire_counts: (usize, usize),
+    ) -> Result<Outputs> {
+        self.verify_hashed(source, source.program_hash()?, statement, wire_counts, None)
+    }
+
+    /// Verifies the proof for a `Statement` like `verify_statement`, but stops
+    /// (with `Error::Cancelled`) once the token is cancelled or times out.
+    pub fn verify_cancellable<S: ProgramSource + ?Sized>(
+        &self,
+        source: &S,
+        statement: &Statement,
+        wire_counts: (usize, usize),
+        cancel: &CancelToken,
+    ) -> Result<Outputs> {
+        let program = source.program_hash()?;
+        cancel.check()?;
+        self.verify_hashed(source, program, statement, wire_counts, Some(cancel))
+    }
+
+    // verification with the hash of the program computed by the caller
+    pub(crate) fn verify_hashed<S: ProgramSource + ?Sized>(
+        &self,
+        source: &S,
+        program: [u8; HASH_SIZE],
+        statement: &Statement,
+        wire_counts: (usize, usize),
+        cancel: Option<&CancelToken>,
+    ) -> Result<Outputs> {
+        let Statement {
+            public, outputs, ..
+        } = *statement;
+        self.gf2.check_format("gf2")?;
+        self.z64.check_format("z64")?;
+        public::check_public(public, wire_counts)?;
+
+        // catch verifying against another program early (the commitment would not match)
+        if program != self.program {
+            return Err(Error::Program {
+                proof: Hash::from(self.program).to_hex().to_string(),
+                program: Hash::from(program).to_hex().to_string(),
+            });
         }
 
+        // the online repetitions (in order) and the players they omit
+        let open = challenge_to_opening(&self.comm);
+        let mut online: Vec<usize> = open.keys().copied().collect();
+        online.sort_unstable();
+        self.gf2.check_challenge("gf2", &online, &open)?;
+        self.z64.check_challenge("z64", &online, &open)?;
+
         let (z64_count, gf2_count) = wire_counts;
+        let _span = info_span!("verify", gf2_count, z64_count).entered();
+        progress::start(TOTAL_REPS / PACKED);
 
-        let online_reps: Vec<(&[OpenOnline], &[OpenOnline])> = self
+        #[allow(clippy::type_complexity)]
+        let online_reps: Vec<((&[OpenOnline], &[OpenOnline]), &[usize])> = self
             .gf2
             .online
             .chunks_exact(PACKED)
             .zip(self.z64.online.chunks_exact(PACKED))
+            .zip(online.chunks_exact(PACKED))
             .collect();
 
         let preprocessing_reps: Vec<(&[OpenPreprocessing], &[OpenPreprocessing])> = self
@@ -246,50 +760,107 @@ impl Proof {
             .collect();
 
         // prepare all the online repetitions (in batches of 8)
-        let online_reps = parallel_iter!(online_reps).map(|(gf2, z64)| {
-            let instance_gf2 = Instance::new(
-                VerifierTranscriptOnline::new(<&[_; PACKED]>::try_from(gf2).unwrap()),
-                gf2_count,
-            );
-            let instance_z64 = Instance::new(
-                VerifierTranscriptOnline::new(<&[_; PACKED]>::try_from(z64).unwrap()),
-                z64_count,
-            );
-            let mut ins = CombineInstance::new(instance_gf2, instance_z64);
-            for op in circuit.iter() {
-                ins.step(op);
-            }
-            ins.hash()
-        });
+        let online_reps =
+            parallel_iter!(online_reps).map(|((gf2, z64), repetitions)| -> Result<_> {
+                let instance_gf2 = Instance::new(
+                    VerifierTranscriptOnline::new(<&[_; PACKED]>::try_from(gf2).unwrap()),
+                    gf2_count,
+                );
+                let instance_z64 = Instance::new(
+                    VerifierTranscriptOnline::new(<&[_; PACKED]>::try_from(z64).unwrap()),
+                    z64_count,
+                );
+                let mut ins = CombineInstance::new(instance_gf2, instance_z64);
+                let mut progress = PassProgress::new("online", cancel);
+                for input in public {
+                    ins.step(&input.op());
+                }
+                for op in source.rewind()? {
+                    let op = op?;
+                    ins.step(&op);
+                    progress.step(&op)?;
+                }
+                progress.finish();
+
+                // every assertion must hold in every repetition of the batch
+                let (gf2, z64) = ins.transcripts();
+                for (domain, okay) in [("gf2", gf2.okay()), ("z64", z64.okay())] {
+                    if !okay {
+                        return Err(Error::AssertZero {
+                            domain,
+                            repetitions: repetitions.to_vec(),
+                        });
+                    }
+                }
+
+                let values: Vec<[OutputValue; PACKED]> = outputs
+                    .iter()
+                    .map(|output| ins.output(output.wire))
+                    .collect();
+                Ok((ins.hash(), values))
+            });
 
         // prepare all the preprocessing repetitions (in batches of 8)
-        let preprocessing_reps = parallel_iter!(preprocessing_reps).map(|(gf2, z64)| {
-            let instance_gf2 = Instance::new(
-                VerifierTranscriptPreprocess::new(<&[_; PACKED]>::try_from(gf2).unwrap()),
-                gf2_count,
-            );
-            let instance_z64 = Instance::new(
-                VerifierTranscriptPreprocess::new(<&[_; PACKED]>::try_from(z64).unwrap()),
-                z64_count,
-            );
-            let mut ins = CombineInstance::new(instance_gf2, instance_z64);
-            for op in circuit.iter() {
-                ins.step(op);
-            }
-            ins.hash()
-        });
+        let preprocessing_reps =
+            parallel_iter!(preprocessing_reps).map(|(gf2, z64)| -> Result<_> {
+                let instance_gf2 = Instance::new(
+                    VerifierTranscriptPreprocess::new(<&[_; PACKED]>::try_from(gf2).unwrap()),
+                    gf2_count,
+                );
+                let instance_z64 = Instance::new(
+                    VerifierTranscriptPreprocess::new(<&[_; PACKED]>::try_from(z64).unwrap()),
+                    z64_count,
+                );
+                let mut ins = CombineInstance::new(instance_gf2, instance_z64);
+                let mut progress = PassProgress::new("preprocessing", cancel);
+                for input in public {
+                    ins.step(&input.op());
+                }
+                for op in source.rewind()? {
+                    let op = op?;
+                    ins.step(&op);
+                    progress.step(&op)?;
+                }
+                for output in outputs.iter() {
+                    ins.output(output.wire);
+                }
+                progress.finish();
+                Ok((ins.hash(), vec![]))
+            });
 
         // run all the executions
-        let reps: Vec<[Hash; PACKED]> = online_reps.chain(preprocessing_reps).collect();
+        let reps: Vec<([Hash; PACKED], Vec<[OutputValue; PACKED]>)> = online_reps
+            .chain(preprocessing_reps)
+            .collect::<Result<_>>()?;
 
         // flat vector of array of hashes to a single vector of hashes
         let mut hashes: Vec<Hash> = Vec::with_capacity(TOTAL_REPS);
-        for arr in reps.iter() {
+        for (arr, _) in reps.iter() {
             hashes.extend(arr);
         }
 
+        // every online repetition must reveal the same outputs
+        let mut values: Vec<Option<OutputValue>> = vec![None; outputs.len()];
+        for (batch, (_, rep_values)) in reps[..ONLINE_REPS / PACKED].iter().enumerate() {
+            for ((value, rep_value), output) in
+                values.iter_mut().zip(rep_values.iter()).zip(outputs.iter())
+            {
+                for (i, v) in rep_value.iter().enumerate() {
+                    match value {
+                        None => *value = Some(*v),
+                        Some(value) if value != v => {
+                            return Err(Error::Outputs {
+                                label: output.label.clone(),
+                                repetition: online[batch * PACKED + i],
+                            })
+                        }
+                        _ => (),
+                    }
+                }
+            }
+        }
+
         // order the repetitions
-        let open = challenge_to_opening(&self.comm);
         let mut online_hashes = hashes[..ONLINE_REPS].iter();
         let mut preprocessing_hashes = hashes[ONLINE_REPS..].iter();
         let mut ordered_hashes: Vec<&Hash> = Vec::with_capacity(TOTAL_REPS);
@@ -301,15 +872,56 @@ impl Proof {
             }
         }
 
-        // join all the hashes into a single hash (fed to the RO)
-        let comm = combine_hashes(ordered_hashes.into_iter());
-        comm.as_bytes() == &self.comm
+        // join all the hashes (and the program and statement) into a single hash (fed to the RO)
+        let program = Hash::from(program);
+        ordered_hashes.push(&program);
+        let statement = statement.hashes();
+        let comm = combine_hashes(ordered_hashes.into_iter().chain(statement.iter()));
+        if comm.as_bytes() != &self.comm {
+            return Err(Error::Commitment);
+        }
+
+        Ok(outputs
+            .iter()
+            .zip(values)
+            .map(|(output, value)| (output.label.clone(), value.unwrap()))
+            .collect())
+    }
+
+    /// Writes the proof: a header identifying the format, domains and parameters,
+    /// followed by the canonical encoding of the proof (see `encoding`).
+    pub fn serialize<W: Write>(&self, mut writer: W) -> Result<()> {
+        envelope::write_header(&mut writer, &Parameters::compiled())?;
+        Ok(encoding::write_proof(&mut writer, self)?)
+    }
+
+    /// Reads a proof written by `serialize`.
+    ///
+    /// Proofs of another format version, for other domains or with other parameters
+    /// than those compiled into this build are rejected.
+    pub fn deserialize<R: Read>(reader: R) -> Result<Self> {
+        Self::deserialize_with_limit(reader, u64::MAX)
+    }
+
+    /// Reads a proof written by `serialize` like `deserialize`, but rejects (`Error::TooLarge`)
+    /// proofs whose encoding after the header is larger than `limit` bytes,
+    /// before allocating memory for them.
+    pub fn deserialize_with_limit<R: Read>(mut reader: R, limit: u64) -> Result<Self> {
+        let params = envelope::read_header(&mut reader)?;
+        Parameters::new(params.players, params.total_reps, params.online_reps)?;
+        encoding::read_proof(reader, limit)
+    }
+
+    /// Size of the proof written by `serialize`.
+    pub fn serialized_size(&self) -> u64 {
+        envelope::HEADER_BYTES + encoding::encoded_size(self)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use bincode;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
     use test::Bencher;
 
     use super::*;
@@ -341,7 +953,7 @@ mod tests {
 
         let wit_gf2 = Arc::new(vec![true, true]);
 
-        let wit_z64 = Arc::new(vec![0]);
+        let wit_z64 = Arc::new(vec![]);
 
         b.iter(|| {
             Proof::new(
@@ -379,7 +991,7 @@ mod tests {
 
         let wit_gf2 = Arc::new(vec![true, true]);
 
-        let wit_z64 = Arc::new(vec![0]);
+        let wit_z64 = Arc::new(vec![]);
 
         let proof = Proof::new(
             circuit.clone(),
@@ -390,7 +1002,7 @@ mod tests {
 
         b.iter(|| {
             println!("1");
-            assert!(proof.verify(circuit.clone(), (128, 128)));
+            proof.verify(circuit.clone(), (128, 128)).unwrap();
         });
     }
 
@@ -416,13 +1028,534 @@ mod tests {
         );
 
         let circuit = Arc::new(circuit);
-        let wit_gf2 = Arc::new(vec![true; 128]);
-        let wit_z64 = Arc::new(vec![0]);
+        let wit_gf2 = Arc::new(vec![true; 66]);
+        let wit_z64 = Arc::new(vec![]);
 
         let proof = Proof::new(circuit.clone(), wit_gf2, wit_z64, (128, 128));
 
-        println!("size = {}", bincode::serialize(&proof).unwrap().len());
+        let mut bytes = vec![];
+        proof.serialize(&mut bytes).unwrap();
+        println!("size = {}", bytes.len());
+
+        let info = proof.info();
+        assert_eq!(info.total_bytes as usize, bytes.len());
+        assert_eq!(info.gf2.online_reps, ONLINE_REPS);
+        assert_eq!(info.gf2.preprocessing_reps, PREPROCESSING_REPS);
+        assert!(info.gf2.recons_bytes > 0);
 
-        assert!(proof.verify(circuit, (128, 128)));
+        proof.verify(circuit, (128, 128)).unwrap();
+    }
+
+    #[test]
+    fn test_prover_spill_inputs() {
+        let mut circuit = vec![];
+        for i in 0..100 {
+            circuit.push(CombineOperation::GF2(Operation::Input(i)));
+        }
+        circuit.push(CombineOperation::GF2(Operation::Mul(100, 0, 1)));
+        circuit.push(CombineOperation::Z64(Operation::Input(0)));
+        circuit.push(CombineOperation::Z64(Operation::Input(1)));
+        circuit.push(CombineOperation::Z64(Operation::Mul(2, 0, 1)));
+
+        let circuit = Arc::new(circuit);
+        let wit_gf2: Arc<Vec<bool>> = Arc::new((0..100).map(|i| i % 3 == 0).collect());
+        let wit_z64 = Arc::new(vec![3, 5]);
+
+        let config = ProverConfig {
+            input_spill_threshold: Some(7),
+            spill_dir: None,
+            seed: None,
+            cancel: None,
+        };
+        let proof = Proof::new_with_config(
+            circuit.clone(),
+            &[],
+            wit_gf2.clone(),
+            wit_z64.clone(),
+            (3, 101),
+            &config,
+        );
+        proof.verify(circuit.clone(), (3, 101)).unwrap();
+
+        // a spill file which cannot be created is an error, not a panic
+        let config = ProverConfig {
+            spill_dir: Some(std::env::temp_dir().join("reverie-no-such-dir")),
+            ..config
+        };
+        let result =
+            Proof::from_source(circuit.as_slice(), &[], wit_gf2, wit_z64, (3, 101), &config);
+        assert!(matches!(result, Err(Error::Io(_))), "{:?}", result.err());
+    }
+
+    #[test]
+    fn test_prover_seed() {
+        let circuit = vec![
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::GF2(Operation::Input(1)),
+            CombineOperation::GF2(Operation::Mul(2, 0, 1)),
+        ];
+        let prove = |seed| {
+            let config = ProverConfig {
+                seed,
+                ..ProverConfig::default()
+            };
+            Proof::new_with_config(
+                Arc::new(circuit.clone()),
+                &[],
+                Arc::new(vec![true, false]),
+                Arc::new(vec![]),
+                (0, 3),
+                &config,
+            )
+        };
+
+        // the same seed gives the same proof, which verifies
+        let proof = prove(Some([1; 32]));
+        assert_eq!(proof, prove(Some([1; 32])));
+        assert_ne!(proof, prove(Some([2; 32])));
+        assert_ne!(prove(None), prove(None));
+        proof.verify(Arc::new(circuit.clone()), (0, 3)).unwrap();
+
+        // as does the same caller-provided generator
+        let prove_rng = |seed| {
+            Proof::new_with_rng(
+                Arc::new(circuit.clone()),
+                &[],
+                Arc::new(vec![true, false]),
+                Arc::new(vec![]),
+                (0, 3),
+                &ProverConfig::default(),
+                &mut StdRng::seed_from_u64(seed),
+            )
+        };
+        let proof = prove_rng(1);
+        assert_eq!(proof, prove_rng(1));
+        assert_ne!(proof, prove_rng(2));
+        proof.verify(Arc::new(circuit), (0, 3)).unwrap();
+    }
+
+    #[test]
+    fn test_prover_cancel() {
+        let circuit = vec![
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Mul(1, 0, 0)),
+        ];
+        let cancel = CancelToken::new();
+        let config = ProverConfig {
+            cancel: Some(cancel.clone()),
+            ..ProverConfig::default()
+        };
+        let prove = || {
+            Proof::from_source(
+                circuit.as_slice(),
+                &[],
+                Arc::new(vec![]),
+                Arc::new(vec![3]),
+                (2, 0),
+                &config,
+            )
+        };
+        let proof = prove().unwrap();
+        let statement = Statement::default();
+        proof
+            .verify_cancellable(circuit.as_slice(), &statement, (2, 0), &cancel)
+            .unwrap();
+
+        // cancelling a clone cancels the token
+        cancel.clone().cancel();
+        assert!(matches!(prove(), Err(Error::Cancelled)));
+        assert!(matches!(
+            proof.verify_cancellable(circuit.as_slice(), &statement, (2, 0), &cancel),
+            Err(Error::Cancelled)
+        ));
+
+        let expired = CancelToken::with_timeout(std::time::Duration::ZERO);
+        assert!(expired.is_cancelled());
+        assert!(!CancelToken::with_timeout(std::time::Duration::from_secs(3600)).is_cancelled());
+    }
+
+    #[test]
+    fn test_prover_outputs() {
+        let circuit = Arc::new(vec![
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::GF2(Operation::Input(1)),
+            CombineOperation::GF2(Operation::Mul(2, 0, 1)),
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Input(1)),
+            CombineOperation::Z64(Operation::Mul(2, 0, 1)),
+            CombineOperation::Z64(Operation::AddConst(3, 2, 7)),
+        ]);
+        let outputs = vec![
+            Output::gf2("and", 2),
+            Output::z64("product", 2),
+            Output::z64("sum", 3),
+        ];
+        let wit_gf2 = Arc::new(vec![true, true]);
+        let wit_z64 = Arc::new(vec![6, 7]);
+
+        let proof = Proof::new_with_config(
+            circuit.clone(),
+            &outputs,
+            wit_gf2,
+            wit_z64,
+            (4, 3),
+            &ProverConfig::default(),
+        );
+
+        let values = proof
+            .verify_with_outputs(circuit.clone(), &outputs, (4, 3))
+            .unwrap();
+        assert_eq!(values["and"], OutputValue::GF2(true));
+        assert_eq!(values["product"], OutputValue::Z64(42));
+        assert_eq!(values["sum"], OutputValue::Z64(49));
+
+        // the outputs are part of the statement
+        assert!(matches!(
+            proof.verify(circuit.clone(), (4, 3)),
+            Err(Error::Commitment)
+        ));
+        assert!(proof
+            .verify_with_outputs(circuit.clone(), &outputs[..2], (4, 3))
+            .is_err());
+
+        // the expected values are checked (a subset of the outputs)
+        let mut expected = Outputs::new();
+        expected.insert("product".to_string(), OutputValue::Z64(42));
+        expected.insert("and".to_string(), OutputValue::GF2(true));
+        let checked = proof
+            .verify_with_expected_outputs(circuit.clone(), &outputs, &expected, (4, 3))
+            .unwrap();
+        assert_eq!(checked, values);
+        expected.insert("sum".to_string(), OutputValue::Z64(50));
+        match proof.verify_with_expected_outputs(circuit.clone(), &outputs, &expected, (4, 3)) {
+            Err(e @ Error::OutputMismatch { .. }) => {
+                assert_eq!(e.to_string(), "output 'sum' is 49, expected 50")
+            }
+            res => panic!("unexpected result {:?}", res),
+        }
+        expected.remove("sum");
+        expected.insert("difference".to_string(), OutputValue::Z64(1));
+        assert!(matches!(
+            proof.verify_with_expected_outputs(circuit, &outputs, &expected, (4, 3)),
+            Err(Error::OutputMissing { label }) if label == "difference"
+        ));
+    }
+
+    #[test]
+    fn test_prover_public() {
+        // knowledge of a square root of the public input y (wire 1)
+        let program = vec![
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Mul(2, 0, 0)),
+            CombineOperation::Z64(Operation::Sub(3, 2, 1)),
+            CombineOperation::Z64(Operation::AssertZero(3)),
+        ];
+        let prove = |y, x| {
+            Proof::from_source_public(
+                program.as_slice(),
+                &[Public::Z64(1, y)],
+                &[],
+                Arc::new(vec![]),
+                Arc::new(vec![x]),
+                (4, 0),
+                &ProverConfig::default(),
+            )
+            .unwrap()
+        };
+        let verify = |proof: &Proof, public: &[Public]| {
+            proof.verify_source_public(program.as_slice(), public, &[], (4, 0))
+        };
+
+        // the same program for different statements
+        verify(&prove(49, 7), &[Public::Z64(1, 49)]).unwrap();
+        let proof = prove(81, 9);
+        verify(&proof, &[Public::Z64(1, 81)]).unwrap();
+        assert!(verify(&proof, &[Public::Z64(1, 49)]).is_err());
+
+        // the public inputs are bound into the commitment, even if unused
+        assert!(matches!(
+            verify(&proof, &[Public::Z64(1, 81), Public::Z64(3, 5)]),
+            Err(Error::Commitment)
+        ));
+        assert!(matches!(
+            verify(&proof, &[Public::GF2(0, true)]),
+            Err(Error::WireRange { .. })
+        ));
+    }
+
+    #[test]
+    fn test_prover_context() {
+        let circuit = Arc::new(vec![
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::GF2(Operation::AssertZero(0)),
+        ]);
+        let proof = Proof::new_with_context(
+            circuit.clone(),
+            b"protocol a",
+            Arc::new(vec![false]),
+            Arc::new(vec![]),
+            (0, 1),
+        );
+        proof
+            .verify_with_context(circuit.clone(), b"protocol a", (0, 1))
+            .unwrap();
+
+        // the proof cannot be replayed in another protocol (or without context)
+        for context in [&b"protocol b"[..], b""] {
+            assert!(matches!(
+                proof.verify_with_context(circuit.clone(), context, (0, 1)),
+                Err(Error::Commitment)
+            ));
+        }
+        assert!(proof.verify(circuit.clone(), (0, 1)).is_err());
+
+        // contexts longer than a block of the hasher
+        let long = vec![7u8; 1000];
+        let proof = Proof::new_with_context(
+            circuit.clone(),
+            &long,
+            Arc::new(vec![false]),
+            Arc::new(vec![]),
+            (0, 1),
+        );
+        proof
+            .verify_with_context(circuit.clone(), &long, (0, 1))
+            .unwrap();
+        assert!(proof
+            .verify_with_context(circuit, &long[..999], (0, 1))
+            .is_err());
+    }
+
+    #[test]
+    fn test_prover_b2a() {
+        for value in [
+            0u64,
+            1,
+            0x8000_0000_0000_0000,
+            0xdead_beef_0123_4567,
+            u64::MAX,
+        ] {
+            // x (bits) converted to z64 must equal y (z64 input)
+            let mut circuit = vec![];
+            for i in 0..64 {
+                circuit.push(CombineOperation::GF2(Operation::Input(i)));
+            }
+            circuit.push(CombineOperation::B2A(0, 0));
+            circuit.push(CombineOperation::Z64(Operation::Input(1)));
+            circuit.push(CombineOperation::Z64(Operation::Sub(2, 0, 1)));
+            circuit.push(CombineOperation::Z64(Operation::AssertZero(2)));
+            circuit.push(CombineOperation::Z64(Operation::MulConst(3, 0, 3)));
+
+            let circuit = Arc::new(circuit);
+            let wit_gf2 = Arc::new((0..64).map(|i| (value >> i) & 1 == 1).collect());
+            let wit_z64 = Arc::new(vec![value]);
+            let outputs = [Output::z64("3x", 3)];
+
+            let proof = Proof::new_with_config(
+                circuit.clone(),
+                &outputs,
+                wit_gf2,
+                wit_z64,
+                (4, 64),
+                &ProverConfig::default(),
+            );
+            let values = proof
+                .verify_with_outputs(circuit, &outputs, (4, 64))
+                .unwrap();
+            assert_eq!(values["3x"], OutputValue::Z64(value.wrapping_mul(3)));
+        }
+    }
+
+    #[test]
+    fn test_prover_z64_gates() {
+        // every z64 gate, checked through both the online and preprocessing verification
+        let circuit = Arc::new(vec![
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Input(1)),
+            CombineOperation::Z64(Operation::Const(2, 1 << 63)),
+            CombineOperation::Z64(Operation::Random(3)),
+            CombineOperation::Z64(Operation::Add(4, 0, 1)),
+            CombineOperation::Z64(Operation::Sub(5, 4, 2)),
+            CombineOperation::Z64(Operation::Mul(6, 5, 0)),
+            CombineOperation::Z64(Operation::AddConst(7, 6, 11)),
+            CombineOperation::Z64(Operation::SubConst(8, 7, 12)),
+            CombineOperation::Z64(Operation::MulConst(9, 8, u64::MAX)),
+            CombineOperation::Z64(Operation::Mul(10, 3, 0)),
+            CombineOperation::Z64(Operation::Sub(11, 10, 10)),
+            CombineOperation::Z64(Operation::AssertZero(11)),
+        ]);
+        let (x, y) = (u64::MAX - 2, 12345);
+        let expected = x
+            .wrapping_add(y)
+            .wrapping_sub(1 << 63)
+            .wrapping_mul(x)
+            .wrapping_add(11)
+            .wrapping_sub(12)
+            .wrapping_mul(u64::MAX);
+
+        let outputs = [Output::z64("result", 9)];
+        let proof = Proof::new_with_config(
+            circuit.clone(),
+            &outputs,
+            Arc::new(vec![]),
+            Arc::new(vec![x, y]),
+            (12, 0),
+            &ProverConfig::default(),
+        );
+        let values = proof
+            .verify_with_outputs(circuit, &outputs, (12, 0))
+            .unwrap();
+        assert_eq!(values["result"], OutputValue::Z64(expected));
+    }
+
+    #[test]
+    fn test_verify_rejects_tampering() {
+        // enough gf2 multiplications that the (1-bit) corrections commit to the preprocessing
+        let mut circuit = vec![
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::GF2(Operation::Input(1)),
+        ];
+        for _ in 0..128 {
+            circuit.push(CombineOperation::GF2(Operation::Mul(2, 0, 1)));
+        }
+        circuit.push(CombineOperation::Z64(Operation::Input(0)));
+        circuit.push(CombineOperation::Z64(Operation::Mul(1, 0, 0)));
+        let circuit = Arc::new(circuit);
+        let proof = Proof::new(
+            circuit.clone(),
+            Arc::new(vec![true, true]),
+            Arc::new(vec![9]),
+            (2, 3),
+        );
+        proof.verify(circuit.clone(), (2, 3)).unwrap();
+
+        // the proof does not verify for a different program
+        let mut other = (*circuit).clone();
+        other.push(CombineOperation::GF2(Operation::AssertZero(2)));
+        let other = Arc::new(other);
+        assert!(matches!(
+            proof.verify(other.clone(), (2, 3)),
+            Err(Error::Program { .. })
+        ));
+        let mut renamed = proof.clone();
+        renamed.program[0] ^= 1;
+        assert!(matches!(
+            renamed.verify(circuit.clone(), (2, 3)),
+            Err(Error::Program { .. })
+        ));
+
+        // nor for a witness which does not satisfy the program:
+        // the prover refuses it, and the verifier reports the failing assertion with the
+        // first batch of online repetitions of a proof created without the check
+        let prove = |evaluated: bool| {
+            let prove = if evaluated {
+                Proof::prove_evaluated
+            } else {
+                Proof::prove
+            };
+            prove(
+                other.as_slice(),
+                &Statement::default(),
+                Arc::new(vec![true, true]),
+                Arc::new(vec![9]),
+                (2, 3),
+                &ProverConfig::default(),
+                |key: &mut Key| OsRng.fill_bytes(key),
+            )
+        };
+        assert_eq!(
+            prove(false).unwrap_err().to_string(),
+            "instruction 132: gf2 assertion fails (wire 2 is not zero)"
+        );
+        let unsatisfied = prove(true).unwrap();
+        match unsatisfied.verify(other, (2, 3)) {
+            Err(Error::AssertZero {
+                domain,
+                repetitions,
+            }) => {
+                let mut online: Vec<usize> = challenge_to_opening(&unsatisfied.comm)
+                    .into_keys()
+                    .collect();
+                online.sort_unstable();
+                assert_eq!(domain, "gf2");
+                assert_eq!(repetitions, online[..PACKED]);
+            }
+            res => panic!("unexpected result {:?}", res),
+        }
+
+        // true if the tampered proof is rejected because of the commitment
+        let tampered = |f: &dyn Fn(&mut Proof)| {
+            let mut proof = proof.clone();
+            f(&mut proof);
+            matches!(
+                proof.verify(circuit.clone(), (2, 3)),
+                Err(Error::Commitment)
+            )
+        };
+
+        // preprocessing openings
+        assert!(tampered(&|p| p.gf2.preprocessing[0].seed[0] ^= 1));
+        assert!(tampered(&|p| p.z64.preprocessing[7].comm_online[0] ^= 1));
+        assert!(tampered(&|p| {
+            p.z64.preprocessing.swap(0, 1);
+        }));
+
+        // online openings
+        assert!(tampered(&|p| p.gf2.online[0].corrs[0] ^= 1));
+        assert!(tampered(&|p| p.z64.online[3].recons[0] ^= 1));
+        assert!(tampered(&|p| p.z64.online[5].inputs[0] ^= 1));
+
+        // the opened players are checked against the challenge first
+        let mut other = proof.clone();
+        other.gf2.online[1].omit ^= 1;
+        assert!(matches!(
+            other.verify(circuit.clone(), (2, 3)),
+            Err(Error::Challenge { domain: "gf2", .. })
+        ));
+        let mut other = proof.clone();
+        other.comm[0] ^= 1;
+        assert!(matches!(
+            other.verify(circuit.clone(), (2, 3)),
+            Err(Error::Challenge { .. })
+        ));
+
+        // format
+        let mut truncated = proof.clone();
+        truncated.gf2.online.pop();
+        assert_eq!(
+            truncated
+                .verify(circuit.clone(), (2, 3))
+                .unwrap_err()
+                .to_string(),
+            format!(
+                "malformed proof: expected {} gf2 online repetitions, found {}",
+                ONLINE_REPS,
+                ONLINE_REPS - 1
+            )
+        );
+
+        // the encoding is checked when deserializing
+        let mut bytes = vec![];
+        proof.serialize(&mut bytes).unwrap();
+        assert_eq!(Proof::deserialize(bytes.as_slice()).unwrap(), proof);
+        assert!(matches!(
+            Proof::deserialize(&bytes[..bytes.len() / 2]),
+            Err(Error::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_prover_from_program() {
+        let program = vec![
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::GF2(Operation::Input(1)),
+            CombineOperation::GF2(Operation::Mul(2, 0, 1)),
+            CombineOperation::GF2(Operation::AssertZero(2)),
+            CombineOperation::Z64(Operation::Input(7)),
+            CombineOperation::Z64(Operation::SubConst(8, 7, 42)),
+            CombineOperation::Z64(Operation::AssertZero(8)),
+        ];
+        let proof = Proof::from_program(&program, &[true, false], &[42]);
+        proof.verify_program(&program).unwrap();
     }
 }
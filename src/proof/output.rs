@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// A wire whose value is revealed by the proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputWire {
+    GF2(usize),
+    Z64(usize),
+}
+
+/// An output wire with a label identifying it to the verifier.
+///
+/// The outputs are part of the statement:
+/// the prover and verifier must use the same list (in the same order).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Output {
+    pub label: String,
+    pub wire: OutputWire,
+}
+
+impl Output {
+    pub fn gf2<L: Into<String>>(label: L, wire: usize) -> Self {
+        Output {
+            label: label.into(),
+            wire: OutputWire::GF2(wire),
+        }
+    }
+
+    pub fn z64<L: Into<String>>(label: L, wire: usize) -> Self {
+        Output {
+            label: label.into(),
+            wire: OutputWire::Z64(wire),
+        }
+    }
+}
+
+/// The value of a revealed output wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputValue {
+    GF2(bool),
+    Z64(u64),
+}
+
+// gf2 values are printed as 0/1, as given to the verifier
+impl fmt::Display for OutputValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputValue::GF2(bit) => write!(f, "{}", *bit as u64),
+            OutputValue::Z64(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+/// Verified output values keyed by label.
+pub type Outputs = BTreeMap<String, OutputValue>;
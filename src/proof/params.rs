@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::{ONLINE_REPS, PACKED, PLAYERS, TOTAL_REPS};
+
+/// A parameter set of the proof system, as recorded in the header of a serialized proof.
+///
+/// The prover/verifier core is compiled for a single parameter set:
+/// `Parameters::new` only accepts that set, see `Parameters::compiled`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Parameters {
+    /// Players in the MPC protocol.
+    pub players: usize,
+    /// Total number of repetitions (committed to by the prover).
+    pub total_reps: usize,
+    /// Repetitions whose online phase is opened (the others open their preprocessing).
+    pub online_reps: usize,
+}
+
+impl Parameters {
+    /// 40 bits of soundness, for interactive or low-stakes uses (estimate only, see `Preset`).
+    pub const SOUNDNESS_40: Parameters = Parameters {
+        players: 8,
+        total_reps: 56,
+        online_reps: 16,
+    };
+
+    /// 64 bits of soundness (estimate only, see `Preset`).
+    pub const SOUNDNESS_64: Parameters = Parameters {
+        players: 8,
+        total_reps: 104,
+        online_reps: 24,
+    };
+
+    /// 80 bits of soundness (estimate only, see `Preset`).
+    pub const SOUNDNESS_80: Parameters = Parameters {
+        players: 8,
+        total_reps: 112,
+        online_reps: 32,
+    };
+
+    /// 128 bits of soundness, for non-interactive proofs (estimate only, see `Preset`).
+    pub const SOUNDNESS_128: Parameters = Parameters {
+        players: 8,
+        total_reps: 200,
+        online_reps: 48,
+    };
+
+    /// The named presets: for every soundness level, the set (with 8 players and repetitions
+    /// in multiples of 8) opening the fewest online repetitions, which dominate the proof size.
+    ///
+    /// Only the `default` preset (the compiled parameters, about 118 bits) can be used
+    /// for proving/verifying, the others are estimate only.
+    pub const PRESETS: [Preset; 5] = [
+        Preset::estimate("40-bit", Self::SOUNDNESS_40),
+        Preset::estimate("64-bit", Self::SOUNDNESS_64),
+        Preset::estimate("80-bit", Self::SOUNDNESS_80),
+        Preset::estimate("128-bit", Self::SOUNDNESS_128),
+        Preset {
+            name: "default",
+            params: Self::compiled(),
+            estimate_only: false,
+        },
+    ];
+
+    /// Looks up a preset by name, see `PRESETS`.
+    pub fn preset(name: &str) -> Option<Preset> {
+        Self::PRESETS
+            .iter()
+            .find(|preset| preset.name == name)
+            .copied()
+    }
+
+    /// The parameter set the prover/verifier core is compiled for.
+    pub const fn compiled() -> Self {
+        Parameters {
+            players: PLAYERS,
+            total_reps: TOTAL_REPS,
+            online_reps: ONLINE_REPS,
+        }
+    }
+
+    /// Validates a parameter set: it must be consistent and supported by this build.
+    pub fn new(players: usize, total_reps: usize, online_reps: usize) -> Result<Self> {
+        let params = Parameters {
+            players,
+            total_reps,
+            online_reps,
+        };
+        if players < 2
+            || online_reps == 0
+            || online_reps > total_reps
+            || !online_reps.is_multiple_of(PACKED)
+            || !total_reps.is_multiple_of(PACKED)
+        {
+            return Err(Error::Parameters {
+                params,
+                reason: "inconsistent parameter set",
+            });
+        }
+        if params != Self::compiled() {
+            return Err(Error::Parameters {
+                params,
+                reason: "not compiled into this build",
+            });
+        }
+        Ok(params)
+    }
+
+    /// Repetitions whose preprocessing is opened.
+    pub fn preprocessing_reps(&self) -> usize {
+        self.total_reps - self.online_reps
+    }
+
+    /// The achieved soundness: -log2 of the probability that a cheating prover succeeds.
+    ///
+    /// A prover cheating in the preprocessing of `k` repetitions succeeds if these are all
+    /// opened online (probability `C(M - k, t - k) / C(M, t)` for `M` total and `t` online
+    /// repetitions) and it guesses the unopened player in the other `t - k` online repetitions
+    /// (probability `1 / players` each). The soundness is that of the best `k` (KKW 2018).
+    pub fn soundness_bits(&self) -> f64 {
+        let (total, online) = (self.total_reps as f64, self.online_reps as f64);
+        let guess = (self.players as f64).log2();
+        let mut best = f64::NEG_INFINITY;
+        let mut opened = 0.0; // log2 C(M - k, t - k) / C(M, t)
+        for k in 0..=self.online_reps {
+            let k = k as f64;
+            best = best.max(opened - (online - k) * guess);
+            opened += ((online - k) / (total - k)).log2();
+        }
+        -best
+    }
+}
+
+/// A named parameter set, see `Parameters::PRESETS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Preset {
+    pub name: &'static str,
+    pub params: Parameters,
+    /// The set is not compiled into this build: `Parameters::new` rejects it,
+    /// it can only be used to estimate proof size and prover cost (see `passes::estimate`).
+    pub estimate_only: bool,
+}
+
+impl Preset {
+    const fn estimate(name: &'static str, params: Parameters) -> Self {
+        Preset {
+            name,
+            params,
+            estimate_only: true,
+        }
+    }
+}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self::compiled()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_soundness_bits() {
+        let bits = Parameters::compiled().soundness_bits();
+        assert!((bits - 118.29).abs() < 0.01, "{}", bits);
+
+        for (name, level) in [
+            ("40-bit", 40.0),
+            ("64-bit", 64.0),
+            ("80-bit", 80.0),
+            ("128-bit", 128.0),
+        ] {
+            let preset = Parameters::preset(name).unwrap();
+            let params = preset.params;
+            assert!(params.soundness_bits() >= level);
+            assert_eq!(params.online_reps % PACKED, 0);
+            assert_eq!(params.total_reps % PACKED, 0);
+        }
+        let default = Parameters::preset("default").unwrap();
+        assert_eq!(default.params, Parameters::compiled());
+        assert_eq!(Parameters::preset("1-bit"), None);
+
+        // exactly the presets which are not compiled in are estimate only
+        for preset in Parameters::PRESETS.iter() {
+            let compiled = Parameters::new(
+                preset.params.players,
+                preset.params.total_reps,
+                preset.params.online_reps,
+            );
+            assert_eq!(preset.estimate_only, compiled.is_err(), "{}", preset.name);
+        }
+
+        // opening every repetition online: only the guess of the unopened player remains
+        let online = Parameters {
+            players: 8,
+            total_reps: 8,
+            online_reps: 8,
+        };
+        assert!((online.soundness_bits() - 0.0).abs() < 1e-9);
+    }
+}
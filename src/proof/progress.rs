@@ -0,0 +1,79 @@
+use tracing::{debug, trace};
+
+use super::CancelToken;
+use crate::error::Result;
+use crate::{CombineOperation, Operation};
+
+/// Target of the `tracing` events reporting the progress of proving and verification.
+///
+/// Every pass over the program (one per packed repetition) emits an event per
+/// `GATES_PER_EVENT` gates and, when done, an event with the remainder and `passes = 1`.
+/// The events count the `gates` and `multiplications` processed since the previous event
+/// of the pass, and name its `phase`: "prove", or "online"/"preprocessing" (verification).
+/// The number of passes is announced by an event with a `total_passes` field.
+pub const PROGRESS_TARGET: &str = "reverie::progress";
+
+const GATES_PER_EVENT: u64 = 1 << 16;
+
+/// Announces the number of passes over the program.
+pub(crate) fn start(total_passes: usize) {
+    debug!(
+        target: PROGRESS_TARGET,
+        total_passes = total_passes as u64,
+        "starting passes"
+    );
+}
+
+/// Counts the gates processed by one pass over the program
+/// (and stops the pass every `GATES_PER_EVENT` gates if cancelled).
+pub(crate) struct PassProgress<'a> {
+    phase: &'static str,
+    gates: u64,
+    multiplications: u64,
+    cancel: Option<&'a CancelToken>,
+}
+
+impl<'a> PassProgress<'a> {
+    pub fn new(phase: &'static str, cancel: Option<&'a CancelToken>) -> Self {
+        PassProgress {
+            phase,
+            gates: 0,
+            multiplications: 0,
+            cancel,
+        }
+    }
+
+    pub fn step(&mut self, op: &CombineOperation) -> Result<()> {
+        self.gates += 1;
+        if matches!(
+            op,
+            CombineOperation::GF2(Operation::Mul(..)) | CombineOperation::Z64(Operation::Mul(..))
+        ) {
+            self.multiplications += 1;
+        }
+        if self.gates == GATES_PER_EVENT {
+            trace!(
+                target: PROGRESS_TARGET,
+                phase = self.phase,
+                gates = self.gates,
+                multiplications = self.multiplications
+            );
+            self.gates = 0;
+            self.multiplications = 0;
+            if let Some(cancel) = self.cancel {
+                cancel.check()?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn finish(self) {
+        debug!(
+            target: PROGRESS_TARGET,
+            phase = self.phase,
+            gates = self.gates,
+            multiplications = self.multiplications,
+            passes = 1u64
+        );
+    }
+}
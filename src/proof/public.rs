@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::hash::{Hash, Hasher};
+use crate::error::{Error, Result};
+use crate::{CombineOperation, Operation};
+
+const CTX_PUBLIC: &[u8] = b"public inputs";
+
+/// A wire assigned a public value before the program runs (a public input of the statement).
+///
+/// Public inputs let the same program be proven for different statements without baking
+/// the instance into the program as constants: both the prover and the verifier assign the
+/// values, which are hashed into the commitment (hence the challenge) of the proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Public {
+    GF2(usize, bool),
+    Z64(usize, u64),
+}
+
+impl Public {
+    /// The operation assigning the value to the wire.
+    pub(crate) fn op(&self) -> CombineOperation {
+        match *self {
+            Public::GF2(wire, value) => CombineOperation::GF2(Operation::Const(wire, value)),
+            Public::Z64(wire, value) => CombineOperation::Z64(Operation::Const(wire, value)),
+        }
+    }
+}
+
+/// Checks that the wires of the public inputs are within the wire counts (z64, gf2):
+/// the values are assigned before any `SizeHint` operation of the program.
+pub(crate) fn check_public(public: &[Public], wire_counts: (usize, usize)) -> Result<()> {
+    let (z64_wires, gf2_wires) = wire_counts;
+    for (idx, input) in public.iter().enumerate() {
+        let (domain, wire, wires) = match *input {
+            Public::GF2(wire, _) => ("gf2", wire, gf2_wires),
+            Public::Z64(wire, _) => ("z64", wire, z64_wires),
+        };
+        if wire >= wires {
+            return Err(Error::WireRange {
+                at: format!("public input {}", idx),
+                domain,
+                wire,
+                wires,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Hash of the public inputs, combined with the hashes of the repetitions into the commitment.
+///
+/// Without public inputs nothing is combined: the commitment is that of a plain program.
+pub(crate) fn hash_public(public: &[Public]) -> Option<Hash> {
+    if public.is_empty() {
+        return None;
+    }
+    let mut hasher = Hasher::new();
+    hasher.update(CTX_PUBLIC);
+    for input in public {
+        let (domain, wire, value) = match *input {
+            Public::GF2(wire, value) => (0u8, wire, value as u64),
+            Public::Z64(wire, value) => (1u8, wire, value),
+        };
+        hasher.update(&[domain]);
+        hasher.update(&(wire as u64).to_le_bytes());
+        hasher.update(&value.to_le_bytes());
+    }
+    Some(hasher.finalize())
+}
@@ -0,0 +1,197 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+#[cfg(not(target_arch = "wasm32"))]
+use memmap2::Mmap;
+
+use crate::crypto::hash::{Backend, HashBackend, HASH_SIZE};
+use crate::parsers::ProgramReader;
+use crate::{largest_wires, CombineOperation};
+
+const CTX_PROGRAM: &[u8] = b"program hash";
+
+/// A program which can be executed several times (concurrently) without holding it in memory.
+///
+/// The prover and verifier execute the program once per (packed) repetition.
+pub trait ProgramSource: Sync {
+    type Pass<'a>: Iterator<Item = io::Result<CombineOperation>> + 'a
+    where
+        Self: 'a;
+
+    /// Starts a new pass over the program from the first operation.
+    fn rewind(&self) -> io::Result<Self::Pass<'_>>;
+
+    /// Wire counts (z64, gf2) of the program, computed with one pass.
+    fn wire_counts(&self) -> io::Result<(usize, usize)> {
+        let (mut z64, mut gf2) = (0, 0);
+        for op in self.rewind()? {
+            let (z, g) = largest_wires(&[op?]);
+            z64 = z64.max(z);
+            gf2 = gf2.max(g);
+        }
+        Ok((z64, gf2))
+    }
+
+    /// Hash of the (bincode encoded) operations of the program, computed with one pass:
+    /// identifies the program a proof is for, see `Proof::program_hash`.
+    fn program_hash(&self) -> io::Result<[u8; HASH_SIZE]> {
+        let mut hasher = Backend::init();
+        hasher.absorb(CTX_PROGRAM);
+        for op in self.rewind()? {
+            bincode::serialize_into(&mut hasher, &op?).map_err(io::Error::other)?;
+        }
+        Ok(*hasher.digest().as_bytes())
+    }
+}
+
+impl ProgramSource for [CombineOperation] {
+    type Pass<'a> = std::iter::Map<
+        std::slice::Iter<'a, CombineOperation>,
+        fn(&CombineOperation) -> io::Result<CombineOperation>,
+    >;
+
+    fn rewind(&self) -> io::Result<Self::Pass<'_>> {
+        Ok(self.iter().map(|op| Ok(*op)))
+    }
+}
+
+/// A program in the compact binary format on disk, streamed chunk by chunk on every pass.
+#[derive(Debug, Clone)]
+pub struct ProgramFile {
+    path: PathBuf,
+}
+
+impl ProgramFile {
+    /// Checks that the file holds a program in the compact binary format.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        ProgramReader::new(BufReader::new(File::open(&path)?))?;
+        Ok(ProgramFile { path })
+    }
+}
+
+/// A pass over a program in the compact binary format: holds a single chunk at a time.
+pub struct ProgramFilePass<R: Read = BufReader<File>> {
+    reader: ProgramReader<R>,
+    chunk: std::vec::IntoIter<CombineOperation>,
+}
+
+impl<R: Read> ProgramFilePass<R> {
+    fn new(reader: R) -> io::Result<Self> {
+        Ok(ProgramFilePass {
+            reader: ProgramReader::new(reader)?,
+            chunk: vec![].into_iter(),
+        })
+    }
+}
+
+impl<R: Read> Iterator for ProgramFilePass<R> {
+    type Item = io::Result<CombineOperation>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(op) = self.chunk.next() {
+                return Some(Ok(op));
+            }
+            match self.reader.next()? {
+                Ok(chunk) => self.chunk = chunk.into_iter(),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+impl ProgramSource for ProgramFile {
+    type Pass<'a> = ProgramFilePass;
+
+    fn rewind(&self) -> io::Result<ProgramFilePass> {
+        ProgramFilePass::new(BufReader::new(File::open(&self.path)?))
+    }
+}
+
+/// A program in the compact binary format mapped into memory:
+/// the pages are loaded lazily (and shared between passes) by the OS.
+///
+/// Not available on wasm32 (there is no file system to map).
+#[cfg(not(target_arch = "wasm32"))]
+pub struct ProgramMap {
+    map: Mmap,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ProgramMap {
+    /// Maps the file and checks that it holds a program in the compact binary format.
+    ///
+    /// The file must not be modified while it is mapped.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let map = unsafe { Mmap::map(&file)? };
+        ProgramReader::new(&map[..])?;
+        Ok(ProgramMap { map })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ProgramSource for ProgramMap {
+    type Pass<'a> = ProgramFilePass<&'a [u8]>;
+
+    fn rewind(&self) -> io::Result<Self::Pass<'_>> {
+        ProgramFilePass::new(&self.map[..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::parsers::write_program;
+    use crate::proof::{Output, OutputValue, Proof, ProverConfig};
+    use crate::Operation;
+
+    #[test]
+    fn test_program_file() {
+        let mut program = vec![];
+        for i in 0..100 {
+            program.push(CombineOperation::Z64(Operation::Input(i)));
+        }
+        for i in 0..99 {
+            program.push(CombineOperation::Z64(Operation::Mul(100 + i, i, i + 1)));
+        }
+        let outputs = [Output::z64("last", 198)];
+        let wit_z64: Vec<u64> = (0..100).collect();
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        write_program(file.as_file(), &program, 16).unwrap();
+        let source = ProgramFile::open(file.path()).unwrap();
+        let wire_counts = source.wire_counts().unwrap();
+        assert_eq!(wire_counts, largest_wires(&program));
+
+        let proof = Proof::from_source(
+            &source,
+            &outputs,
+            Arc::new(vec![]),
+            Arc::new(wit_z64),
+            wire_counts,
+            &ProverConfig::default(),
+        )
+        .unwrap();
+        let values = proof.verify_source(&source, &outputs, wire_counts).unwrap();
+        assert_eq!(values["last"], OutputValue::Z64(98 * 99));
+
+        // the same proof verifies against the program in memory
+        proof
+            .verify_source(program.as_slice(), &outputs, wire_counts)
+            .unwrap();
+
+        // the same proof verifies against the mapped file
+        let map = ProgramMap::open(file.path()).unwrap();
+        assert_eq!(map.wire_counts().unwrap(), wire_counts);
+        proof.verify_source(&map, &outputs, wire_counts).unwrap();
+
+        // a file which is not a program
+        assert!(ProgramFile::open(tempfile::NamedTempFile::new().unwrap().path()).is_err());
+        assert!(ProgramMap::open(tempfile::NamedTempFile::new().unwrap().path()).is_err());
+    }
+}
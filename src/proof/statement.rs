@@ -0,0 +1,36 @@
+use super::public::hash_public;
+use super::{Output, Public};
+use crate::crypto::hash::{Hash, Hasher};
+
+const CTX_CONTEXT: &[u8] = b"application context";
+
+/// What is proven about a program, besides the program itself:
+/// the prover and the verifier must use the same statement.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Statement<'a> {
+    /// Public inputs assigned before the program runs, see `Public`.
+    pub public: &'a [Public],
+    /// Wires revealed to the verifier (in order).
+    pub outputs: &'a [Output],
+    /// Application context (domain separation), mixed into the commitment and the challenge:
+    /// a proof created for one protocol does not verify in another. Empty for none.
+    pub context: &'a [u8],
+}
+
+impl Statement<'_> {
+    /// Hashes combined with the hashes of the repetitions into the commitment
+    /// (none for a statement without public inputs and context).
+    pub(crate) fn hashes(&self) -> Vec<Hash> {
+        let mut hashes: Vec<Hash> = hash_public(self.public).into_iter().collect();
+        if !self.context.is_empty() {
+            let mut hasher = Hasher::new();
+            hasher.update(CTX_CONTEXT);
+            hasher.update(&(self.context.len() as u64).to_le_bytes());
+            for chunk in self.context.chunks(64) {
+                hasher.update(chunk);
+            }
+            hashes.push(hasher.finalize());
+        }
+        hashes
+    }
+}
@@ -0,0 +1,262 @@
+use std::sync::Arc;
+
+use super::public::{check_public, Public};
+use super::{Output, ProgramSource};
+use crate::error::{Error, Result};
+use crate::passes::WireBounds;
+use crate::{CombineOperation, Operation};
+
+/// Order in which the bits of a value are appended to the gf2 witness.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitOrder {
+    /// Least significant bit first: the order consumed by `B2A`.
+    Lsb,
+    /// Most significant bit first.
+    Msb,
+}
+
+/// Assembles the witness in the order consumed by the `Input` operations of the program.
+///
+/// Integers and byte strings are decomposed into gf2 bits with an explicit bit order,
+/// e.g. `WitnessBuilder::new().u8(0x1c, BitOrder::Msb)` is the witness 0, 0, 0, 1, 1, 1, 0, 0.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WitnessBuilder {
+    gf2: Vec<bool>,
+    z64: Vec<u64>,
+}
+
+impl WitnessBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // the low `width` bits of the value
+    fn push_bits(&mut self, value: u64, width: usize, order: BitOrder) -> &mut Self {
+        let bit = |i: usize| (value >> i) & 1 == 1;
+        match order {
+            BitOrder::Lsb => self.gf2.extend((0..width).map(bit)),
+            BitOrder::Msb => self.gf2.extend((0..width).rev().map(bit)),
+        }
+        self
+    }
+
+    pub fn bit(&mut self, bit: bool) -> &mut Self {
+        self.gf2.push(bit);
+        self
+    }
+
+    pub fn bits(&mut self, bits: &[bool]) -> &mut Self {
+        self.gf2.extend_from_slice(bits);
+        self
+    }
+
+    pub fn u8(&mut self, value: u8, order: BitOrder) -> &mut Self {
+        self.push_bits(value.into(), 8, order)
+    }
+
+    pub fn u32(&mut self, value: u32, order: BitOrder) -> &mut Self {
+        self.push_bits(value.into(), 32, order)
+    }
+
+    pub fn u64(&mut self, value: u64, order: BitOrder) -> &mut Self {
+        self.push_bits(value, 64, order)
+    }
+
+    /// Appends the bytes in order, each decomposed into 8 bits.
+    pub fn bytes(&mut self, bytes: &[u8], order: BitOrder) -> &mut Self {
+        for byte in bytes {
+            self.u8(*byte, order);
+        }
+        self
+    }
+
+    /// Appends a z64 witness value.
+    pub fn z64(&mut self, value: u64) -> &mut Self {
+        self.z64.push(value);
+        self
+    }
+
+    pub fn gf2_len(&self) -> usize {
+        self.gf2.len()
+    }
+
+    pub fn z64_len(&self) -> usize {
+        self.z64.len()
+    }
+
+    /// The gf2 and z64 witness, as taken by `Proof::new`.
+    pub fn build(self) -> (Arc<Vec<bool>>, Arc<Vec<u64>>) {
+        (Arc::new(self.gf2), Arc::new(self.z64))
+    }
+}
+
+/// Checks, with one pass over the program, that the witness holds exactly one value for every
+/// `Input` operation of each domain and that every wire (public input and output) is within
+/// the wire counts (z64, gf2), as extended by `SizeHint` operations
+/// (the bounds are checked by `WireBounds`, like in `passes::validate`).
+///
+/// Run by the prover before proving: otherwise a short witness panics deep in the proving pass,
+/// a long witness is silently ignored and a wire out of range panics.
+pub(crate) fn check_witness<S: ProgramSource + ?Sized>(
+    source: &S,
+    public: &[Public],
+    outputs: &[Output],
+    witness_lens: (usize, usize), // (gf2, z64)
+    wire_counts: (usize, usize),
+) -> Result<()> {
+    let mut bounds = WireBounds::new(wire_counts);
+    let (mut gf2_inputs, mut z64_inputs) = (0, 0);
+    check_public(public, wire_counts)?;
+    for (idx, op) in source.rewind()?.enumerate() {
+        let op = op?;
+        bounds.op(&|| format!("instruction {}", idx), &op)?;
+        match op {
+            CombineOperation::GF2(Operation::Input(_)) => gf2_inputs += 1,
+            CombineOperation::Z64(Operation::Input(_)) => z64_inputs += 1,
+            _ => (),
+        }
+    }
+    for output in outputs {
+        bounds.output(output)?;
+    }
+
+    for (domain, expected, got) in [
+        ("gf2", gf2_inputs, witness_lens.0),
+        ("z64", z64_inputs, witness_lens.1),
+    ] {
+        if expected != got {
+            return Err(Error::WitnessLength {
+                domain,
+                expected,
+                got,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::largest_wires;
+    use crate::proof::{OutputValue, Proof, ProverConfig};
+
+    #[test]
+    fn test_witness_builder() {
+        let mut builder = WitnessBuilder::new();
+        builder
+            .u8(0x1c, BitOrder::Msb)
+            .u8(0x1c, BitOrder::Lsb)
+            .bytes(b"\x01\x80", BitOrder::Msb)
+            .bit(true)
+            .z64(7);
+        assert_eq!(builder.gf2_len(), 33);
+        assert_eq!(builder.z64_len(), 1);
+
+        let (gf2, z64) = builder.build();
+        let bits: Vec<u8> = gf2.iter().map(|b| *b as u8).collect();
+        assert_eq!(&bits[..8], &[0, 0, 0, 1, 1, 1, 0, 0]);
+        assert_eq!(&bits[8..16], &[0, 0, 1, 1, 1, 0, 0, 0]);
+        assert_eq!(
+            &bits[16..32],
+            &[0, 0, 0, 0, 0, 0, 0, 1, 1, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(bits[32], 1);
+        assert_eq!(*z64, vec![7]);
+
+        // B2A recomposes a u64 appended least significant bit first
+        let value = 0xdead_beef_0123_4567;
+        let mut builder = WitnessBuilder::new();
+        builder.u64(value, BitOrder::Lsb);
+        let (gf2, z64) = builder.build();
+        let mut program: Vec<CombineOperation> = (0..64)
+            .map(|i| CombineOperation::GF2(Operation::Input(i)))
+            .collect();
+        program.push(CombineOperation::B2A(0, 0));
+        let outputs = [Output::z64("value", 0)];
+        let wire_counts = largest_wires(&program);
+        let proof = Proof::new_with_config(
+            Arc::new(program.clone()),
+            &outputs,
+            gf2,
+            z64,
+            wire_counts,
+            &ProverConfig::default(),
+        );
+        let values = proof
+            .verify_with_outputs(Arc::new(program), &outputs, wire_counts)
+            .unwrap();
+        assert_eq!(values["value"], OutputValue::Z64(value));
+    }
+
+    #[test]
+    fn test_check_witness() {
+        let program = vec![
+            CombineOperation::GF2(Operation::Input(0)),
+            CombineOperation::GF2(Operation::Input(1)),
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::GF2(Operation::Mul(2, 0, 1)),
+        ];
+        let outputs = [Output::gf2("and", 2)];
+        let check =
+            |lens, wire_counts| check_witness(program.as_slice(), &[], &outputs, lens, wire_counts);
+        check((2, 1), (1, 3)).unwrap();
+
+        // too short and too long
+        assert!(matches!(
+            check((1, 1), (1, 3)),
+            Err(Error::WitnessLength {
+                domain: "gf2",
+                expected: 2,
+                got: 1
+            })
+        ));
+        assert_eq!(
+            check((2, 2), (1, 3)).unwrap_err().to_string(),
+            "the z64 witness has 2 values, but the program has 1 z64 inputs"
+        );
+
+        // wires beyond the wire counts
+        assert_eq!(
+            check((2, 1), (1, 2)).unwrap_err().to_string(),
+            "instruction 3: gf2 wire 2 out of range (2 wires)"
+        );
+        assert!(matches!(
+            check((2, 1), (0, 3)),
+            Err(Error::WireRange {
+                domain: "z64",
+                wire: 0,
+                ..
+            })
+        ));
+        let sized: Vec<_> = [CombineOperation::SizeHint(1, 3)]
+            .iter()
+            .chain(program.iter())
+            .cloned()
+            .collect();
+        check_witness(sized.as_slice(), &[], &outputs, (2, 1), (0, 0)).unwrap();
+        assert_eq!(
+            check_witness(
+                program.as_slice(),
+                &[],
+                &[Output::gf2("x", 3)],
+                (2, 1),
+                (1, 3)
+            )
+            .unwrap_err()
+            .to_string(),
+            "output 'x': gf2 wire 3 out of range (3 wires)"
+        );
+
+        // the prover reports the error
+        let proof = Proof::from_source(
+            program.as_slice(),
+            &outputs,
+            Arc::new(vec![true]),
+            Arc::new(vec![1]),
+            (1, 3),
+            &ProverConfig::default(),
+        );
+        assert!(matches!(proof, Err(Error::WitnessLength { .. })));
+    }
+}
@@ -0,0 +1,54 @@
+//! Python extension module `reverie` (feature `python`, e.g. built with maturin).
+//!
+//! Programs are passed in the compact binary format (see `parsers::write_program`),
+//! proofs as written by `Proof::serialize`:
+//!
+//! ```python
+//! import reverie
+//! proof = reverie.prove(program_bytes, witness_bits, witness_z64=[])
+//! reverie.verify(program_bytes, proof)  # raises ValueError if the proof is rejected
+//! ```
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+
+use crate::proof::bytes;
+use crate::CombineOperation;
+
+fn value_error(e: impl ToString) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn decode_program(bytes: &[u8]) -> PyResult<Vec<CombineOperation>> {
+    bytes::decode_program(bytes).map_err(|e| value_error(format!("invalid program: {}", e)))
+}
+
+/// Proves a program for the witness, returns the serialized proof.
+#[pyfunction]
+#[pyo3(signature = (program, witness_bits, witness_z64 = vec![]))]
+fn prove<'py>(
+    py: Python<'py>,
+    program: &[u8],
+    witness_bits: Vec<bool>,
+    witness_z64: Vec<u64>,
+) -> PyResult<&'py PyBytes> {
+    let program = decode_program(program)?;
+    // proving takes a while: let other Python threads run
+    let proof = py.allow_threads(|| bytes::prove(&program, witness_bits, witness_z64));
+    Ok(PyBytes::new(py, &proof.map_err(value_error)?))
+}
+
+/// Verifies a serialized proof of a program, raises `ValueError` if it is rejected.
+#[pyfunction]
+fn verify(py: Python<'_>, program: &[u8], proof: &[u8]) -> PyResult<()> {
+    let program = decode_program(program)?;
+    py.allow_threads(|| bytes::verify(&program, proof))
+        .map_err(value_error)
+}
+
+#[pymodule]
+fn reverie(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(prove, m)?)?;
+    m.add_function(wrap_pyfunction!(verify, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,166 @@
+//! Structured programs: operations interleaved with (no-op) region markers.
+//!
+//! Markers are stripped before proving; the resulting regions refer to instruction
+//! indices of the stripped program and are used to label profiling reports and errors.
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+use crate::CombineOperation;
+
+/// An instruction of a structured program.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Instruction {
+    Op(CombineOperation),
+    Begin(String),
+    End(String),
+}
+
+impl From<CombineOperation> for Instruction {
+    fn from(op: CombineOperation) -> Self {
+        Instruction::Op(op)
+    }
+}
+
+/// A labelled range of instructions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub label: String,
+    pub range: Range<usize>,
+}
+
+fn regions_error(reason: String) -> Error {
+    Error::Regions { reason }
+}
+
+/// Removes the region markers from a structured program.
+///
+/// Regions may be nested but must be properly closed: every `End` must match the label
+/// of the innermost open region. Regions are returned in the order they begin.
+pub fn strip(program: &[Instruction]) -> Result<(Vec<CombineOperation>, Vec<Region>)> {
+    let mut ops = Vec::with_capacity(program.len());
+    let mut regions: Vec<Region> = vec![];
+    let mut open: Vec<usize> = vec![];
+
+    for (idx, ins) in program.iter().enumerate() {
+        match ins {
+            Instruction::Op(op) => ops.push(*op),
+            Instruction::Begin(label) => {
+                open.push(regions.len());
+                regions.push(Region {
+                    label: label.clone(),
+                    range: ops.len()..ops.len(),
+                });
+            }
+            Instruction::End(label) => {
+                let region = match open.pop() {
+                    Some(region) => &mut regions[region],
+                    None => {
+                        return Err(regions_error(format!(
+                            "marker {}: end of region '{}' which is not open",
+                            idx, label
+                        )))
+                    }
+                };
+                if &region.label != label {
+                    return Err(regions_error(format!(
+                        "marker {}: end of region '{}' inside region '{}'",
+                        idx, label, region.label
+                    )));
+                }
+                region.range.end = ops.len();
+            }
+        }
+    }
+
+    if let Some(region) = open.pop() {
+        return Err(regions_error(format!(
+            "region '{}' is not closed",
+            regions[region].label
+        )));
+    }
+    Ok((ops, regions))
+}
+
+/// Returns the innermost region containing the instruction.
+pub fn innermost(regions: &[Region], idx: usize) -> Option<&Region> {
+    regions
+        .iter()
+        .filter(|region| region.range.contains(&idx))
+        .min_by_key(|region| region.range.len())
+}
+
+/// Describes the location of an instruction, e.g. "instruction 12 inside region 'round_3'".
+pub fn locate(regions: &[Region], idx: usize) -> String {
+    match innermost(regions, idx) {
+        Some(region) => format!("instruction {} inside region '{}'", idx, region.label),
+        None => format!("instruction {}", idx),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Operation;
+
+    fn begin(label: &str) -> Instruction {
+        Instruction::Begin(label.to_string())
+    }
+
+    fn end(label: &str) -> Instruction {
+        Instruction::End(label.to_string())
+    }
+
+    fn input(dst: usize) -> Instruction {
+        CombineOperation::GF2(Operation::Input(dst)).into()
+    }
+
+    #[test]
+    fn test_strip_regions() {
+        let program = vec![
+            input(0),
+            begin("outer"),
+            input(1),
+            begin("inner"),
+            input(2),
+            input(3),
+            end("inner"),
+            input(4),
+            end("outer"),
+            input(5),
+        ];
+        let (ops, regions) = strip(&program).unwrap();
+        assert_eq!(ops.len(), 6);
+        assert_eq!(
+            regions,
+            vec![
+                Region {
+                    label: "outer".to_string(),
+                    range: 1..5
+                },
+                Region {
+                    label: "inner".to_string(),
+                    range: 2..4
+                },
+            ]
+        );
+        assert_eq!(locate(&regions, 0), "instruction 0");
+        assert_eq!(locate(&regions, 1), "instruction 1 inside region 'outer'");
+        assert_eq!(locate(&regions, 3), "instruction 3 inside region 'inner'");
+        assert_eq!(locate(&regions, 5), "instruction 5");
+    }
+
+    #[test]
+    fn test_strip_unbalanced() {
+        assert!(strip(&[begin("a"), input(0)]).is_err());
+        assert!(strip(&[input(0), end("a")]).is_err());
+        assert_eq!(
+            strip(&[begin("a"), begin("b"), end("a"), end("b")])
+                .unwrap_err()
+                .to_string(),
+            "invalid region markers: marker 2: end of region 'a' inside region 'b'"
+        );
+    }
+}
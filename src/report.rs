@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use std::time::Instant;
+
+use reverie::proof::{OutputValue, Outputs};
+use serde::Serialize;
+
+/// Result of proving/verifying, printed as text or (with `--json`) as a JSON object.
+#[derive(Debug, Default, Serialize)]
+pub struct Report {
+    #[serde(skip)]
+    pub json: bool,
+    pub success: bool,
+    pub error: Option<String>,
+    pub timings_ms: BTreeMap<&'static str, f64>,
+    pub proof_bytes: Option<u64>,
+    pub outputs: BTreeMap<String, u64>,
+}
+
+impl Report {
+    pub fn new(json: bool) -> Self {
+        Report {
+            json,
+            ..Self::default()
+        }
+    }
+
+    /// Progress message (not printed in JSON mode, which keeps stdout parseable).
+    pub fn status(&self, msg: &str) {
+        if !self.json {
+            println!("{}", msg);
+        }
+    }
+
+    /// Runs the step and records its wall-clock time.
+    pub fn time<T>(&mut self, step: &'static str, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let res = f();
+        self.timings_ms
+            .insert(step, start.elapsed().as_secs_f64() * 1000.0);
+        res
+    }
+
+    pub fn outputs(&mut self, values: &Outputs) {
+        for (label, value) in values.iter() {
+            let value = match value {
+                OutputValue::GF2(bit) => *bit as u64,
+                OutputValue::Z64(value) => *value,
+            };
+            self.status(&format!("{} = {}", label, value));
+            self.outputs.insert(label.clone(), value);
+        }
+    }
+
+    /// Records the result of the operation.
+    pub fn record(&mut self, res: io::Result<Result<(), String>>) {
+        let output = res.unwrap_or_else(|e| Err(e.to_string()));
+        self.success = output.is_ok();
+        self.error = output.err();
+    }
+
+    /// Prints the result of the operation and exits with a non-zero status if it failed.
+    pub fn finish(mut self, res: io::Result<Result<(), String>>) {
+        match res {
+            Err(e) if !self.json => {
+                eprintln!("Invalid proof: {}", e);
+                exit(-1)
+            }
+            Ok(ref output) if !self.json => println!("{:?}", output),
+            _ => (),
+        }
+        self.record(res);
+        if self.json {
+            println!("{}", serde_json::to_string(&self).unwrap());
+        }
+        if !self.success {
+            exit(-1)
+        }
+    }
+}
+
+/// Prints the results of verifying several proofs (one line or JSON object per proof)
+/// and exits with a non-zero status if any failed.
+pub fn finish_batch(proof_paths: &[&PathBuf], reports: Vec<Report>, json: bool) {
+    #[derive(Serialize)]
+    struct Entry<'a> {
+        proof: &'a Path,
+        #[serde(flatten)]
+        report: &'a Report,
+    }
+
+    let passed = reports.iter().filter(|report| report.success).count();
+    if json {
+        let entries: Vec<Entry> = proof_paths
+            .iter()
+            .zip(reports.iter())
+            .map(|(proof, report)| Entry { proof, report })
+            .collect();
+        println!("{}", serde_json::to_string(&entries).unwrap());
+    } else {
+        for (proof, report) in proof_paths.iter().zip(reports.iter()) {
+            match &report.error {
+                None => println!("{}: Ok", proof.display()),
+                Some(e) => println!("{}: {}", proof.display(), e),
+            }
+        }
+        println!("{} of {} proofs verified", passed, reports.len());
+    }
+    if passed < reports.len() {
+        exit(-1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_json() {
+        let mut report = Report::new(true);
+        report.proof_bytes = Some(100);
+        let mut values = Outputs::new();
+        values.insert("sum".to_string(), OutputValue::Z64(12));
+        values.insert("carry".to_string(), OutputValue::GF2(true));
+        report.outputs(&values);
+        report.time("prove", || ());
+        report.success = true;
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["success"], true);
+        assert_eq!(json["error"], serde_json::Value::Null);
+        assert_eq!(json["proof_bytes"], 100);
+        assert_eq!(json["outputs"]["sum"], 12);
+        assert_eq!(json["outputs"]["carry"], 1);
+        assert!(json["timings_ms"]["prove"].is_number());
+        assert!(json.get("json").is_none());
+    }
+}
@@ -0,0 +1,365 @@
+//! HTTP service proving and verifying programs for other processes (feature `service`).
+//!
+//! Endpoints (request bodies are bincode encoded):
+//!
+//! - `POST /prove` with a `ProveRequest`: starts proving in the background and responds
+//!   `202` with the id of the job. The id is the key of the request in the `ProofCache`:
+//!   sending the same request again (e.g. after a restart of the service) resumes the job
+//!   instead of proving again.
+//! - `GET /jobs/{id}`: `202` while the job is running, `200` with the proof (as written by
+//!   `Proof::serialize`, streamed from the cache), `422` with the error if proving failed
+//!   and `404` for an unknown job.
+//! - `DELETE /jobs/{id}`: cancels a running job (`204`), which then fails.
+//! - `POST /verify` with a `VerifyRequest`: `200` with the output values (JSON) of a valid
+//!   proof, `422` with the error otherwise.
+//!
+//! Request bodies larger than the limit of the service (see `Service::with_limit`) are
+//! rejected with `400`.
+use std::any::Any;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read};
+use std::net::ToSocketAddrs;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+use tiny_http::{Method, Request, Response, ResponseBox, Server};
+use tracing::warn;
+
+use crate::crypto::hash::Hash;
+use crate::proof::{CancelToken, Output, Proof, ProofCache, ProverConfig};
+use crate::{largest_wires, CombineOperation};
+
+/// Body of `POST /prove`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProveRequest {
+    pub program: Vec<CombineOperation>,
+    pub outputs: Vec<Output>,
+    pub wit_gf2: Vec<bool>,
+    pub wit_z64: Vec<u64>,
+}
+
+/// Body of `POST /verify`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyRequest {
+    pub program: Vec<CombineOperation>,
+    pub outputs: Vec<Output>,
+    /// The proof, as written by `Proof::serialize`.
+    pub proof: Vec<u8>,
+}
+
+/// The default limit on the size of request bodies (1 GiB).
+pub const DEFAULT_LIMIT: u64 = 1 << 30;
+
+// jobs of this process which have not (successfully) written a proof to the cache
+enum Job {
+    Running(CancelToken),
+    Failed(String),
+}
+
+// a response, independent of the HTTP server
+#[derive(Debug)]
+enum Reply {
+    Text(u16, String),
+    Proof(File),
+}
+
+/// Proves and verifies programs over HTTP, keeping the proofs in a `ProofCache`.
+pub struct Service {
+    cache: ProofCache,
+    config: ProverConfig,
+    limit: u64,
+    jobs: Mutex<HashMap<String, Job>>,
+}
+
+impl Service {
+    /// A service keeping the proofs in `dir`, see `ProofCache::new` for the salt.
+    pub fn new<P: Into<PathBuf>>(dir: P, salt: &[u8], config: ProverConfig) -> io::Result<Self> {
+        Ok(Service {
+            cache: ProofCache::new(dir, salt)?,
+            config,
+            limit: DEFAULT_LIMIT,
+            jobs: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Rejects request bodies (and the proofs in them) larger than `limit` bytes,
+    /// instead of `DEFAULT_LIMIT`.
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Serves requests on `addr` (each in its own thread), never returns on success.
+    pub fn serve<A: ToSocketAddrs>(self, addr: A) -> io::Result<()> {
+        let server = Server::http(addr).map_err(|e| io::Error::other(e.to_string()))?;
+        let service = Arc::new(self);
+        for request in server.incoming_requests() {
+            let service = service.clone();
+            thread::spawn(move || service.respond(request));
+        }
+        Ok(())
+    }
+
+    fn respond(self: &Arc<Self>, mut request: Request) {
+        let method = request.method().clone();
+        let url = request.url().to_string();
+        let reply = self
+            .route(&method, &url, request.as_reader())
+            .unwrap_or_else(|e| Reply::Text(500, e.to_string()));
+        let response: ResponseBox = match reply {
+            Reply::Text(status, body) => {
+                Response::from_string(body).with_status_code(status).boxed()
+            }
+            Reply::Proof(file) => Response::from_file(file).boxed(),
+        };
+        if let Err(e) = request.respond(response) {
+            warn!("cannot respond to {} {}: {}", method, url, e);
+        }
+    }
+
+    fn route(self: &Arc<Self>, method: &Method, url: &str, body: impl Read) -> io::Result<Reply> {
+        // the encoding of `bincode::serialize`, but bounded: the body is untrusted
+        let decode = bincode::options()
+            .with_fixint_encoding()
+            .allow_trailing_bytes()
+            .with_limit(self.limit);
+        match (method, url) {
+            (Method::Post, "/prove") => match decode.deserialize_from(body) {
+                Ok(request) => Ok(self.prove(request)),
+                Err(e) => Ok(Reply::Text(400, format!("malformed request: {}", e))),
+            },
+            (Method::Post, "/verify") => match decode.deserialize_from(body) {
+                Ok(request) => Ok(self.verify(request)),
+                Err(e) => Ok(Reply::Text(400, format!("malformed request: {}", e))),
+            },
+            (Method::Get, url) if url.starts_with("/jobs/") => self.job(&url["/jobs/".len()..]),
+            (Method::Delete, url) if url.starts_with("/jobs/") => {
+                Ok(self.cancel(&url["/jobs/".len()..]))
+            }
+            _ => Ok(Reply::Text(404, "not found".to_string())),
+        }
+    }
+
+    fn prove(self: &Arc<Self>, request: ProveRequest) -> Reply {
+        let wire_counts = largest_wires(&request.program);
+        let key = self.cache.key(
+            &request.program,
+            &request.outputs,
+            &request.wit_gf2,
+            &request.wit_z64,
+            wire_counts,
+        );
+        let id = key.to_hex().to_string();
+
+        // a finished or running job is not started again
+        let mut jobs = self.jobs.lock().unwrap();
+        if !self.cache.path(&key).exists() && !matches!(jobs.get(&id), Some(Job::Running(_))) {
+            let cancel = CancelToken::new();
+            jobs.insert(id.clone(), Job::Running(cancel.clone()));
+            let config = ProverConfig {
+                cancel: Some(cancel),
+                ..self.config.clone()
+            };
+            let service = self.clone();
+            let job = id.clone();
+            thread::spawn(move || {
+                // a panic must not leave the job running forever
+                let proof = panic::catch_unwind(AssertUnwindSafe(|| {
+                    service.cache.get_or_prove(
+                        Arc::new(request.program),
+                        &request.outputs,
+                        Arc::new(request.wit_gf2),
+                        Arc::new(request.wit_z64),
+                        wire_counts,
+                        &config,
+                    )
+                }));
+                let mut jobs = service.jobs.lock().unwrap();
+                match proof {
+                    Ok(Ok(_)) => jobs.remove(&job),
+                    Ok(Err(e)) => jobs.insert(job, Job::Failed(e.to_string())),
+                    Err(panic) => jobs.insert(job, Job::Failed(panic_message(panic))),
+                };
+            });
+        }
+        Reply::Text(202, id)
+    }
+
+    fn job(&self, id: &str) -> io::Result<Reply> {
+        // ids are hex digests: anything else is not a path in the cache
+        let key = match Hash::from_hex(id) {
+            Ok(key) => key,
+            Err(_) => return Ok(Reply::Text(404, "unknown job".to_string())),
+        };
+        match self.jobs.lock().unwrap().get(id) {
+            Some(Job::Running(_)) => return Ok(Reply::Text(202, "running".to_string())),
+            Some(Job::Failed(e)) => return Ok(Reply::Text(422, e.clone())),
+            None => (),
+        }
+        match File::open(self.cache.path(&key)) {
+            Ok(file) => Ok(Reply::Proof(file)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                Ok(Reply::Text(404, "unknown job".to_string()))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn cancel(&self, id: &str) -> Reply {
+        match self.jobs.lock().unwrap().get(id) {
+            Some(Job::Running(cancel)) => {
+                cancel.cancel();
+                Reply::Text(204, String::new())
+            }
+            _ => Reply::Text(404, "no running job".to_string()),
+        }
+    }
+
+    fn verify(&self, request: VerifyRequest) -> Reply {
+        let proof = match Proof::deserialize_with_limit(request.proof.as_slice(), self.limit) {
+            Ok(proof) => proof,
+            Err(e) => return Reply::Text(422, e.to_string()),
+        };
+        let wire_counts = largest_wires(&request.program);
+        match proof.verify_source(request.program.as_slice(), &request.outputs, wire_counts) {
+            Ok(values) => Reply::Text(200, serde_json::to_string(&values).unwrap()),
+            Err(e) => Reply::Text(422, e.to_string()),
+        }
+    }
+}
+
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    let reason = match panic.downcast_ref::<&str>() {
+        Some(reason) => reason.to_string(),
+        None => match panic.downcast_ref::<String>() {
+            Some(reason) => reason.clone(),
+            None => "unknown reason".to_string(),
+        },
+    };
+    format!("the prover panicked: {}", reason)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::Operation;
+
+    #[test]
+    fn test_service() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = Arc::new(Service::new(dir.path(), b"salt", ProverConfig::default()).unwrap());
+        let program = vec![
+            CombineOperation::Z64(Operation::Input(0)),
+            CombineOperation::Z64(Operation::Mul(1, 0, 0)),
+        ];
+        let outputs = vec![Output::z64("y", 1)];
+        let request = |method: Method, url: &str, body: Vec<u8>| {
+            service.route(&method, url, body.as_slice()).unwrap()
+        };
+
+        let prove = bincode::serialize(&ProveRequest {
+            program: program.clone(),
+            outputs: outputs.clone(),
+            wit_gf2: vec![],
+            wit_z64: vec![3],
+        })
+        .unwrap();
+        let id = match request(Method::Post, "/prove", prove.clone()) {
+            Reply::Text(202, id) => id,
+            reply => panic!("unexpected reply {:?}", reply),
+        };
+
+        // poll the job until the proof is ready
+        let mut file = loop {
+            match request(Method::Get, &format!("/jobs/{}", id), vec![]) {
+                Reply::Text(202, _) => thread::sleep(Duration::from_millis(10)),
+                Reply::Proof(file) => break file,
+                reply => panic!("unexpected reply {:?}", reply),
+            }
+        };
+        let mut proof = vec![];
+        file.read_to_end(&mut proof).unwrap();
+
+        // the same request resumes the finished job
+        assert!(
+            matches!(request(Method::Post, "/prove", prove), Reply::Text(202, same) if same == id)
+        );
+
+        let verify = |proof: Vec<u8>| {
+            let body = VerifyRequest {
+                program: program.clone(),
+                outputs: outputs.clone(),
+                proof,
+            };
+            request(Method::Post, "/verify", bincode::serialize(&body).unwrap())
+        };
+        assert!(matches!(verify(proof.clone()), Reply::Text(200, values) if values.contains("9")));
+        proof.truncate(proof.len() / 2);
+        assert!(matches!(verify(proof), Reply::Text(422, _)));
+
+        // no paths outside the cache, nor unknown jobs
+        assert!(matches!(
+            request(Method::Get, "/jobs/../x", vec![]),
+            Reply::Text(404, _)
+        ));
+        let unknown = format!("/jobs/{}", "0".repeat(64));
+        assert!(matches!(
+            request(Method::Get, &unknown, vec![]),
+            Reply::Text(404, _)
+        ));
+        assert!(matches!(
+            request(Method::Delete, &unknown, vec![]),
+            Reply::Text(404, _)
+        ));
+    }
+
+    #[test]
+    fn test_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let service = Arc::new(
+            Service::new(dir.path(), b"salt", ProverConfig::default())
+                .unwrap()
+                .with_limit(64),
+        );
+        let request = ProveRequest {
+            program: vec![CombineOperation::Z64(Operation::Input(0))],
+            outputs: vec![],
+            wit_gf2: vec![],
+            wit_z64: vec![3],
+        };
+        let body = bincode::serialize(&request).unwrap();
+        assert!(matches!(
+            service
+                .route(&Method::Post, "/prove", body.as_slice())
+                .unwrap(),
+            Reply::Text(202, _)
+        ));
+
+        // a body claiming a huge witness is rejected before allocating it
+        let mut body = bincode::serialize(&ProveRequest {
+            wit_z64: vec![],
+            ..request
+        })
+        .unwrap();
+        let len = body.len();
+        body[len - 8..].copy_from_slice(&u64::MAX.to_le_bytes());
+        body.extend_from_slice(&[0; 64]);
+        assert!(matches!(
+            service.route(&Method::Post, "/prove", body.as_slice()).unwrap(),
+            Reply::Text(400, e) if e.contains("limit")
+        ));
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let panic = panic::catch_unwind(|| panic!("at the disco")).unwrap_err();
+        assert_eq!(panic_message(panic), "the prover panicked: at the disco");
+    }
+}
@@ -1,4 +1,5 @@
 mod prover;
+mod spill;
 mod verifier;
 
 pub use prover::ProverTranscript;
@@ -55,7 +56,7 @@ pub trait Transcript<D: Domain> {
     ///
     /// # Proving
     ///
-    /// Check if zero: if not the witness was invalid and the prover aborts
+    /// Nop: the witness is checked by plain evaluation before proving.
     ///
     /// # Verifying (Online)
     ///
@@ -1,14 +1,14 @@
 use std::any::type_name;
 use std::convert::TryFrom;
+use std::io;
 use std::mem;
 
-use num_traits::Zero;
-
+use super::spill::InputLog;
 use super::*;
 use crate::algebra::{Hashable, Pack, PackSelected};
 use crate::crypto::hash::PackedHasher;
 use crate::generator::ShareGen;
-use crate::proof::{OpenOnline, OpenPreprocessing};
+use crate::proof::{OpenOnline, OpenPreprocessing, ProverConfig};
 
 pub struct ProverTranscript<D: Domain, I: Iterator<Item = D::Recon>> {
     // original un-expanded seeds
@@ -28,13 +28,21 @@ pub struct ProverTranscript<D: Domain, I: Iterator<Item = D::Recon>> {
     // recorded corrections/reconstructions/masked inputs
     reconstructions: Vec<D::Share>,
     corrections: Vec<D::Recon>,
-    inputs: Vec<D::Recon>,
+    inputs: InputLog<D::Recon>,
 }
 
 impl<D: Domain, I: Iterator<Item = D::Recon>> ProverTranscript<D, I> {
     pub fn new(
         witness: I,           // iterator over
         seeds: [Key; PACKED], // seeds for each packed repetition
+    ) -> Self {
+        Self::new_with_config(witness, seeds, &ProverConfig::default())
+    }
+
+    pub fn new_with_config(
+        witness: I,            // iterator over
+        seeds: [Key; PACKED],  // seeds for each packed repetition
+        config: &ProverConfig, // memory/spill settings
     ) -> Self {
         Self {
             seeds,
@@ -44,20 +52,28 @@ impl<D: Domain, I: Iterator<Item = D::Recon>> ProverTranscript<D, I> {
             hash_preprocess: PackedHasher::new(),
             reconstructions: vec![],
             corrections: vec![],
-            inputs: vec![],
+            inputs: InputLog::new(config),
         }
     }
 
+    /// Number of elements recorded so far (reconstructions, corrections and masked inputs),
+    /// each of which is included in the opening of an online repetition.
+    pub(crate) fn recorded(&self) -> usize {
+        self.reconstructions.len() + self.corrections.len() + self.inputs.len()
+    }
+
     /// Extracts proofs from transcript
     ///
     /// # Arguments
     ///
     /// - 'players': The players to omit from the online execution.
     ///              If players[i] == PLAYERS, the preprocessing is opened instead.
+    ///
+    /// Fails if the masked inputs spilled to disk cannot be read back.
     pub(crate) fn extract(
         self,                     // consumes the transcript
         players: [usize; PACKED], // the online players to omit.
-    ) -> (Vec<OpenOnline>, Vec<OpenPreprocessing>) {
+    ) -> io::Result<(Vec<OpenOnline>, Vec<OpenPreprocessing>)> {
         let mut dst_recon: [Vec<u8>; PACKED] = [
             vec![],
             vec![],
@@ -103,12 +119,11 @@ impl<D: Domain, I: Iterator<Item = D::Recon>> ProverTranscript<D, I> {
             <&[bool; PACKED]>::try_from(&selected[..]).unwrap(),
         );
 
-        // pack masked inputs
-        D::Recon::pack(
+        // pack masked inputs (reading back any part spilled to disk)
+        self.inputs.pack(
             &mut dst_input,
-            &self.inputs[..],
             <&[bool; PACKED]>::try_from(&selected[..]).unwrap(),
-        );
+        )?;
 
         // open
         let mut open_preprocessing: Vec<OpenPreprocessing> = vec![];
@@ -171,7 +186,7 @@ impl<D: Domain, I: Iterator<Item = D::Recon>> ProverTranscript<D, I> {
             }
         }
 
-        (open_online, open_preprocessing)
+        Ok((open_online, open_preprocessing))
     }
 }
 
@@ -218,13 +233,9 @@ impl<D: Domain, I: Iterator<Item = D::Recon>> Transcript<D> for ProverTranscript
         corr
     }
 
-    fn zero_check(&mut self, recon: D::Recon) {
-        // TODO: create a useful trace for the user
-        assert!(
-            recon.is_zero(),
-            "witness is invalid!. Wire has value {:?}, expected zero.",
-            recon
-        );
+    fn zero_check(&mut self, _recon: D::Recon) {
+        // the witness is evaluated in the clear before proving (see `proof::evaluate`),
+        // an unsatisfied assertion is left for the verifier to reject
     }
 
     fn new_mask(&mut self) -> D::Share {
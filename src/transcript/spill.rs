@@ -0,0 +1,182 @@
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Seek, SeekFrom};
+use std::mem;
+use std::path::{Path, PathBuf};
+
+use crate::algebra::Recon;
+use crate::proof::ProverConfig;
+use crate::PACKED;
+
+// number of elements read back at a time (must be a multiple of 8 to keep GF2 packing aligned)
+const SPILL_BLOCK: usize = 1 << 16;
+
+#[cfg(not(target_arch = "wasm32"))]
+fn spill_file(dir: &Path) -> io::Result<File> {
+    tempfile::tempfile_in(dir)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn spill_file(_dir: &Path) -> io::Result<File> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Append-only log of masked inputs which moves to disk once it grows beyond a threshold.
+pub(crate) struct InputLog<R: Recon> {
+    memory: Vec<R>,
+    threshold: Option<(usize, PathBuf)>,
+    spilled: usize,
+    file: Option<BufWriter<File>>,
+    error: Option<io::Error>,
+}
+
+impl<R: Recon> InputLog<R> {
+    pub fn new(config: &ProverConfig) -> Self {
+        InputLog {
+            memory: vec![],
+            // there is no file system to spill to on wasm32
+            threshold: config
+                .input_spill_threshold
+                .filter(|_| cfg!(not(target_arch = "wasm32")))
+                .map(|threshold| (threshold, config.spill_dir())),
+            spilled: 0,
+            file: None,
+            error: None,
+        }
+    }
+
+    pub fn push(&mut self, elem: R) {
+        self.memory.push(elem);
+        if let Some((threshold, _)) = self.threshold {
+            if self.memory.len() > threshold {
+                // keep the rest in memory, the error is returned by pack
+                if let Err(e) = self.spill() {
+                    self.threshold = None;
+                    self.error = Some(e);
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.spilled + self.memory.len()
+    }
+
+    fn spill(&mut self) -> io::Result<()> {
+        if self.file.is_none() {
+            let (_, dir) = self.threshold.as_ref().unwrap();
+            self.file = Some(BufWriter::new(spill_file(dir)?));
+        }
+        let file = self.file.as_mut().unwrap();
+        let elems = mem::take(&mut self.memory);
+        for elem in elems.iter() {
+            elem.serialize(file)?;
+        }
+        self.spilled += elems.len();
+        Ok(())
+    }
+
+    /// Pack the selected repetitions of every logged element (in order) into dst.
+    /// Fails if the log could not be written to or read back from disk.
+    pub fn pack(self, dst: &mut [Vec<u8>; PACKED], selected: &[bool; PACKED]) -> io::Result<()> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+        let file = match self.file {
+            None => {
+                R::pack(dst, &self.memory[..], selected);
+                return Ok(());
+            }
+            Some(file) => file,
+        };
+
+        // nothing to read back if no repetition is opened
+        if selected.iter().all(|v| !v) {
+            return Ok(());
+        }
+
+        let mut file = file.into_inner().map_err(|e| e.into_error())?;
+        file.seek(SeekFrom::Start(0))?;
+        let mut reader = BufReader::new(file);
+
+        // every call to pack ends with the same trailer (the zero padding of GF2),
+        // which must only follow the last block
+        let mut trailer: [Vec<u8>; PACKED] = Default::default();
+        R::pack(&mut trailer, &[], selected);
+
+        // all but the last block are multiples of 8 elements,
+        // the last block is joined with the in-memory tail
+        let mut block: Vec<R> = Vec::with_capacity(SPILL_BLOCK);
+        let mut remaining = self.spilled;
+        loop {
+            let n = remaining.min(SPILL_BLOCK);
+            block.clear();
+            for _ in 0..n {
+                let mut elem = R::default();
+                elem.deserialize(&mut reader)?;
+                block.push(elem);
+            }
+            remaining -= n;
+            if remaining == 0 {
+                break;
+            }
+            R::pack(dst, &block[..], selected);
+            for (dst, trailer) in dst.iter_mut().zip(trailer.iter()) {
+                dst.truncate(dst.len() - trailer.len());
+            }
+        }
+        block.extend(self.memory);
+        R::pack(dst, &block[..], selected);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+
+    use super::*;
+    use crate::algebra::{gf2, z64, Domain, Pack};
+
+    fn spill_matches_memory<D: Domain>() {
+        // the last case reads back two full blocks and a partial one
+        for (num, threshold) in [
+            (0, 1),
+            (1, 1),
+            (17, 3),
+            (100, 8),
+            (1000, 999),
+            (65, 64),
+            (2 * SPILL_BLOCK + 1003, 1000),
+        ] {
+            let recons = D::random_recon(&mut OsRng, num);
+            let selected = [true, false, true, true, false, false, true, false];
+
+            let mut expected: [Vec<u8>; PACKED] = Default::default();
+            D::Recon::pack(&mut expected, &recons[..], &selected);
+
+            let config = ProverConfig {
+                input_spill_threshold: Some(threshold),
+                spill_dir: None,
+                seed: None,
+                cancel: None,
+            };
+            let mut log: InputLog<D::Recon> = InputLog::new(&config);
+            for recon in recons.iter() {
+                log.push(*recon);
+            }
+            let mut actual: [Vec<u8>; PACKED] = Default::default();
+            log.pack(&mut actual, &selected).unwrap();
+            assert_eq!(expected, actual, "num = {}, threshold = {}", num, threshold);
+        }
+    }
+
+    #[test]
+    fn test_spill_gf2() {
+        spill_matches_memory::<gf2::Domain>();
+    }
+
+    #[test]
+    fn test_spill_z64() {
+        spill_matches_memory::<z64::Domain>();
+    }
+}
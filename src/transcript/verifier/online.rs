@@ -117,6 +117,11 @@ impl<D: Domain> VerifierTranscriptOnline<D> {
             okay: true,
         }
     }
+
+    /// False if an assertion failed (in any of the packed repetitions).
+    pub(crate) fn okay(&self) -> bool {
+        self.okay
+    }
 }
 
 impl<D: Domain> Transcript<D> for VerifierTranscriptOnline<D> {
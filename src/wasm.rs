@@ -0,0 +1,45 @@
+//! JavaScript interface to the prover and verifier (feature `wasm`, for
+//! `wasm32-unknown-unknown`), e.g. for verifying proofs in the browser.
+//!
+//! Build the `cdylib` and generate the bindings with:
+//!
+//! ```text
+//! cargo rustc --lib --release --target wasm32-unknown-unknown --crate-type cdylib \
+//!     --no-default-features --features wasm
+//! wasm-bindgen --target web target/wasm32-unknown-unknown/release/reverie.wasm --out-dir pkg
+//! ```
+//!
+//! Programs are passed in the compact binary format (see `parsers::write_program`),
+//! proofs as written by `Proof::serialize`:
+//!
+//! ```js
+//! const proof = prove(program, witness);  // Uint8Array, one byte (0 or 1) per gf2 input
+//! verify(program, proof);                 // throws if the proof is rejected
+//! ```
+use wasm_bindgen::prelude::*;
+
+use crate::proof::bytes;
+
+/// Proves a program for the witness (one byte, 0 or 1, per gf2 input, and optionally
+/// the z64 inputs), returns the serialized proof.
+#[wasm_bindgen]
+pub fn prove(
+    program: &[u8],
+    witness: &[u8],
+    witness_z64: Option<Vec<u64>>,
+) -> std::result::Result<Vec<u8>, JsError> {
+    let program = bytes::decode_program(program)?;
+    let witness = witness.iter().map(|bit| *bit != 0).collect();
+    Ok(bytes::prove(
+        &program,
+        witness,
+        witness_z64.unwrap_or_default(),
+    )?)
+}
+
+/// Verifies a serialized proof of a program, throws if it is rejected.
+#[wasm_bindgen]
+pub fn verify(program: &[u8], proof: &[u8]) -> std::result::Result<(), JsError> {
+    let program = bytes::decode_program(program)?;
+    Ok(bytes::verify(&program, proof)?)
+}
@@ -1,60 +1,246 @@
-use std::fs::File;
-use std::io::{self, prelude::*, BufReader};
+use std::collections::VecDeque;
+use std::io::{self, prelude::*};
 
 use reverie::algebra::gf2;
 
-use super::Parser;
+use super::{Parser, WitnessReader};
 
+/// Witness given as '0'/'1' characters, typically one bit per line.
+///
+/// Whitespace, blank lines and comments (from '#' to the end of the line) are skipped,
+/// any other character is reported with its line number.
 pub struct WitParser {
-    reader: BufReader<File>,
+    reader: WitnessReader,
+    line: usize,
+    bits: VecDeque<bool>,
 }
 
-impl Parser<bool> for WitParser {
-    fn new(reader: BufReader<File>) -> io::Result<Self> {
-        Ok(WitParser { reader })
-    }
-
-    fn next(&mut self) -> io::Result<Option<bool>> {
-        loop {
-            let mut buf: [u8; 1] = [0];
-            let n = self.reader.read(&mut buf)?;
-            if n == 0 {
+impl WitParser {
+    fn next_bit(&mut self) -> io::Result<Option<bool>> {
+        while self.bits.is_empty() {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
                 return Ok(None);
             }
-            match buf[0] as char {
-                '0' => {
-                    return Ok(Some(false));
+            self.line += 1;
+            let values = line.split('#').next().unwrap();
+            for c in values.chars().filter(|c| !c.is_whitespace()) {
+                match c {
+                    '0' => self.bits.push_back(false),
+                    '1' => self.bits.push_back(true),
+                    _ => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("line {}: invalid witness value '{}'", self.line, c),
+                        ))
+                    }
                 }
-                '1' => {
-                    return Ok(Some(true));
-                }
-                _ => (),
             }
         }
+        Ok(self.bits.pop_front())
+    }
+}
+
+impl Parser<bool> for WitParser {
+    fn new(reader: WitnessReader) -> io::Result<Self> {
+        Ok(WitParser {
+            reader,
+            line: 0,
+            bits: VecDeque::new(),
+        })
+    }
+
+    fn next(&mut self) -> io::Result<Option<bool>> {
+        self.next_bit()
     }
 }
 
 impl Parser<gf2::Recon> for WitParser {
-    fn new(reader: BufReader<File>) -> io::Result<Self> {
-        Ok(WitParser { reader })
+    fn new(reader: WitnessReader) -> io::Result<Self> {
+        <Self as Parser<bool>>::new(reader)
     }
 
     fn next(&mut self) -> io::Result<Option<gf2::Recon>> {
-        loop {
-            let mut buf: [u8; 1] = [0];
-            let n = self.reader.read(&mut buf)?;
-            if n == 0 {
+        Ok(self.next_bit()?.map(Into::into))
+    }
+}
+
+/// Witness given as hex strings, e.g. "0xdeadbeef" (separated by whitespace, `0x` is optional).
+///
+/// Every hex digit is 4 witness bits, most significant bit first:
+/// "0x1c" is the witness 0, 0, 0, 1, 1, 1, 0, 0.
+pub struct HexParser {
+    reader: WitnessReader,
+    line: usize,
+    bits: VecDeque<bool>,
+}
+
+impl HexParser {
+    fn next_bit(&mut self) -> io::Result<Option<bool>> {
+        while self.bits.is_empty() {
+            let mut line = String::new();
+            if self.reader.read_line(&mut line)? == 0 {
                 return Ok(None);
             }
-            match buf[0] as char {
-                '0' => {
-                    return Ok(Some(false.into()));
-                }
-                '1' => {
-                    return Ok(Some(true.into()));
+            self.line += 1;
+            for token in line.split_whitespace() {
+                let digits = token
+                    .strip_prefix("0x")
+                    .or_else(|| token.strip_prefix("0X"))
+                    .unwrap_or(token);
+                for c in digits.chars() {
+                    let digit = c.to_digit(16).ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!("line {}: invalid hex digit '{}'", self.line, c),
+                        )
+                    })?;
+                    for i in (0..4).rev() {
+                        self.bits.push_back((digit >> i) & 1 == 1);
+                    }
                 }
-                _ => (),
             }
         }
+        Ok(self.bits.pop_front())
+    }
+}
+
+impl Parser<bool> for HexParser {
+    fn new(reader: WitnessReader) -> io::Result<Self> {
+        Ok(HexParser {
+            reader,
+            line: 0,
+            bits: VecDeque::new(),
+        })
+    }
+
+    fn next(&mut self) -> io::Result<Option<bool>> {
+        self.next_bit()
+    }
+}
+
+impl Parser<gf2::Recon> for HexParser {
+    fn new(reader: WitnessReader) -> io::Result<Self> {
+        <Self as Parser<bool>>::new(reader)
+    }
+
+    fn next(&mut self) -> io::Result<Option<gf2::Recon>> {
+        Ok(self.next_bit()?.map(Into::into))
+    }
+}
+
+/// Packed binary witness: the number of bits (u64, little-endian) followed by the bits,
+/// 8 per byte with the first bit in the least significant position of the byte.
+pub struct PackedParser {
+    reader: WitnessReader,
+    remaining: u64,
+    byte: u8,
+    used: u8, // bits of `byte` already returned (8: read the next byte)
+}
+
+impl PackedParser {
+    fn next_bit(&mut self) -> io::Result<Option<bool>> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+        if self.used == 8 {
+            let mut buf = [0u8; 1];
+            self.reader
+                .read_exact(&mut buf)
+                .map_err(|e| match e.kind() {
+                    io::ErrorKind::UnexpectedEof => io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("packed witness is missing {} bits", self.remaining),
+                    ),
+                    _ => e,
+                })?;
+            self.byte = buf[0];
+            self.used = 0;
+        }
+        let bit = (self.byte >> self.used) & 1 == 1;
+        self.used += 1;
+        self.remaining -= 1;
+        Ok(Some(bit))
+    }
+}
+
+impl Parser<bool> for PackedParser {
+    fn new(mut reader: WitnessReader) -> io::Result<Self> {
+        let mut len = [0u8; 8];
+        reader.read_exact(&mut len)?;
+        Ok(PackedParser {
+            reader,
+            remaining: u64::from_le_bytes(len),
+            byte: 0,
+            used: 8,
+        })
+    }
+
+    fn next(&mut self) -> io::Result<Option<bool>> {
+        self.next_bit()
+    }
+}
+
+impl Parser<gf2::Recon> for PackedParser {
+    fn new(reader: WitnessReader) -> io::Result<Self> {
+        <Self as Parser<bool>>::new(reader)
+    }
+
+    fn next(&mut self) -> io::Result<Option<gf2::Recon>> {
+        Ok(self.next_bit()?.map(Into::into))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // witnesses can be parsed from memory
+    fn parse<P: Parser<bool>, C: AsRef<[u8]>>(contents: C) -> io::Result<Vec<bool>> {
+        let reader = io::Cursor::new(contents.as_ref().to_vec());
+        let mut parser = P::new(Box::new(reader))?;
+        let mut bits = vec![];
+        while let Some(bit) = parser.next()? {
+            bits.push(bit);
+        }
+        Ok(bits)
+    }
+
+    #[test]
+    fn test_bit_witness() {
+        let contents = "# witness\n0\n\n 1 # comment\r\n10\n";
+        assert_eq!(
+            parse::<WitParser, _>(contents).unwrap(),
+            vec![false, true, true, false]
+        );
+        let err = parse::<WitParser, _>("0\n1\n2\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 3: invalid witness value '2'");
+    }
+
+    #[test]
+    fn test_hex_witness() {
+        let bits: Vec<bool> = [0, 0, 0, 1, 1, 1, 0, 0, 1, 0, 1, 0]
+            .iter()
+            .map(|b| *b == 1)
+            .collect();
+        assert_eq!(parse::<HexParser, _>("0x1c\n\n  A\n").unwrap(), bits);
+        assert_eq!(parse::<HexParser, _>("1C a").unwrap(), bits);
+        assert!(parse::<HexParser, _>("0x1c\n0xg0\n").is_err());
+    }
+
+    #[test]
+    fn test_packed_witness() {
+        // 11 bits: 0b1100_1010, 0b101 (first bit least significant)
+        let mut contents = 11u64.to_le_bytes().to_vec();
+        contents.extend([0b1100_1010, 0b1111_0101]);
+        let bits: Vec<bool> = [0, 1, 0, 1, 0, 0, 1, 1, 1, 0, 1]
+            .iter()
+            .map(|b| *b == 1)
+            .collect();
+        assert_eq!(parse::<PackedParser, _>(&contents).unwrap(), bits);
+
+        // truncated bits and header
+        assert!(parse::<PackedParser, _>(&contents[..9]).is_err());
+        assert!(parse::<PackedParser, _>(&contents[..4]).is_err());
     }
 }